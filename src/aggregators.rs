@@ -6,15 +6,30 @@ use crate::{
 };
 
 mod aggregating_buffer;
+mod aggregator_inputs;
+mod any_value_agg;
+mod array_agg;
 mod avg_agg;
+mod bool_agg;
 mod count_agg;
+mod filtered_agg;
+mod first_last_agg;
 mod max_agg;
 mod min_agg;
+mod skip_nulls_agg;
+mod string_agg;
 mod sum_agg;
+pub use any_value_agg::AnyValueAgg;
+pub use array_agg::ArrayAgg;
 pub use avg_agg::AvgAgg;
+pub use bool_agg::BoolAgg;
 pub use count_agg::CountAgg;
+pub use filtered_agg::FilteredAgg;
+pub use first_last_agg::FirstLastAgg;
 pub use max_agg::MaxAgg;
 pub use min_agg::MinAgg;
+pub use skip_nulls_agg::SkipNullsAgg;
+pub use string_agg::StringAgg;
 pub use sum_agg::SumAgg;
 
 pub trait Aggregator {
@@ -0,0 +1,124 @@
+use std::cmp::Ordering;
+
+use crate::{
+    expressions::{Expression, Literal},
+    interpreter::{booleans::cmp_impl, Interpreter},
+    logical_plans::SortOption,
+    optimizer::{rules::ResolveExprRule, OptimizerContextForExpr},
+    row::Row,
+    tables::RelationSchema,
+    DBResult,
+};
+
+/// AggregatorInputs evaluates the argument and ordering expressions of an aggregator
+/// against inbound rows, e.g. `name` and `id` of `string_agg(name, ',' ORDER BY id)`.
+pub struct AggregatorInputs {
+    args: Vec<Expression>,
+    order_by: Vec<SortOption>,
+}
+
+impl AggregatorInputs {
+    pub fn new(args: Vec<Expression>, order_by: Vec<SortOption>) -> Self {
+        Self { args, order_by }
+    }
+
+    pub fn resolve_expr(&mut self, inbound_schema: &RelationSchema) -> DBResult<()> {
+        let context = OptimizerContextForExpr::new(inbound_schema.clone());
+        let resolve = |expr: &Expression| {
+            expr.transform_bottom_up(&context, &mut ResolveExprRule::resolve_expression)
+                .map(|opt_resolved| opt_resolved.unwrap_or_else(|| expr.clone()))
+        };
+        self.args = self
+            .args
+            .iter()
+            .map(resolve)
+            .collect::<DBResult<Vec<_>>>()?;
+        self.order_by = self
+            .order_by
+            .iter()
+            .map(|option| {
+                resolve(option.expr()).map(|expr| SortOption::new(expr, option.asc()))
+            })
+            .collect::<DBResult<Vec<_>>>()?;
+        Ok(())
+    }
+
+    pub fn eval_arg(&self, index: usize, input_row: &Row) -> DBResult<Literal> {
+        Interpreter::eval(&self.args[index], input_row)
+    }
+
+    pub fn eval_sort_keys(&self, input_row: &Row) -> DBResult<Vec<Literal>> {
+        self.order_by
+            .iter()
+            .map(|option| Interpreter::eval(option.expr(), input_row))
+            .collect()
+    }
+
+    /// compare two lists of sort keys evaluated by `eval_sort_keys`, nulls come last
+    /// in ascending order
+    pub fn cmp_sort_keys(
+        &self,
+        left: &[Literal],
+        right: &[Literal],
+    ) -> DBResult<Ordering> {
+        for ((option, l), r) in self.order_by.iter().zip(left).zip(right) {
+            let ordering = match (l, r) {
+                (Literal::Null, Literal::Null) => Ordering::Equal,
+                (Literal::Null, _) => Ordering::Greater,
+                (_, Literal::Null) => Ordering::Less,
+                (l, r) => cmp_impl(l, r)?,
+            };
+            let ordering = if option.asc() {
+                ordering
+            } else {
+                ordering.reverse()
+            };
+            if ordering.is_ne() {
+                return Ok(ordering);
+            }
+        }
+        Ok(Ordering::Equal)
+    }
+
+    /// sort collected `(value, sort keys)` entries by their sort keys, the sort is
+    /// stable so entries with equal keys keep the input order
+    pub fn sort_entries(&self, entries: &mut [Literal]) -> DBResult<()> {
+        if self.order_by.is_empty() {
+            return Ok(());
+        }
+        let mut error = None;
+        entries.sort_by(|left, right| {
+            match self.cmp_sort_keys(entry_sort_keys(left), entry_sort_keys(right)) {
+                Ok(ordering) => ordering,
+                Err(e) => {
+                    error.get_or_insert(e);
+                    Ordering::Equal
+                }
+            }
+        });
+        error.map_or(Ok(()), Err)
+    }
+
+    /// evaluate an entry to be collected, the entry is a list of the first arg followed
+    /// by sort keys
+    pub fn eval_entry(&self, input_row: &Row) -> DBResult<Literal> {
+        let mut entry = vec![self.eval_arg(0, input_row)?];
+        entry.extend(self.eval_sort_keys(input_row)?);
+        Ok(Literal::List(entry))
+    }
+}
+
+/// the value of an entry evaluated by `AggregatorInputs::eval_entry`
+pub fn entry_value(entry: &Literal) -> &Literal {
+    match entry {
+        Literal::List(values) => values.first().unwrap_or(&Literal::Null),
+        _ => &Literal::Null,
+    }
+}
+
+fn entry_sort_keys(entry: &Literal) -> &[Literal] {
+    match entry {
+        Literal::List(values) if !values.is_empty() => &values[1..],
+        _ => &[],
+    }
+}
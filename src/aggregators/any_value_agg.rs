@@ -0,0 +1,42 @@
+use crate::{
+    expressions::{Expression, Literal},
+    row::Row,
+    tables::RelationSchema,
+    DBResult,
+};
+
+use super::{aggregator_inputs::AggregatorInputs, Aggregator};
+
+/// Pick an arbitrary non-null value, which is the first one seen
+pub struct AnyValueAgg {
+    inputs: AggregatorInputs,
+}
+
+impl AnyValueAgg {
+    pub fn new(arg: &Expression) -> DBResult<Self> {
+        let inputs = AggregatorInputs::new(vec![arg.clone()], vec![]);
+        Ok(Self { inputs })
+    }
+}
+
+impl Aggregator for AnyValueAgg {
+    fn initial_row(&self) -> DBResult<Row<'static>> {
+        Ok(Row::new(vec![Literal::Null]))
+    }
+
+    fn resolve_expr(&mut self, inbound_schema: &RelationSchema) -> DBResult<()> {
+        self.inputs.resolve_expr(inbound_schema)
+    }
+
+    fn process(&self, input_row: &Row, result_row: &mut Row) -> DBResult<()> {
+        if matches!(result_row.get_field(0)?, Literal::Null) {
+            let input = self.inputs.eval_arg(0, input_row)?;
+            result_row.update_field(0, input)?;
+        }
+        Ok(())
+    }
+
+    fn result(&self, result_row: &Row) -> DBResult<Literal> {
+        result_row.get_field(0)
+    }
+}
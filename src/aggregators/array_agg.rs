@@ -0,0 +1,57 @@
+use crate::{
+    expressions::{Expression, Literal},
+    logical_plans::SortOption,
+    row::Row,
+    tables::RelationSchema,
+    DBError, DBResult,
+};
+
+use super::{
+    aggregator_inputs::{entry_value, AggregatorInputs},
+    Aggregator,
+};
+
+/// Collect values into a list, e.g. `array_agg(amount ORDER BY dateTime)`
+pub struct ArrayAgg {
+    inputs: AggregatorInputs,
+}
+
+impl ArrayAgg {
+    pub fn new(arg: &Expression, order_by: &[SortOption]) -> DBResult<Self> {
+        let inputs = AggregatorInputs::new(vec![arg.clone()], order_by.to_vec());
+        Ok(Self { inputs })
+    }
+}
+
+impl Aggregator for ArrayAgg {
+    fn initial_row(&self) -> DBResult<Row<'static>> {
+        Ok(Row::new(vec![Literal::List(vec![])]))
+    }
+
+    fn resolve_expr(&mut self, inbound_schema: &RelationSchema) -> DBResult<()> {
+        self.inputs.resolve_expr(inbound_schema)
+    }
+
+    fn process(&self, input_row: &Row, result_row: &mut Row) -> DBResult<()> {
+        let entry = self.inputs.eval_entry(input_row)?;
+        match result_row.get_field_mut(0)? {
+            Literal::List(entries) => {
+                entries.push(entry);
+                Ok(())
+            }
+            _ => Err(DBError::should_never_happen()),
+        }
+    }
+
+    fn result(&self, result_row: &Row) -> DBResult<Literal> {
+        match result_row.get_field(0)? {
+            Literal::List(entries) if entries.is_empty() => Ok(Literal::Null),
+            Literal::List(mut entries) => {
+                self.inputs.sort_entries(&mut entries)?;
+                let values = entries.iter().map(|e| entry_value(e).clone()).collect();
+                Ok(Literal::List(values))
+            }
+            _ => Err(DBError::should_never_happen()),
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use crate::{
+    expressions::{Expression, Literal},
+    row::Row,
+    tables::RelationSchema,
+    DBError, DBResult,
+};
+
+use super::{aggregator_inputs::AggregatorInputs, Aggregator};
+
+/// `bool_and`/`every` and `bool_or`, null inputs are ignored
+pub struct BoolAgg {
+    inputs: AggregatorInputs,
+    is_and: bool,
+}
+
+impl BoolAgg {
+    pub fn and(arg: &Expression) -> DBResult<Self> {
+        Ok(Self {
+            inputs: AggregatorInputs::new(vec![arg.clone()], vec![]),
+            is_and: true,
+        })
+    }
+
+    pub fn or(arg: &Expression) -> DBResult<Self> {
+        Ok(Self {
+            inputs: AggregatorInputs::new(vec![arg.clone()], vec![]),
+            is_and: false,
+        })
+    }
+}
+
+impl Aggregator for BoolAgg {
+    fn initial_row(&self) -> DBResult<Row<'static>> {
+        Ok(Row::new(vec![Literal::Null]))
+    }
+
+    fn resolve_expr(&mut self, inbound_schema: &RelationSchema) -> DBResult<()> {
+        self.inputs.resolve_expr(inbound_schema)
+    }
+
+    fn process(&self, input_row: &Row, result_row: &mut Row) -> DBResult<()> {
+        let input = match self.inputs.eval_arg(0, input_row)? {
+            Literal::Null => return Ok(()),
+            Literal::Bool(v) => v,
+            other => {
+                return Err(DBError::InterpretingError(format!(
+                    "boolean aggregator expects boolean input, got {other}"
+                )))
+            }
+        };
+        let result = match result_row.get_field(0)? {
+            Literal::Bool(acc) if self.is_and => acc && input,
+            Literal::Bool(acc) => acc || input,
+            _ => input,
+        };
+        result_row.update_field(0, Literal::Bool(result))
+    }
+
+    fn result(&self, result_row: &Row) -> DBResult<Literal> {
+        result_row.get_field(0)
+    }
+}
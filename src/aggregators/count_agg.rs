@@ -6,16 +6,22 @@ use crate::{
     DBResult,
 };
 
-use super::{aggregating_buffer::AggregatingBuffer, Aggregator};
+use super::{
+    aggregating_buffer::AggregatingBuffer, aggregator_inputs::AggregatorInputs,
+    Aggregator,
+};
 
 const FIELD_COUNT: &str = "count_agg_count";
 
+/// `count(*)` counts all rows, while `count(x)` only counts rows where `x` is not null
 pub struct CountAgg {
     agg_buffer: AggregatingBuffer,
+    /// None for `count(*)`
+    inputs: Option<AggregatorInputs>,
 }
 
 impl CountAgg {
-    pub fn new(_arg: &Expression) -> DBResult<Self> {
+    pub fn new(arg: &Expression) -> DBResult<Self> {
         let expr = Expression::BinaryOp {
             op: BinaryOp::Plus,
             left: Box::new(Expression::UnResolvedFieldRef(FIELD_COUNT.to_string())),
@@ -27,7 +33,11 @@ impl CountAgg {
             DataType::UInt64,
         )]);
         let agg_buffer = AggregatingBuffer::new(buffer_schema, aggregating_exprs);
-        Ok(Self { agg_buffer })
+        let inputs = match arg {
+            Expression::Wildcard => None,
+            arg => Some(AggregatorInputs::new(vec![arg.clone()], vec![])),
+        };
+        Ok(Self { agg_buffer, inputs })
     }
 }
 impl Aggregator for CountAgg {
@@ -37,10 +47,18 @@ impl Aggregator for CountAgg {
     }
 
     fn resolve_expr(&mut self, inbound_schema: &RelationSchema) -> DBResult<()> {
+        if let Some(inputs) = self.inputs.as_mut() {
+            inputs.resolve_expr(inbound_schema)?;
+        }
         self.agg_buffer.resolve_expr(inbound_schema)
     }
 
     fn process(&self, input_row: &Row, output_buffer: &mut Row) -> DBResult<()> {
+        if let Some(inputs) = &self.inputs {
+            if matches!(inputs.eval_arg(0, input_row)?, Literal::Null) {
+                return Ok(());
+            }
+        }
         self.agg_buffer.process(input_row, output_buffer)
     }
 
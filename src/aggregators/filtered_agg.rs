@@ -0,0 +1,56 @@
+use crate::{
+    expressions::{Expression, Literal},
+    interpreter::Interpreter,
+    optimizer::{rules::ResolveExprRule, OptimizerContextForExpr},
+    row::Row,
+    tables::RelationSchema,
+    DBResult,
+};
+
+use super::Aggregator;
+
+/// Only feed input rows satisfying the filter into the wrapped aggregator, e.g.
+/// `count(*) FILTER (WHERE amount > 10)`
+pub struct FilteredAgg {
+    filter: Expression,
+    aggregator: Box<dyn Aggregator>,
+}
+
+impl FilteredAgg {
+    pub fn new(filter: &Expression, aggregator: Box<dyn Aggregator>) -> Self {
+        Self {
+            filter: filter.clone(),
+            aggregator,
+        }
+    }
+}
+
+impl Aggregator for FilteredAgg {
+    fn initial_row(&self) -> DBResult<Row<'static>> {
+        self.aggregator.initial_row()
+    }
+
+    fn resolve_expr(&mut self, inbound_schema: &RelationSchema) -> DBResult<()> {
+        let context = OptimizerContextForExpr::new(inbound_schema.clone());
+        if let Some(resolved) = self
+            .filter
+            .transform_bottom_up(&context, &mut ResolveExprRule::resolve_expression)?
+        {
+            self.filter = resolved;
+        }
+        self.aggregator.resolve_expr(inbound_schema)
+    }
+
+    fn process(&self, input_row: &Row, result_row: &mut Row) -> DBResult<()> {
+        // null is treated as false, as in WHERE clause
+        match Interpreter::eval(&self.filter, input_row)? {
+            Literal::Null => Ok(()),
+            passed if passed.as_bool()? => self.aggregator.process(input_row, result_row),
+            _ => Ok(()),
+        }
+    }
+
+    fn result(&self, result_row: &Row) -> DBResult<Literal> {
+        self.aggregator.result(result_row)
+    }
+}
@@ -0,0 +1,75 @@
+use crate::{
+    expressions::{Expression, Literal},
+    logical_plans::SortOption,
+    row::Row,
+    tables::RelationSchema,
+    DBError, DBResult,
+};
+
+use super::{aggregator_inputs::AggregatorInputs, Aggregator};
+
+/// `first` and `last` value of a group, by the aggregate-level ordering if given,
+/// otherwise by the input order.
+///
+/// The result row holds the current value, its sort keys and whether any row was seen.
+pub struct FirstLastAgg {
+    inputs: AggregatorInputs,
+    is_first: bool,
+}
+
+impl FirstLastAgg {
+    pub fn first(arg: &Expression, order_by: &[SortOption]) -> DBResult<Self> {
+        Ok(Self {
+            inputs: AggregatorInputs::new(vec![arg.clone()], order_by.to_vec()),
+            is_first: true,
+        })
+    }
+
+    pub fn last(arg: &Expression, order_by: &[SortOption]) -> DBResult<Self> {
+        Ok(Self {
+            inputs: AggregatorInputs::new(vec![arg.clone()], order_by.to_vec()),
+            is_first: false,
+        })
+    }
+}
+
+impl Aggregator for FirstLastAgg {
+    fn initial_row(&self) -> DBResult<Row<'static>> {
+        Ok(Row::new(vec![
+            Literal::Null,
+            Literal::List(vec![]),
+            Literal::Bool(false),
+        ]))
+    }
+
+    fn resolve_expr(&mut self, inbound_schema: &RelationSchema) -> DBResult<()> {
+        self.inputs.resolve_expr(inbound_schema)
+    }
+
+    fn process(&self, input_row: &Row, result_row: &mut Row) -> DBResult<()> {
+        let sort_keys = self.inputs.eval_sort_keys(input_row)?;
+        let replace = match (result_row.get_field(2)?, result_row.get_field(1)?) {
+            (Literal::Bool(false), _) => true,
+            (Literal::Bool(true), Literal::List(current_keys)) => {
+                let ordering = self.inputs.cmp_sort_keys(&sort_keys, &current_keys)?;
+                // ties keep the earliest row for first, and the latest row for last
+                if self.is_first {
+                    ordering.is_lt()
+                } else {
+                    ordering.is_ge()
+                }
+            }
+            _ => return Err(DBError::should_never_happen()),
+        };
+        if replace {
+            result_row.update_field(0, self.inputs.eval_arg(0, input_row)?)?;
+            result_row.update_field(1, Literal::List(sort_keys))?;
+            result_row.update_field(2, Literal::Bool(true))?;
+        }
+        Ok(())
+    }
+
+    fn result(&self, result_row: &Row) -> DBResult<Literal> {
+        result_row.get_field(0)
+    }
+}
@@ -0,0 +1,57 @@
+use crate::{
+    expressions::{Expression, Literal},
+    row::Row,
+    tables::RelationSchema,
+    DBResult,
+};
+
+use super::{aggregator_inputs::AggregatorInputs, Aggregator};
+
+/// Skip null inputs of the wrapped aggregator, and result in null if no input was
+/// processed at all, e.g. `sum` over an empty table is null rather than zero.
+///
+/// A flag of whether any input was processed is appended to the wrapped result row.
+pub struct SkipNullsAgg {
+    inputs: AggregatorInputs,
+    aggregator: Box<dyn Aggregator>,
+}
+
+impl SkipNullsAgg {
+    pub fn new(arg: &Expression, aggregator: Box<dyn Aggregator>) -> Self {
+        Self {
+            inputs: AggregatorInputs::new(vec![arg.clone()], vec![]),
+            aggregator,
+        }
+    }
+}
+
+impl Aggregator for SkipNullsAgg {
+    fn initial_row(&self) -> DBResult<Row<'static>> {
+        let row = self.aggregator.initial_row()?;
+        let mut fields = (0..row.num_fields())
+            .map(|idx| row.get_field(idx))
+            .collect::<DBResult<Vec<_>>>()?;
+        fields.push(Literal::Bool(false));
+        Ok(Row::new(fields))
+    }
+
+    fn resolve_expr(&mut self, inbound_schema: &RelationSchema) -> DBResult<()> {
+        self.inputs.resolve_expr(inbound_schema)?;
+        self.aggregator.resolve_expr(inbound_schema)
+    }
+
+    fn process(&self, input_row: &Row, result_row: &mut Row) -> DBResult<()> {
+        if matches!(self.inputs.eval_arg(0, input_row)?, Literal::Null) {
+            return Ok(());
+        }
+        self.aggregator.process(input_row, result_row)?;
+        result_row.update_field(result_row.num_fields() - 1, Literal::Bool(true))
+    }
+
+    fn result(&self, result_row: &Row) -> DBResult<Literal> {
+        match result_row.get_field(result_row.num_fields() - 1)? {
+            Literal::Bool(true) => self.aggregator.result(result_row),
+            _ => Ok(Literal::Null),
+        }
+    }
+}
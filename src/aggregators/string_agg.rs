@@ -0,0 +1,86 @@
+use crate::{
+    expressions::{Expression, Literal},
+    interpreter::Interpreter,
+    logical_plans::SortOption,
+    row::Row,
+    tables::RelationSchema,
+    DBError, DBResult,
+};
+
+use super::{
+    aggregator_inputs::{entry_value, AggregatorInputs},
+    Aggregator,
+};
+
+/// Concatenate non-null values with a delimiter, e.g. `string_agg(name, ',' ORDER BY name)`
+pub struct StringAgg {
+    inputs: AggregatorInputs,
+    delimiter: Expression,
+}
+
+impl StringAgg {
+    pub fn new(
+        arg: &Expression,
+        delimiter: &Expression,
+        order_by: &[SortOption],
+    ) -> DBResult<Self> {
+        let inputs = AggregatorInputs::new(vec![arg.clone()], order_by.to_vec());
+        Ok(Self {
+            inputs,
+            delimiter: delimiter.clone(),
+        })
+    }
+
+    fn delimiter(&self) -> DBResult<String> {
+        // delimiter is a constant, thus evaluated without an input row
+        match Interpreter::eval(&self.delimiter, &Row::new(vec![]))? {
+            Literal::String(v) | Literal::UnResolvedString(v) => Ok(v),
+            Literal::Null => Ok("".to_owned()),
+            other => Err(DBError::InterpretingError(format!(
+                "string_agg delimiter must be a string, got {other}"
+            ))),
+        }
+    }
+}
+
+impl Aggregator for StringAgg {
+    fn initial_row(&self) -> DBResult<Row<'static>> {
+        Ok(Row::new(vec![Literal::List(vec![])]))
+    }
+
+    fn resolve_expr(&mut self, inbound_schema: &RelationSchema) -> DBResult<()> {
+        self.inputs.resolve_expr(inbound_schema)
+    }
+
+    fn process(&self, input_row: &Row, result_row: &mut Row) -> DBResult<()> {
+        let entry = self.inputs.eval_entry(input_row)?;
+        if matches!(entry_value(&entry), Literal::Null) {
+            return Ok(());
+        }
+        match result_row.get_field_mut(0)? {
+            Literal::List(entries) => {
+                entries.push(entry);
+                Ok(())
+            }
+            _ => Err(DBError::should_never_happen()),
+        }
+    }
+
+    fn result(&self, result_row: &Row) -> DBResult<Literal> {
+        match result_row.get_field(0)? {
+            Literal::List(entries) if entries.is_empty() => Ok(Literal::Null),
+            Literal::List(mut entries) => {
+                self.inputs.sort_entries(&mut entries)?;
+                let values = entries
+                    .iter()
+                    .map(|entry| match entry_value(entry) {
+                        Literal::String(v) => v.clone(),
+                        other => other.to_string(),
+                    })
+                    .collect::<Vec<_>>();
+                Ok(Literal::String(values.join(self.delimiter()?.as_str())))
+            }
+            _ => Err(DBError::should_never_happen()),
+        }
+    }
+}
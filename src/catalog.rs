@@ -1,23 +1,121 @@
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
+    fmt::Display,
     sync::{Arc, RwLock},
 };
 
+use sqlparser::ast::{Ident, ObjectName};
+
 use crate::{
-    tables::{csv::CsvTable, inmem::InMemTable, Table, TableMeta},
+    data_types::DataType,
+    logical_plans::LogicalPlan,
+    parser::{parse_object_name, TableFunction},
+    tables::{
+        arrow_ipc::ArrowTable,
+        compression::strip_compression_extension,
+        csv::{CsvOptions, CsvTable},
+        information_schema,
+        inmem::InMemTable,
+        json::{JsonOptions, JsonTable},
+        multi_file::{is_glob, MultiFileTable},
+        parquet::ParquetTable,
+        FieldInfo, RelationSchema, Table, TableMeta,
+    },
     DBError, DBResult,
 };
 
 type CatalogTable = Arc<RwLock<Box<dyn Table>>>;
-#[derive(Default)]
+
+/// the schema of tables created without a schema, unless changed by `SET search_path`
+pub const DEFAULT_SCHEMA: &str = "main";
+/// the name of the database, which may qualify table names as `crackdb.schema.table`
+pub const DEFAULT_CATALOG: &str = "crackdb";
+/// the read-only schema of the tables describing the catalog
+pub const INFORMATION_SCHEMA: &str = "information_schema";
+
+/// Catalog registers tables and views under qualified names `schema.table`, except
+/// tables of files read ad-hoc, which are registered under their paths, or the text of
+/// the calls of table functions, e.g. `read_csv('file.csv', header => 'false')`.
 pub struct Catalog {
     tables: Arc<RwLock<HashMap<String, CatalogTable>>>,
+    views: Arc<RwLock<HashMap<String, View>>>,
+    schemas: Arc<RwLock<HashSet<String>>>,
+    /// schemas to look up unqualified names in order, new tables of unqualified names
+    /// are created in the first schema
+    search_path: Arc<RwLock<Vec<String>>>,
+}
+
+impl Default for Catalog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// View is a named query. The logical plan of the query of a view is inlined into
+/// queries referencing it, while a materialized view is a table of the results of its
+/// query, which are only recomputed on refresh.
+#[derive(Debug, Clone)]
+pub struct View {
+    /// the plan built from the query, whose table names are qualified by their schemas
+    /// when the view is created but not resolved yet
+    plan: LogicalPlan,
+    /// the schema of the results of the query when the view is created
+    schema: RelationSchema,
+    materialized: bool,
+}
+
+impl View {
+    pub fn new(plan: LogicalPlan, schema: RelationSchema, materialized: bool) -> Self {
+        Self {
+            plan,
+            schema,
+            materialized,
+        }
+    }
+
+    pub fn plan(&self) -> &LogicalPlan {
+        &self.plan
+    }
+
+    pub fn schema(&self) -> &RelationSchema {
+        &self.schema
+    }
+
+    pub fn is_materialized(&self) -> bool {
+        self.materialized
+    }
+}
+
+/// TableType is the kind of a relation registered in the catalog
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TableType {
+    Table,
+    View,
+    MaterializedView,
+}
+
+impl Display for TableType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TableType::Table => write!(f, "BASE TABLE"),
+            TableType::View => write!(f, "VIEW"),
+            TableType::MaterializedView => write!(f, "MATERIALIZED VIEW"),
+        }
+    }
 }
 
 impl Catalog {
     pub fn new() -> Self {
         let tables = Arc::new(RwLock::new(HashMap::new()));
-        Self { tables }
+        let views = Arc::new(RwLock::new(HashMap::new()));
+        let schemas = Arc::new(RwLock::new(HashSet::from([DEFAULT_SCHEMA.to_owned()])));
+        let search_path = Arc::new(RwLock::new(vec![DEFAULT_SCHEMA.to_owned()]));
+        Self {
+            tables,
+            views,
+            schemas,
+            search_path,
+        }
     }
 
     pub fn try_get_table(&self, table_name: &str) -> DBResult<CatalogTable> {
@@ -27,32 +125,516 @@ impl Catalog {
     }
 
     fn get_or_create_table(&self, table_name: &str) -> DBResult<Option<CatalogTable>> {
+        let name = self.resolve_name(table_name)?;
+        if let Some(table) = information_schema::create_table(self, &name)? {
+            return Ok(Some(Arc::new(RwLock::new(table))));
+        }
         let opt_table = self
             .tables
             .read()
             .map_err(|_| {
                 DBError::Unknown("Acceess read lock of tables failed!".to_string())
             })
-            .map(|tables| tables.get(table_name).cloned())?;
-        if opt_table.is_none() && table_name.to_lowercase().ends_with(".csv") {
-            // read csv ad-hoc from given path
-            let csv_table = CsvTable::new(table_name.to_owned())?;
-            self.add_new_table(table_name.to_owned(), Box::new(csv_table))?;
-            return self.get_or_create_table(table_name);
+            .map(|tables| tables.get(&name).cloned())?;
+        if opt_table.is_none() && is_file_path(&name) {
+            // read file ad-hoc from given path, which may have been registered by a
+            // concurrent query meanwhile
+            self.add_new_table(name.clone(), create_file_table(&name)?, false, true)?;
+            return self.get_or_create_table(&name);
         }
         Ok(opt_table)
     }
 
-    fn add_new_table(&self, name: String, table: Box<dyn Table>) -> DBResult<()> {
-        let mut tables_map = RwLock::write(Arc::as_ref(&self.tables)).map_err(|_| {
+    /// the qualified name of a table or view of SQL text, e.g. `analytics."daily.orders"`,
+    /// see `resolve_object_name`. Text which is not a name of SQL, e.g. the path of a
+    /// file, is the name of a single part.
+    pub fn resolve_name(&self, name: &str) -> DBResult<String> {
+        let name =
+            parse_object_name(name).unwrap_or_else(|| ObjectName(vec![Ident::new(name)]));
+        self.resolve_object_name(&name)
+    }
+
+    /// the qualified name `schema.table` of a table or view. Unqualified names are looked
+    /// up in the schemas of the search path in order, and belong to the first schema of
+    /// the search path if not found. Paths of files are returned as is. The name of
+    /// information_schema and its tables are case insensitive.
+    pub fn resolve_object_name(&self, name: &ObjectName) -> DBResult<String> {
+        let parts = name
+            .0
+            .iter()
+            .map(|ident| ident.value.as_str())
+            .collect::<Vec<_>>();
+        let path = parts.join(".");
+        let (schema, table) = match parts.as_slice() {
+            [table] => {
+                let search_path = self.search_path()?;
+                for schema in search_path.iter() {
+                    let qualified = qualified_name(schema, table);
+                    if self.contains(&qualified)? {
+                        return Ok(qualified);
+                    }
+                }
+                if is_file_path(table) {
+                    return Ok(path);
+                }
+                return Ok(qualified_name(&search_path[0], table));
+            }
+            [schema, table] => (*schema, *table),
+            [catalog, schema, table] if *catalog == DEFAULT_CATALOG => (*schema, *table),
+            _ => ("", ""),
+        };
+        if schema.eq_ignore_ascii_case(INFORMATION_SCHEMA) {
+            Ok(qualified_name(INFORMATION_SCHEMA, &table.to_lowercase()))
+        } else if self.schema_exists(schema)? {
+            Ok(qualified_name(schema, table))
+        } else if is_file_path(&path) {
+            Ok(path)
+        } else if parts.len() == 3 && parts[0] != DEFAULT_CATALOG {
+            Err(DBError::ParserError(format!(
+                "catalog {} does not exist",
+                parts[0]
+            )))
+        } else if parts.len() > 3 {
+            Err(DBError::ParserError(format!("invalid table name {name}")))
+        } else {
+            Err(DBError::SchemaNotFound(schema.to_owned()))
+        }
+    }
+
+    /// the name of a table or view qualified by its schema, see `resolve_object_name`.
+    /// Paths of files are returned as is.
+    pub fn qualify_object_name(&self, name: &ObjectName) -> DBResult<ObjectName> {
+        let qualified = self.resolve_object_name(name)?;
+        let path = name
+            .0
+            .iter()
+            .map(|ident| ident.value.as_str())
+            .collect::<Vec<_>>()
+            .join(".");
+        if is_file_path(&qualified) && qualified == path {
+            return Ok(name.clone());
+        }
+        parse_object_name(&qualified).ok_or_else(DBError::should_never_happen)
+    }
+
+    /// whether a table or view is registered under the qualified name
+    fn contains(&self, qualified: &str) -> DBResult<bool> {
+        let is_table = self
+            .tables
+            .read()
+            .map_err(|_| {
+                DBError::Unknown("Acceess read lock of tables failed!".to_string())
+            })?
+            .contains_key(qualified);
+        Ok(is_table || self.read_views()?.contains_key(qualified))
+    }
+
+    pub fn schema_exists(&self, schema: &str) -> DBResult<bool> {
+        Ok(schema == INFORMATION_SCHEMA || self.read_schemas()?.contains(schema))
+    }
+
+    /// create a new schema, an existing schema is an error unless `if_not_exists` is true
+    pub fn create_schema(&self, schema: String, if_not_exists: bool) -> DBResult<()> {
+        if self.schema_exists(&schema)? {
+            return match if_not_exists {
+                true => Ok(()),
+                false => Err(DBError::SchemaAlreadyExists(schema)),
+            };
+        }
+        RwLock::write(Arc::as_ref(&self.schemas))
+            .map_err(|_| {
+                DBError::Unknown("Access write lock of DB schemas failed!".to_string())
+            })?
+            .insert(schema);
+        Ok(())
+    }
+
+    pub fn search_path(&self) -> DBResult<Vec<String>> {
+        RwLock::read(Arc::as_ref(&self.search_path))
+            .map_err(|_| {
+                DBError::Unknown("Acceess read lock of search path failed!".to_string())
+            })
+            .map(|search_path| search_path.clone())
+    }
+
+    /// replace the search path, all schemas of it must exist
+    pub fn set_search_path(&self, search_path: Vec<String>) -> DBResult<()> {
+        if search_path.is_empty() {
+            return Err(DBError::ParserError(
+                "search_path must have at least one schema".to_string(),
+            ));
+        }
+        let schemas = self.read_schemas()?;
+        if let Some(schema) = search_path.iter().find(|s| !schemas.contains(*s)) {
+            return Err(DBError::SchemaNotFound(schema.clone()));
+        }
+        drop(schemas);
+        *RwLock::write(Arc::as_ref(&self.search_path)).map_err(|_| {
+            DBError::Unknown("Access write lock of search path failed!".to_string())
+        })? = search_path;
+        Ok(())
+    }
+
+    /// schemas, names and types of all tables and views, ordered by schemas and names.
+    /// Tables of files read ad-hoc are not listed.
+    pub fn list_tables(&self) -> DBResult<Vec<(String, String, TableType)>> {
+        let names = self
+            .tables
+            .read()
+            .map_err(|_| {
+                DBError::Unknown("Acceess read lock of tables failed!".to_string())
+            })?
+            .keys()
+            .cloned()
+            .collect::<Vec<_>>();
+        let views = self.read_views()?.clone();
+        let mut tables = names
+            .into_iter()
+            .filter(|name| !views.contains_key(name))
+            .map(|name| (name, TableType::Table))
+            .collect::<Vec<_>>();
+        tables.extend(views.into_iter().map(
+            |(name, view)| match view.is_materialized() {
+                true => (name, TableType::MaterializedView),
+                false => (name, TableType::View),
+            },
+        ));
+        let schemas = self.read_schemas()?;
+        let mut tables = tables
+            .into_iter()
+            .filter_map(|(name, table_type)| {
+                match parse_object_name(&name)?.0.as_slice() {
+                    [schema, table] => schemas
+                        .contains(&schema.value)
+                        .then(|| (schema.value.clone(), table.value.clone(), table_type)),
+                    _ => None,
+                }
+            })
+            .collect::<Vec<_>>();
+        tables.sort_by(|(s1, t1, _), (s2, t2, _)| (s1, t1).cmp(&(s2, t2)));
+        Ok(tables)
+    }
+
+    /// the meta of a table or materialized view, or the schema of a view
+    pub fn get_relation_meta(&self, name: &str) -> DBResult<TableMeta> {
+        match self.get_view(name)? {
+            Some(view) if !view.is_materialized() => {
+                Ok(TableMeta::new(view.schema.clone()))
+            }
+            _ => {
+                let table = self.try_get_table(name)?;
+                let table = RwLock::read(&table).map_err(|_| {
+                    DBError::Unknown("Access read lock of table failed.".to_string())
+                })?;
+                Ok(table.get_table_meta())
+            }
+        }
+    }
+
+    /// whether a table is registered under the given name, materialized views are not
+    /// considered as tables
+    pub fn table_exists(&self, table_name: &str) -> DBResult<bool> {
+        let name = self.resolve_name(table_name)?;
+        let is_table = self
+            .tables
+            .read()
+            .map_err(|_| {
+                DBError::Unknown("Acceess read lock of tables failed!".to_string())
+            })
+            .map(|tables| tables.contains_key(&name))?;
+        Ok(is_table && !self.read_views()?.contains_key(&name))
+    }
+
+    /// the view registered under the given name, if any
+    pub fn get_view(&self, name: &str) -> DBResult<Option<View>> {
+        let name = self.resolve_name(name)?;
+        Ok(self.read_views()?.get(&name).cloned())
+    }
+
+    /// register a view under the given name, an existing view with the same name is
+    /// replaced only if `replace` is true. A materialized view is registered with the
+    /// table of its results.
+    pub fn add_view(
+        &self,
+        name: String,
+        view: View,
+        table: Option<Box<dyn Table>>,
+        replace: bool,
+    ) -> DBResult<()> {
+        let key = self.resolve_writable_name(&name)?;
+        let mut tables_map = self.write_tables()?;
+        let mut views_map = self.write_views()?;
+        let is_view = views_map.contains_key(&key);
+        if (!replace && is_view) || (!is_view && tables_map.contains_key(&key)) {
+            return Err(DBError::TableAlreadyExists(name));
+        }
+        tables_map.remove(&key);
+        if let Some(table) = table {
+            tables_map.insert(key.clone(), Arc::new(RwLock::new(table)));
+        }
+        views_map.insert(key, view);
+        Ok(())
+    }
+
+    /// remove the view with the given name, returns whether the view existed
+    pub fn drop_view(&self, name: &str) -> DBResult<bool> {
+        let name = self.resolve_name(name)?;
+        let mut tables_map = self.write_tables()?;
+        let mut views_map = self.write_views()?;
+        let view = views_map.remove(&name);
+        if view.as_ref().is_some_and(View::is_materialized) {
+            tables_map.remove(&name);
+        }
+        Ok(view.is_some())
+    }
+
+    /// the qualified name of a new table or view, which cannot be created in
+    /// information_schema
+    fn resolve_writable_name(&self, name: &str) -> DBResult<String> {
+        let key = self.resolve_name(name)?;
+        if key.starts_with(&format!("{INFORMATION_SCHEMA}.")) {
+            return Err(DBError::ParserError(format!(
+                "schema {INFORMATION_SCHEMA} is read-only"
+            )));
+        }
+        Ok(key)
+    }
+
+    fn read_views(
+        &self,
+    ) -> DBResult<std::sync::RwLockReadGuard<'_, HashMap<String, View>>> {
+        RwLock::read(Arc::as_ref(&self.views)).map_err(|_| {
+            DBError::Unknown("Acceess read lock of views failed!".to_string())
+        })
+    }
+
+    fn read_schemas(&self) -> DBResult<std::sync::RwLockReadGuard<'_, HashSet<String>>> {
+        RwLock::read(Arc::as_ref(&self.schemas)).map_err(|_| {
+            DBError::Unknown("Acceess read lock of schemas failed!".to_string())
+        })
+    }
+
+    fn write_tables(
+        &self,
+    ) -> DBResult<std::sync::RwLockWriteGuard<'_, HashMap<String, CatalogTable>>> {
+        RwLock::write(Arc::as_ref(&self.tables)).map_err(|_| {
             DBError::Unknown("Access write lock of DB tables failed!".to_string())
-        })?;
-        tables_map.insert(name, Arc::new(RwLock::new(table)));
+        })
+    }
+
+    fn write_views(
+        &self,
+    ) -> DBResult<std::sync::RwLockWriteGuard<'_, HashMap<String, View>>> {
+        RwLock::write(Arc::as_ref(&self.views)).map_err(|_| {
+            DBError::Unknown("Access write lock of DB views failed!".to_string())
+        })
+    }
+
+    /// register a table under the given name, an existing table with the same name is
+    /// replaced if `replace` is true, or kept if `if_not_exists` is true. Views are never
+    /// replaced by tables.
+    pub fn add_new_table(
+        &self,
+        name: String,
+        table: Box<dyn Table>,
+        replace: bool,
+        if_not_exists: bool,
+    ) -> DBResult<()> {
+        let key = self.resolve_writable_name(&name)?;
+        if self.read_views()?.contains_key(&key) {
+            return Err(DBError::TableAlreadyExists(name));
+        }
+        let mut tables_map = self.write_tables()?;
+        if !replace && tables_map.contains_key(&key) {
+            if if_not_exists {
+                return Ok(());
+            }
+            return Err(DBError::TableAlreadyExists(name));
+        }
+        tables_map.insert(key, Arc::new(RwLock::new(table)));
         Ok(())
     }
 
-    pub fn create_table(&self, name: String, meta: TableMeta) -> DBResult<()> {
+    pub fn create_table(
+        &self,
+        name: String,
+        meta: TableMeta,
+        replace: bool,
+    ) -> DBResult<()> {
         let table = InMemTable::new(meta);
-        self.add_new_table(name, Box::new(table))
+        self.add_new_table(name, Box::new(table), replace, false)
+    }
+
+    /// remove the table with the given name, returns whether the table existed
+    pub fn drop_table(&self, name: &str) -> DBResult<bool> {
+        if !self.table_exists(name)? {
+            return Ok(false);
+        }
+        let name = self.resolve_name(name)?;
+        Ok(self.write_tables()?.remove(&name).is_some())
+    }
+
+    /// rename a table, an unqualified new name stays in the schema of the table
+    pub fn rename_table(&self, name: &str, new_name: String) -> DBResult<()> {
+        if !self.table_exists(name)? {
+            return Err(DBError::TableNotFound(name.to_string()));
+        }
+        let key = self.resolve_name(name)?;
+        let schema = parse_object_name(&key).and_then(|key| key.0.into_iter().next());
+        let new_key = match (parse_object_name(&new_name), schema) {
+            (Some(ObjectName(parts)), Some(schema)) if parts.len() == 1 => {
+                qualified_name(&schema.value, &parts[0].value)
+            }
+            _ => self.resolve_writable_name(&new_name)?,
+        };
+        let mut tables_map = self.write_tables()?;
+        if tables_map.contains_key(&new_key) {
+            return Err(DBError::TableAlreadyExists(new_name));
+        }
+        let table = tables_map
+            .remove(&key)
+            .ok_or(DBError::TableNotFound(name.to_string()))?;
+        tables_map.insert(new_key, table);
+        Ok(())
+    }
+}
+
+const CSV_EXTENSIONS: [&str; 1] = [".csv"];
+const JSON_EXTENSIONS: [&str; 3] = [".json", ".ndjson", ".jsonl"];
+const PARQUET_EXTENSIONS: [&str; 1] = [".parquet"];
+const ARROW_EXTENSIONS: [&str; 2] = [".arrow", ".feather"];
+
+/// whether the table name is the path of a file, which is read ad-hoc. Csv and json
+/// files may be compressed, e.g. `orders.csv.gz`
+fn is_file_path(name: &str) -> bool {
+    let name = name.to_lowercase();
+    let text_name = strip_compression_extension(&name);
+    CSV_EXTENSIONS
+        .iter()
+        .chain(JSON_EXTENSIONS.iter())
+        .any(|ext| text_name.ends_with(ext))
+        || PARQUET_EXTENSIONS
+            .iter()
+            .chain(ARROW_EXTENSIONS.iter())
+            .any(|ext| name.ends_with(ext))
+}
+
+/// the qualified name `schema.table` of the schema and the table, names which are not
+/// plain identifiers are quoted, e.g. `main."daily.orders"`, so that the qualified name
+/// is resolved into itself
+fn qualified_name(schema: &str, table: &str) -> String {
+    let ident = |name: &str| {
+        let is_plain = name.starts_with(|c: char| c.is_alphabetic() || c == '_')
+            && name.chars().all(|c| c.is_alphanumeric() || c == '_');
+        match is_plain {
+            true => Ident::new(name),
+            false => Ident::with_quote('"', name),
+        }
+    };
+    ObjectName(vec![ident(schema), ident(table)]).to_string()
+}
+
+/// the table of a table function, e.g. `read_csv('file.csv', header => false)`
+pub(crate) fn create_function_table(
+    function: &TableFunction,
+) -> DBResult<Box<dyn Table>> {
+    match function.name.as_str() {
+        "read_csv" => {
+            let options = CsvOptions::try_from_options(&function.options)?;
+            if is_glob(&function.path) {
+                return Ok(Box::new(MultiFileTable::try_new(
+                    &function.path,
+                    |path, schema| create_csv_table(path, schema, options.clone()),
+                )?));
+            }
+            Ok(Box::new(CsvTable::with_options(
+                function.path.clone(),
+                options,
+            )?))
+        }
+        "read_json" => {
+            let options = JsonOptions::try_from_options(&function.options)?;
+            if is_glob(&function.path) {
+                return Ok(Box::new(MultiFileTable::try_new(
+                    &function.path,
+                    |path, _schema| {
+                        let table =
+                            JsonTable::with_options(path.to_owned(), options.clone())?;
+                        Ok(Box::new(table) as Box<dyn Table>)
+                    },
+                )?));
+            }
+            Ok(Box::new(JsonTable::with_options(
+                function.path.clone(),
+                options,
+            )?))
+        }
+        name => Err(DBError::ParserError(format!(
+            "table function {name} is not supported"
+        ))),
+    }
+}
+
+/// the table of a file, by the extension of the path, or the table of the files
+/// matching the path if it is a glob pattern
+fn create_file_table(path: &str) -> DBResult<Box<dyn Table>> {
+    if is_glob(path) {
+        return Ok(Box::new(MultiFileTable::try_new(path, |path, schema| {
+            create_single_file_table(path, schema)
+        })?));
+    }
+    create_single_file_table(path, None)
+}
+
+/// the table of a single file, a csv file is read with the schema if given
+fn create_single_file_table(
+    path: &str,
+    schema: Option<&RelationSchema>,
+) -> DBResult<Box<dyn Table>> {
+    let lower_case = path.to_lowercase();
+    if JSON_EXTENSIONS
+        .iter()
+        .any(|ext| strip_compression_extension(&lower_case).ends_with(ext))
+    {
+        Ok(Box::new(JsonTable::new(path.to_owned())?))
+    } else if PARQUET_EXTENSIONS
+        .iter()
+        .any(|ext| lower_case.ends_with(ext))
+    {
+        Ok(Box::new(ParquetTable::new(path.to_owned())?))
+    } else if ARROW_EXTENSIONS.iter().any(|ext| lower_case.ends_with(ext)) {
+        Ok(Box::new(ArrowTable::new(path.to_owned())?))
+    } else {
+        create_csv_table(path, schema, CsvOptions::default())
+    }
+}
+
+/// the table of a csv file, whose schema is inferred unless given
+fn create_csv_table(
+    path: &str,
+    schema: Option<&RelationSchema>,
+    options: CsvOptions,
+) -> DBResult<Box<dyn Table>> {
+    match schema {
+        Some(schema) => {
+            // the names are read from the file, so that files of other columns are not
+            // read by the names of the schema
+            let fields = CsvTable::field_names(path, &options)?
+                .into_iter()
+                .enumerate()
+                .map(|(idx, name)| {
+                    let data_type = schema
+                        .get_field_at(idx)
+                        .map_or(DataType::String, |field| field.data_type().clone());
+                    FieldInfo::new(name, data_type)
+                })
+                .collect();
+            Ok(Box::new(CsvTable::with_schema(
+                path.to_owned(),
+                RelationSchema::new(fields),
+                options,
+            )?))
+        }
+        None => Ok(Box::new(CsvTable::with_options(path.to_owned(), options)?)),
     }
 }
@@ -17,6 +17,7 @@ pub enum DataType {
     String,
     Boolean,
     DateTime,
+    List(Box<DataType>),
     Unknown,
 }
 
@@ -35,6 +36,37 @@ impl DataType {
         )
     }
 
+    /// whether values of the type are integers, and the number of bits of them
+    fn integer_bits(&self) -> Option<(bool, u32)> {
+        match self {
+            Self::UInt8 => Some((false, 8)),
+            Self::UInt16 => Some((false, 16)),
+            Self::UInt32 => Some((false, 32)),
+            Self::UInt64 => Some((false, 64)),
+            Self::Int8 => Some((true, 8)),
+            Self::Int16 => Some((true, 16)),
+            Self::Int32 => Some((true, 32)),
+            Self::Int64 => Some((true, 64)),
+            _ => None,
+        }
+    }
+
+    /// whether values of the type are implicitly converted to the wider numeric type,
+    /// e.g. `Int32` to `Int64` or `Float64`
+    pub fn can_widen_to(&self, data_type: &DataType) -> bool {
+        match (self.integer_bits(), data_type.integer_bits()) {
+            (Some((signed, bits)), Some((to_signed, to_bits))) => {
+                (signed == to_signed || to_signed) && bits < to_bits
+            }
+            (Some((_, bits)), None) => match data_type {
+                DataType::Float32 => bits <= 16,
+                DataType::Float64 => true,
+                _ => false,
+            },
+            (None, _) => *self == DataType::Float32 && *data_type == DataType::Float64,
+        }
+    }
+
     pub fn zero(&self) -> DBResult<Literal> {
         match self {
             DataType::UInt8 => Ok(Literal::UInt8(0)),
@@ -6,9 +6,17 @@ use sqlparser::parser::ParserError;
 pub enum DBError {
     ParserError(String),
     TableNotFound(String),
+    TableAlreadyExists(String),
+    SchemaNotFound(String),
+    SchemaAlreadyExists(String),
     InterpretingError(String),
     Unknown(String),
     StorageEngine(String),
+    NotNullViolation(String),
+    UniqueViolation(String),
+    CheckViolation(String),
+    /// the index of the failed statement in a batch, and its error
+    StatementFailed(usize, Box<DBError>),
 }
 
 impl Display for DBError {
@@ -16,9 +24,26 @@ impl Display for DBError {
         match self {
             DBError::ParserError(msg) => write!(f, "ParserError: {}", msg),
             DBError::TableNotFound(msg) => write!(f, "TableNoteFound: {}", msg),
+            DBError::TableAlreadyExists(msg) => write!(f, "TableAlreadyExists: {}", msg),
+            DBError::SchemaNotFound(msg) => write!(f, "SchemaNotFound: {}", msg),
+            DBError::SchemaAlreadyExists(msg) => {
+                write!(f, "SchemaAlreadyExists: {}", msg)
+            }
             DBError::InterpretingError(msg) => write!(f, "InterpretingError: {}", msg),
             DBError::Unknown(msg) => write!(f, "Unknown: {}", msg),
             DBError::StorageEngine(msg) => write!(f, "StorageEngineError: {}", msg),
+            DBError::NotNullViolation(constraint) => {
+                write!(f, "NotNullViolation: {}", constraint)
+            }
+            DBError::UniqueViolation(constraint) => {
+                write!(f, "UniqueViolation: {}", constraint)
+            }
+            DBError::CheckViolation(constraint) => {
+                write!(f, "CheckViolation: {}", constraint)
+            }
+            DBError::StatementFailed(idx, e) => {
+                write!(f, "StatementFailed: statement {} failed with {}", idx, e)
+            }
         }
     }
 }
@@ -1,14 +1,17 @@
 use core::slice;
 use std::borrow::Borrow;
+use std::collections::BTreeSet;
 use std::fmt::Display;
 use std::hash::Hash;
-use std::rc::Rc;
+use std::sync::Arc;
 
 use serde::Serialize;
 
 use crate::data_types::DataType;
 use crate::functions::Function;
+use crate::logical_plans::SortOption;
 use crate::optimizer::{OptimizerContextForExpr, OptimizerNode};
+use crate::tables::csv::parse_datetime;
 use crate::DBError;
 use crate::DBResult;
 
@@ -37,10 +40,17 @@ pub enum Expression {
     UnResolvedFunction {
         name: String,
         args: Vec<Expression>,
+        clauses: AggregateClauses,
     },
-    // TODO: revisit the usage of Rc here
-    Function(Rc<dyn Function>),
+    // TODO: revisit the usage of Arc here
+    Function(Arc<dyn Function>),
     Wildcard,
+    /// a placeholder of a prepared statement, e.g. `$1`, the index is zero-based and the
+    /// data type is inferred from where the parameter is used
+    Parameter {
+        index: usize,
+        data_type: DataType,
+    },
 }
 
 impl Display for Expression {
@@ -54,20 +64,101 @@ impl Display for Expression {
                 write!(f, "{left}_{op}_{right}")
             }
             Expression::UnaryOp { op, input } => write!(f, "{op}_{input}"),
-            Expression::UnResolvedFunction { name, args } => write!(
-                f,
-                "{name}({})",
-                args.iter()
-                    .map(|e| { e.to_string() })
-                    .collect::<Vec<String>>()
-                    .join(", ")
-            ),
+            Expression::UnResolvedFunction {
+                name,
+                args,
+                clauses,
+            } => write!(f, "{}", function_expr_string(name, args, clauses)),
             Expression::Function(func) => func.as_ref().to_expr_string().fmt(f),
+            Expression::Parameter { index, .. } => write!(f, "${}", index + 1),
             Expression::Wildcard => "*".fmt(f),
         }
     }
 }
 
+/// format a function call, e.g. `first(amount ORDER BY dateTime DESC)`
+pub(crate) fn function_expr_string(
+    name: &str,
+    args: &[impl Borrow<Expression>],
+    clauses: &AggregateClauses,
+) -> String {
+    let args = args
+        .iter()
+        .map(|e| e.borrow().to_string())
+        .collect::<Vec<String>>()
+        .join(", ");
+    let mut expr_string = if clauses.order_by.is_empty() {
+        format!("{name}({args})")
+    } else {
+        let order_by = clauses
+            .order_by
+            .iter()
+            .map(|option| match option.asc() {
+                true => option.expr().to_string(),
+                false => format!("{} DESC", option.expr()),
+            })
+            .collect::<Vec<String>>()
+            .join(", ");
+        format!("{name}({args} ORDER BY {order_by})")
+    };
+    if let Some(filter) = &clauses.filter {
+        expr_string = format!("{expr_string} FILTER (WHERE {filter})");
+    }
+    expr_string
+}
+
+/// Aggregate-level clauses of a function call, e.g. the `ORDER BY` of
+/// `string_agg(name, ',' ORDER BY name)` and the `FILTER` of
+/// `count(*) FILTER (WHERE amount > 10)`
+#[derive(Debug, Clone, Default)]
+pub struct AggregateClauses {
+    order_by: Vec<SortOption>,
+    filter: Option<Box<Expression>>,
+}
+
+impl AggregateClauses {
+    pub fn new(order_by: Vec<SortOption>, filter: Option<Expression>) -> Self {
+        Self {
+            order_by,
+            filter: filter.map(Box::new),
+        }
+    }
+
+    pub fn order_by(&self) -> &[SortOption] {
+        &self.order_by
+    }
+
+    pub fn filter(&self) -> Option<&Expression> {
+        self.filter.as_deref()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.order_by.is_empty() && self.filter.is_none()
+    }
+
+    /// expressions of the clauses, ordering expressions followed by the filter
+    pub fn exprs(&self) -> Vec<&Expression> {
+        self.order_by
+            .iter()
+            .map(|option| option.expr())
+            .chain(self.filter.as_deref())
+            .collect()
+    }
+
+    /// rebuild the clauses with expressions in the same layout of `exprs`
+    pub fn with_exprs(&self, exprs: Vec<Expression>) -> Self {
+        let mut iter = exprs.into_iter();
+        let order_by = self
+            .order_by
+            .iter()
+            .zip(iter.by_ref())
+            .map(|(option, expr)| SortOption::new(expr, option.asc()))
+            .collect();
+        let filter = self.filter.as_ref().and(iter.next()).map(Box::new);
+        Self { order_by, filter }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum BinaryOp {
     Plus,
@@ -152,9 +243,10 @@ impl Expression {
             Expression::UnaryOp { input, .. } => input.data_type(),
             Expression::Alias { alias: _, child } => child.data_type(),
             // TODO: implement this
-            Expression::UnResolvedFunction { name: _, args: _ } => DataType::Unknown,
+            Expression::UnResolvedFunction { .. } => DataType::Unknown,
             Expression::Function(func) => func.as_ref().data_type(),
             Expression::Wildcard => DataType::Unknown,
+            Expression::Parameter { data_type, .. } => data_type.clone(),
         }
     }
 
@@ -174,9 +266,10 @@ impl Expression {
                 format!("{}_{}_{}", left.sematic_id(), op, right.sematic_id())
             }
             Expression::UnaryOp { op, input } => format!("{op}_{}", input.sematic_id()),
-            Expression::UnResolvedFunction { name: _, args: _ } => self.to_string(),
+            Expression::UnResolvedFunction { .. } => self.to_string(),
             Expression::Function(_) => self.to_string(),
             Expression::Wildcard => self.to_string(),
+            Expression::Parameter { .. } => format!("parameter_{self}"),
         }
     }
 
@@ -192,6 +285,7 @@ impl Expression {
             Expression::Literal(_) => func(self, context),
             Expression::UnResolvedFieldRef(_) => func(self, context),
             Expression::FieldRef { .. } => func(self, context),
+            Expression::Parameter { .. } => func(self, context),
             Expression::BinaryOp { op, left, right } => {
                 let children = vec![left.as_ref(), right.as_ref()];
                 self.transform_bottom_up_helper(&children, context, func, |children| {
@@ -223,13 +317,18 @@ impl Expression {
                     })
                 })
             }
-            Expression::UnResolvedFunction { name, args } => self
-                .transform_bottom_up_helper(args, context, func, |children| {
-                    Ok(Expression::UnResolvedFunction {
-                        name: name.clone(),
-                        args: children,
-                    })
-                }),
+            Expression::UnResolvedFunction {
+                name,
+                args,
+                clauses,
+            } => {
+                let children = self.children();
+                self.transform_bottom_up_helper(&children, context, func, |children| {
+                    Ok(Self::unresolved_function_with_children(
+                        name, args, clauses, children,
+                    ))
+                })
+            }
             Expression::Function(f) => {
                 let args = f.args();
                 self.transform_bottom_up_helper(&args, context, func, |args| {
@@ -273,6 +372,22 @@ impl Expression {
         }
     }
 
+    /// indexes of the fields referenced by the expression, None if any field is not
+    /// resolved
+    pub(crate) fn referenced_fields(&self) -> Option<BTreeSet<usize>> {
+        match self {
+            Expression::FieldRef { index, .. } => Some(BTreeSet::from([*index])),
+            Expression::UnResolvedFieldRef(_) => None,
+            _ => self.children().into_iter().try_fold(
+                BTreeSet::new(),
+                |mut fields, child| {
+                    fields.extend(child.referenced_fields()?);
+                    Some(fields)
+                },
+            ),
+        }
+    }
+
     fn children(&self) -> Vec<&Expression> {
         match self {
             Expression::Literal(_) => vec![],
@@ -285,9 +400,12 @@ impl Expression {
             Expression::Alias { alias: _, child } => vec![child],
             Expression::BinaryOp { op: _, left, right } => vec![left, right],
             Expression::UnaryOp { op: _, input } => vec![input],
-            Expression::UnResolvedFunction { name: _, args } => args.iter().collect(),
+            Expression::UnResolvedFunction { args, clauses, .. } => {
+                args.iter().chain(clauses.exprs()).collect()
+            }
             Expression::Function(f) => f.args(),
             Expression::Wildcard => vec![],
+            Expression::Parameter { .. } => vec![],
         }
     }
 
@@ -320,17 +438,35 @@ impl Expression {
                 op: op.clone(),
                 input: Box::new(children.into_iter().next().unwrap()),
             }),
-            Expression::UnResolvedFunction { name, args: _ } => {
-                Ok(Expression::UnResolvedFunction {
-                    name: name.clone(),
-                    args: children,
-                })
-            }
+            Expression::UnResolvedFunction {
+                name,
+                args,
+                clauses,
+            } => Ok(Self::unresolved_function_with_children(
+                name, args, clauses, children,
+            )),
             Expression::Function(f) => {
                 let updated_f = f.with_args(children)?;
                 Ok(Expression::Function(updated_f))
             }
             Expression::Wildcard => Ok(Expression::Wildcard),
+            Expression::Parameter { .. } => Err(DBError::should_never_happen()),
+        }
+    }
+
+    /// rebuild an unresolved function from its children, which are the args followed by
+    /// the expressions of aggregate-level clauses
+    fn unresolved_function_with_children(
+        name: &str,
+        args: &[Expression],
+        clauses: &AggregateClauses,
+        mut children: Vec<Expression>,
+    ) -> Expression {
+        let clause_exprs = children.split_off(args.len());
+        Expression::UnResolvedFunction {
+            name: name.to_owned(),
+            args: children,
+            clauses: clauses.with_exprs(clause_exprs),
         }
     }
 
@@ -398,6 +534,7 @@ pub enum Literal {
     Bool(bool),
     String(String),
     DateTime(String),
+    List(Vec<Literal>),
     Null,
 }
 
@@ -421,7 +558,8 @@ impl Serialize for Literal {
             Literal::Float64(v) => serializer.serialize_f64(*v),
             Literal::Bool(v) => serializer.serialize_bool(*v),
             Literal::String(v) => serializer.serialize_str(v.as_str()),
-            Literal::DateTime(_) => todo!(),
+            Literal::DateTime(v) => serializer.serialize_str(v.as_str()),
+            Literal::List(v) => v.serialize(serializer),
             Literal::Null => serializer.serialize_none(),
         }
     }
@@ -445,6 +583,7 @@ impl PartialEq for Literal {
             (Self::Bool(l0), Self::Bool(r0)) => l0 == r0,
             (Self::String(l0), Self::String(r0)) => l0 == r0,
             (Self::DateTime(l0), Self::DateTime(r0)) => l0 == r0,
+            (Self::List(l0), Self::List(r0)) => l0 == r0,
             _ => core::mem::discriminant(self) == core::mem::discriminant(other),
         }
     }
@@ -452,14 +591,36 @@ impl PartialEq for Literal {
 
 /// this is hack for using Literal as HashMap key
 /// FIXME: deal with NaN
-impl Eq for Literal {
-    fn assert_receiver_is_total_eq(&self) {}
-}
+impl Eq for Literal {}
 
-/// TODO: revisit this to make sure floats are safe for hashing
+/// hash is consistent with PartialEq, i.e. values of different variants are never equal.
+/// Floats are hashed by their bits, with both zeros hashed the same since `0.0 == -0.0`.
 impl Hash for Literal {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
         core::mem::discriminant(self).hash(state);
+        match self {
+            Literal::UnResolvedNumber(v)
+            | Literal::UnResolvedString(v)
+            | Literal::String(v)
+            | Literal::DateTime(v) => v.hash(state),
+            Literal::UInt8(v) => v.hash(state),
+            Literal::UInt16(v) => v.hash(state),
+            Literal::UInt32(v) => v.hash(state),
+            Literal::UInt64(v) => v.hash(state),
+            Literal::Int8(v) => v.hash(state),
+            Literal::Int16(v) => v.hash(state),
+            Literal::Int32(v) => v.hash(state),
+            Literal::Int64(v) => v.hash(state),
+            Literal::Float32(v) => {
+                (if *v == 0.0 { 0.0 } else { *v }).to_bits().hash(state)
+            }
+            Literal::Float64(v) => {
+                (if *v == 0.0 { 0.0 } else { *v }).to_bits().hash(state)
+            }
+            Literal::Bool(v) => v.hash(state),
+            Literal::List(v) => v.hash(state),
+            Literal::Null => {}
+        }
     }
 }
 
@@ -481,12 +642,51 @@ impl Display for Literal {
             Literal::Bool(v) => v.fmt(f),
             Literal::String(v) => v.fmt(f),
             Literal::DateTime(v) => v.fmt(f),
+            Literal::List(v) => write!(
+                f,
+                "[{}]",
+                v.iter()
+                    .map(|l| l.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
             Literal::Null => "null".fmt(f),
         }
     }
 }
 
 impl Literal {
+    /// the value converted to the wider numeric type, None if the type of the value
+    /// cannot be widened to it
+    pub fn widen(&self, data_type: &DataType) -> Option<Literal> {
+        if !self.data_type().can_widen_to(data_type) {
+            return None;
+        }
+        let integer = match self {
+            Literal::UInt8(v) => i128::from(*v),
+            Literal::UInt16(v) => i128::from(*v),
+            Literal::UInt32(v) => i128::from(*v),
+            Literal::UInt64(v) => i128::from(*v),
+            Literal::Int8(v) => i128::from(*v),
+            Literal::Int16(v) => i128::from(*v),
+            Literal::Int32(v) => i128::from(*v),
+            Literal::Int64(v) => i128::from(*v),
+            Literal::Float32(v) => return Some(Literal::Float64(f64::from(*v))),
+            _ => return None,
+        };
+        match data_type {
+            DataType::UInt16 => u16::try_from(integer).ok().map(Literal::UInt16),
+            DataType::UInt32 => u32::try_from(integer).ok().map(Literal::UInt32),
+            DataType::UInt64 => u64::try_from(integer).ok().map(Literal::UInt64),
+            DataType::Int16 => i16::try_from(integer).ok().map(Literal::Int16),
+            DataType::Int32 => i32::try_from(integer).ok().map(Literal::Int32),
+            DataType::Int64 => i64::try_from(integer).ok().map(Literal::Int64),
+            DataType::Float32 => Some(Literal::Float32(integer as f32)),
+            DataType::Float64 => Some(Literal::Float64(integer as f64)),
+            _ => None,
+        }
+    }
+
     pub fn as_bool(&self) -> DBResult<bool> {
         match self {
             Literal::Bool(v) => Ok(*v),
@@ -511,6 +711,12 @@ impl Literal {
             Literal::Bool(_) => DataType::Boolean,
             Literal::String(_) => DataType::String,
             Literal::DateTime(_) => DataType::DateTime,
+            Literal::List(v) => DataType::List(Box::new(
+                v.iter()
+                    .map(|l| l.data_type())
+                    .find(|t| *t != DataType::Unknown)
+                    .unwrap_or(DataType::Unknown),
+            )),
             Literal::Null => DataType::Unknown,
         }
     }
@@ -536,7 +742,9 @@ impl Literal {
             }
             Literal::UnResolvedString(v) => match data_type {
                 DataType::String => Ok(Some(Literal::String(v.to_string()))),
-                DataType::DateTime => Ok(Some(Literal::DateTime(v.to_string()))),
+                DataType::DateTime => Ok(Some(Literal::DateTime(
+                    parse_datetime(v).unwrap_or_else(|| v.to_string()),
+                ))),
                 _ => Ok(None),
             },
             // TODO: add support for more castings
@@ -1,23 +1,38 @@
 mod agg_function;
+mod scalar_function;
 
 use core::fmt;
-use std::{collections::HashMap, rc::Rc};
+use std::{collections::HashMap, sync::Arc};
 
 use crate::{
-    aggregators::{Aggregator, AvgAgg, CountAgg, MaxAgg, MinAgg, SumAgg},
+    aggregators::{
+        Aggregator, AnyValueAgg, ArrayAgg, AvgAgg, BoolAgg, CountAgg, FirstLastAgg,
+        MaxAgg, MinAgg, SkipNullsAgg, StringAgg, SumAgg,
+    },
     data_types::DataType,
-    expressions::Expression,
+    expressions::{AggregateClauses, Expression, Literal},
     DBError, DBResult,
 };
 
-use self::agg_function::AggFunction;
+use self::{
+    agg_function::{AggFunction, AggregatorBuilder, DataTypeExtractor},
+    scalar_function::{ScalarEvaluator, ScalarFunction},
+};
 
-pub trait Function: std::fmt::Debug {
+pub trait Function: std::fmt::Debug + Send + Sync {
     /// TODO: consider remove this method
     fn is_aggregator(&self) -> bool;
 
     fn aggregator(&self) -> DBResult<Box<dyn Aggregator>>;
 
+    /// evaluate a scalar function with the evaluated args
+    fn eval(&self, _args: &[Literal]) -> DBResult<Literal> {
+        Err(DBError::InterpretingError(format!(
+            "{} cannot be evaluated as a scalar function",
+            self.name()
+        )))
+    }
+
     fn to_expr_string(&self) -> String {
         let args = self
             .args()
@@ -35,7 +50,7 @@ pub trait Function: std::fmt::Debug {
     fn data_type(&self) -> DataType;
 
     /// TODO: the return signature is wired
-    fn with_args(&self, args: Vec<Expression>) -> DBResult<Rc<dyn Function>>;
+    fn with_args(&self, args: Vec<Expression>) -> DBResult<Arc<dyn Function>>;
 }
 
 #[derive(Debug)]
@@ -73,6 +88,41 @@ impl FunctionsRegistry {
             "min".to_string(),
             FunctionBuilder::new_aggregator(build_min_fn),
         );
+        functions.insert(
+            "string_agg".to_string(),
+            FunctionBuilder::new_aggregator(build_string_agg_fn),
+        );
+        functions.insert(
+            "array_agg".to_string(),
+            FunctionBuilder::new_aggregator(build_array_agg_fn),
+        );
+        functions.insert(
+            "bool_and".to_string(),
+            FunctionBuilder::new_aggregator(build_bool_and_fn),
+        );
+        functions.insert(
+            "every".to_string(),
+            FunctionBuilder::new_aggregator(build_bool_and_fn),
+        );
+        functions.insert(
+            "bool_or".to_string(),
+            FunctionBuilder::new_aggregator(build_bool_or_fn),
+        );
+        functions.insert(
+            "any_value".to_string(),
+            FunctionBuilder::new_aggregator(build_any_value_fn),
+        );
+        functions.insert(
+            "first".to_string(),
+            FunctionBuilder::new_aggregator(build_first_fn),
+        );
+        functions.insert(
+            "last".to_string(),
+            FunctionBuilder::new_aggregator(build_last_fn),
+        );
+        functions.insert("upper".to_string(), FunctionBuilder::new(build_upper_fn));
+        functions.insert("lower".to_string(), FunctionBuilder::new(build_lower_fn));
+        functions.insert("length".to_string(), FunctionBuilder::new(build_length_fn));
         Self { functions }
     }
 
@@ -80,9 +130,10 @@ impl FunctionsRegistry {
         &self,
         name: &str,
         args: &[Expression],
-    ) -> DBResult<Option<Rc<dyn Function>>> {
+        clauses: &AggregateClauses,
+    ) -> DBResult<Option<Arc<dyn Function>>> {
         match self.functions.get(name) {
-            Some(fn_builder) => fn_builder.build(args).map(Some),
+            Some(fn_builder) => fn_builder.build(args, clauses).map(Some),
             None => Ok(None),
         }
     }
@@ -100,8 +151,10 @@ impl FunctionsRegistry {
     }
 }
 
+type BuildFunction = fn(&[Expression], &AggregateClauses) -> DBResult<Arc<dyn Function>>;
+
 pub struct FunctionBuilder {
-    builder: fn(&[Expression]) -> DBResult<Rc<dyn Function>>,
+    builder: BuildFunction,
     is_aggregator: bool,
 }
 
@@ -112,107 +165,360 @@ impl fmt::Debug for FunctionBuilder {
 }
 
 impl FunctionBuilder {
-    pub fn _new(builder: fn(&[Expression]) -> DBResult<Rc<dyn Function>>) -> Self {
+    pub fn new(builder: BuildFunction) -> Self {
         Self {
             builder,
             is_aggregator: false,
         }
     }
-    pub fn new_aggregator(
-        builder: fn(&[Expression]) -> DBResult<Rc<dyn Function>>,
-    ) -> Self {
+    pub fn new_aggregator(builder: BuildFunction) -> Self {
         Self {
             builder,
             is_aggregator: true,
         }
     }
 
-    pub fn build(&self, args: &[Expression]) -> DBResult<Rc<dyn Function>> {
-        (self.builder)(args)
+    pub fn build(
+        &self,
+        args: &[Expression],
+        clauses: &AggregateClauses,
+    ) -> DBResult<Arc<dyn Function>> {
+        (self.builder)(args, clauses)
     }
 }
 
-fn build_sum_fn(args: &[Expression]) -> DBResult<Rc<dyn Function>> {
-    if args.len() == 1 {
-        let arg = args[0].clone();
+/// build an aggregate function after validating the number of args
+fn build_agg_fn(
+    name: &str,
+    num_args: usize,
+    args: &[Expression],
+    clauses: &AggregateClauses,
+    data_type_extractor: Arc<DataTypeExtractor>,
+    agg_builder: Arc<AggregatorBuilder>,
+) -> DBResult<Arc<dyn Function>> {
+    if args.len() == num_args {
         let function = AggFunction::new(
-            "sum",
-            Rc::new(|arg| arg.data_type()),
-            arg,
-            Rc::new(|arg| {
-                SumAgg::new(arg).map(|agg| Box::new(agg) as Box<dyn Aggregator>)
-            }),
+            name,
+            data_type_extractor,
+            args.to_vec(),
+            clauses.clone(),
+            agg_builder,
         );
-        Ok(Rc::new(function) as Rc<dyn Function>)
+        Ok(Arc::new(function) as Arc<dyn Function>)
     } else {
-        Err(DBError::Unknown("invalid args".to_string()))
+        Err(DBError::Unknown(format!(
+            "invalid args: {name} expects {num_args} args but got {}",
+            args.len()
+        )))
     }
 }
 
-fn build_avg_fn(args: &[Expression]) -> DBResult<Rc<dyn Function>> {
-    if args.len() == 1 {
-        let arg = args[0].clone();
-        let function = AggFunction::new(
-            "avg",
-            Rc::new(|_| DataType::Float64),
-            arg,
-            Rc::new(|arg| {
-                AvgAgg::new(arg).map(|agg| Box::new(agg) as Box<dyn Aggregator>)
-            }),
-        );
-        Ok(Rc::new(function) as Rc<dyn Function>)
-    } else {
-        Err(DBError::Unknown("invalid args".to_string()))
+/// build an aggregate function whose result does not depend on the order of inputs,
+/// which is not allowed to have ORDER BY
+fn build_unordered_agg_fn(
+    name: &str,
+    num_args: usize,
+    args: &[Expression],
+    clauses: &AggregateClauses,
+    data_type_extractor: Arc<DataTypeExtractor>,
+    agg_builder: Arc<AggregatorBuilder>,
+) -> DBResult<Arc<dyn Function>> {
+    if !clauses.order_by().is_empty() {
+        return Err(DBError::ParserError(format!(
+            "ORDER BY is not supported by the aggregate function {name}"
+        )));
     }
+    build_agg_fn(
+        name,
+        num_args,
+        args,
+        clauses,
+        data_type_extractor,
+        agg_builder,
+    )
 }
 
-fn build_count_fn(args: &[Expression]) -> DBResult<Rc<dyn Function>> {
-    if args.len() == 1 {
-        let arg = args[0].clone();
-        let function = AggFunction::new(
-            "count",
-            Rc::new(|_| DataType::UInt64),
-            arg,
-            Rc::new(|arg| {
-                CountAgg::new(arg).map(|agg| Box::new(agg) as Box<dyn Aggregator>)
-            }),
-        );
-        Ok(Rc::new(function) as Rc<dyn Function>)
+/// build a scalar function after validating the number of args. Aggregate-level
+/// clauses of scalar functions are rejected by `ResolveFunctionsRule`
+fn build_scalar_fn(
+    name: &str,
+    num_args: usize,
+    args: &[Expression],
+    data_type_extractor: Arc<DataTypeExtractor>,
+    evaluator: Arc<ScalarEvaluator>,
+) -> DBResult<Arc<dyn Function>> {
+    if args.len() == num_args {
+        let function =
+            ScalarFunction::new(name, data_type_extractor, args.to_vec(), evaluator);
+        Ok(Arc::new(function) as Arc<dyn Function>)
     } else {
-        Err(DBError::Unknown("invalid args".to_string()))
+        Err(DBError::Unknown(format!(
+            "invalid args: {name} expects {num_args} args but got {}",
+            args.len()
+        )))
     }
 }
 
-fn build_max_fn(args: &[Expression]) -> DBResult<Rc<dyn Function>> {
-    if args.len() == 1 {
-        let arg = args[0].clone();
-        let function = AggFunction::new(
-            "max",
-            Rc::new(|arg| arg.data_type()),
-            arg,
-            Rc::new(|arg| {
-                MaxAgg::new(arg).map(|agg| Box::new(agg) as Box<dyn Aggregator>)
-            }),
-        );
-        Ok(Rc::new(function) as Rc<dyn Function>)
-    } else {
-        Err(DBError::Unknown("invalid args".to_string()))
-    }
+/// wrap an arithmetic aggregator so that it ignores null inputs and results in null
+/// when no input is aggregated
+fn skip_nulls(arg: &Expression, aggregator: Box<dyn Aggregator>) -> Box<dyn Aggregator> {
+    Box::new(SkipNullsAgg::new(arg, aggregator))
 }
 
-fn build_min_fn(args: &[Expression]) -> DBResult<Rc<dyn Function>> {
-    if args.len() == 1 {
-        let arg = args[0].clone();
-        let function = AggFunction::new(
-            "min",
-            Rc::new(|arg| arg.data_type()),
-            arg,
-            Rc::new(|arg| {
-                MinAgg::new(arg).map(|agg| Box::new(agg) as Box<dyn Aggregator>)
-            }),
-        );
-        Ok(Rc::new(function) as Rc<dyn Function>)
-    } else {
-        Err(DBError::Unknown("invalid args".to_string()))
+fn build_sum_fn(
+    args: &[Expression],
+    clauses: &AggregateClauses,
+) -> DBResult<Arc<dyn Function>> {
+    build_unordered_agg_fn(
+        "sum",
+        1,
+        args,
+        clauses,
+        Arc::new(|args| args[0].data_type()),
+        Arc::new(|args, _| {
+            SumAgg::new(&args[0]).map(|agg| skip_nulls(&args[0], Box::new(agg)))
+        }),
+    )
+}
+
+fn build_avg_fn(
+    args: &[Expression],
+    clauses: &AggregateClauses,
+) -> DBResult<Arc<dyn Function>> {
+    build_unordered_agg_fn(
+        "avg",
+        1,
+        args,
+        clauses,
+        Arc::new(|_| DataType::Float64),
+        Arc::new(|args, _| {
+            AvgAgg::new(&args[0]).map(|agg| skip_nulls(&args[0], Box::new(agg)))
+        }),
+    )
+}
+
+fn build_count_fn(
+    args: &[Expression],
+    clauses: &AggregateClauses,
+) -> DBResult<Arc<dyn Function>> {
+    build_unordered_agg_fn(
+        "count",
+        1,
+        args,
+        clauses,
+        Arc::new(|_| DataType::UInt64),
+        Arc::new(|args, _| {
+            CountAgg::new(&args[0]).map(|agg| Box::new(agg) as Box<dyn Aggregator>)
+        }),
+    )
+}
+
+fn build_max_fn(
+    args: &[Expression],
+    clauses: &AggregateClauses,
+) -> DBResult<Arc<dyn Function>> {
+    build_unordered_agg_fn(
+        "max",
+        1,
+        args,
+        clauses,
+        Arc::new(|args| args[0].data_type()),
+        Arc::new(|args, _| {
+            MaxAgg::new(&args[0]).map(|agg| skip_nulls(&args[0], Box::new(agg)))
+        }),
+    )
+}
+
+fn build_min_fn(
+    args: &[Expression],
+    clauses: &AggregateClauses,
+) -> DBResult<Arc<dyn Function>> {
+    build_unordered_agg_fn(
+        "min",
+        1,
+        args,
+        clauses,
+        Arc::new(|args| args[0].data_type()),
+        Arc::new(|args, _| {
+            MinAgg::new(&args[0]).map(|agg| skip_nulls(&args[0], Box::new(agg)))
+        }),
+    )
+}
+
+fn build_string_agg_fn(
+    args: &[Expression],
+    clauses: &AggregateClauses,
+) -> DBResult<Arc<dyn Function>> {
+    build_agg_fn(
+        "string_agg",
+        2,
+        args,
+        clauses,
+        Arc::new(|_| DataType::String),
+        Arc::new(|args, order_by| {
+            StringAgg::new(&args[0], &args[1], order_by)
+                .map(|agg| Box::new(agg) as Box<dyn Aggregator>)
+        }),
+    )
+}
+
+fn build_array_agg_fn(
+    args: &[Expression],
+    clauses: &AggregateClauses,
+) -> DBResult<Arc<dyn Function>> {
+    build_agg_fn(
+        "array_agg",
+        1,
+        args,
+        clauses,
+        Arc::new(|args| DataType::List(Box::new(args[0].data_type()))),
+        Arc::new(|args, order_by| {
+            ArrayAgg::new(&args[0], order_by)
+                .map(|agg| Box::new(agg) as Box<dyn Aggregator>)
+        }),
+    )
+}
+
+fn build_bool_and_fn(
+    args: &[Expression],
+    clauses: &AggregateClauses,
+) -> DBResult<Arc<dyn Function>> {
+    build_unordered_agg_fn(
+        "bool_and",
+        1,
+        args,
+        clauses,
+        Arc::new(|_| DataType::Boolean),
+        Arc::new(|args, _| {
+            BoolAgg::and(&args[0]).map(|agg| Box::new(agg) as Box<dyn Aggregator>)
+        }),
+    )
+}
+
+fn build_bool_or_fn(
+    args: &[Expression],
+    clauses: &AggregateClauses,
+) -> DBResult<Arc<dyn Function>> {
+    build_unordered_agg_fn(
+        "bool_or",
+        1,
+        args,
+        clauses,
+        Arc::new(|_| DataType::Boolean),
+        Arc::new(|args, _| {
+            BoolAgg::or(&args[0]).map(|agg| Box::new(agg) as Box<dyn Aggregator>)
+        }),
+    )
+}
+
+fn build_any_value_fn(
+    args: &[Expression],
+    clauses: &AggregateClauses,
+) -> DBResult<Arc<dyn Function>> {
+    build_unordered_agg_fn(
+        "any_value",
+        1,
+        args,
+        clauses,
+        Arc::new(|args| args[0].data_type()),
+        Arc::new(|args, _| {
+            AnyValueAgg::new(&args[0]).map(|agg| Box::new(agg) as Box<dyn Aggregator>)
+        }),
+    )
+}
+
+fn build_first_fn(
+    args: &[Expression],
+    clauses: &AggregateClauses,
+) -> DBResult<Arc<dyn Function>> {
+    build_agg_fn(
+        "first",
+        1,
+        args,
+        clauses,
+        Arc::new(|args| args[0].data_type()),
+        Arc::new(|args, order_by| {
+            FirstLastAgg::first(&args[0], order_by)
+                .map(|agg| Box::new(agg) as Box<dyn Aggregator>)
+        }),
+    )
+}
+
+fn build_last_fn(
+    args: &[Expression],
+    clauses: &AggregateClauses,
+) -> DBResult<Arc<dyn Function>> {
+    build_agg_fn(
+        "last",
+        1,
+        args,
+        clauses,
+        Arc::new(|args| args[0].data_type()),
+        Arc::new(|args, order_by| {
+            FirstLastAgg::last(&args[0], order_by)
+                .map(|agg| Box::new(agg) as Box<dyn Aggregator>)
+        }),
+    )
+}
+
+/// evaluate a string function, null results in null
+fn eval_string_fn(
+    name: &str,
+    arg: &Literal,
+    func: impl Fn(&str) -> Literal,
+) -> DBResult<Literal> {
+    match arg {
+        Literal::Null => Ok(Literal::Null),
+        Literal::String(v) | Literal::UnResolvedString(v) => Ok(func(v)),
+        _ => Err(DBError::InterpretingError(format!(
+            "{name} expects a string but got {arg}"
+        ))),
     }
 }
+
+fn build_upper_fn(
+    args: &[Expression],
+    _clauses: &AggregateClauses,
+) -> DBResult<Arc<dyn Function>> {
+    build_scalar_fn(
+        "upper",
+        1,
+        args,
+        Arc::new(|_| DataType::String),
+        Arc::new(|args| {
+            eval_string_fn("upper", &args[0], |v| Literal::String(v.to_uppercase()))
+        }),
+    )
+}
+
+fn build_lower_fn(
+    args: &[Expression],
+    _clauses: &AggregateClauses,
+) -> DBResult<Arc<dyn Function>> {
+    build_scalar_fn(
+        "lower",
+        1,
+        args,
+        Arc::new(|_| DataType::String),
+        Arc::new(|args| {
+            eval_string_fn("lower", &args[0], |v| Literal::String(v.to_lowercase()))
+        }),
+    )
+}
+
+fn build_length_fn(
+    args: &[Expression],
+    _clauses: &AggregateClauses,
+) -> DBResult<Arc<dyn Function>> {
+    build_scalar_fn(
+        "length",
+        1,
+        args,
+        Arc::new(|_| DataType::Int64),
+        Arc::new(|args| {
+            eval_string_fn("length", &args[0], |v| {
+                Literal::Int64(v.chars().count() as i64)
+            })
+        }),
+    )
+}
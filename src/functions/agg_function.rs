@@ -1,21 +1,26 @@
-use std::rc::Rc;
+use std::sync::Arc;
 
 use crate::{
-    aggregators::Aggregator, data_types::DataType, expressions::Expression, DBError,
-    DBResult,
+    aggregators::{Aggregator, FilteredAgg},
+    data_types::DataType,
+    expressions::{function_expr_string, AggregateClauses, Expression},
+    logical_plans::SortOption,
+    DBError, DBResult,
 };
 
 use super::Function;
 
-pub type AggregatorBuilder = dyn Fn(&Expression) -> DBResult<Box<dyn Aggregator>>;
-pub type DataTypeExtractor = dyn Fn(&Expression) -> DataType;
+pub type AggregatorBuilder =
+    dyn Fn(&[Expression], &[SortOption]) -> DBResult<Box<dyn Aggregator>> + Send + Sync;
+pub type DataTypeExtractor = dyn Fn(&[Expression]) -> DataType + Send + Sync;
 
 #[derive(Clone)]
 pub struct AggFunction {
     name: String,
-    data_type_extractor: Rc<DataTypeExtractor>,
-    arg: Expression,
-    agg_builder: Rc<AggregatorBuilder>,
+    data_type_extractor: Arc<DataTypeExtractor>,
+    args: Vec<Expression>,
+    clauses: AggregateClauses,
+    agg_builder: Arc<AggregatorBuilder>,
 }
 
 impl std::fmt::Debug for AggFunction {
@@ -23,7 +28,8 @@ impl std::fmt::Debug for AggFunction {
         f.debug_struct("AggFunction")
             .field("name", &self.name)
             .field("data_type", &self.data_type())
-            .field("arg", &self.arg)
+            .field("args", &self.args)
+            .field("clauses", &self.clauses)
             .finish()
     }
 }
@@ -31,14 +37,16 @@ impl std::fmt::Debug for AggFunction {
 impl AggFunction {
     pub fn new(
         name: &str,
-        data_type_extractor: Rc<DataTypeExtractor>,
-        arg: Expression,
-        agg_builder: Rc<AggregatorBuilder>,
+        data_type_extractor: Arc<DataTypeExtractor>,
+        args: Vec<Expression>,
+        clauses: AggregateClauses,
+        agg_builder: Arc<AggregatorBuilder>,
     ) -> Self {
         Self {
             name: name.to_owned(),
             data_type_extractor,
-            arg,
+            args,
+            clauses,
             agg_builder,
         }
     }
@@ -50,28 +58,40 @@ impl Function for AggFunction {
     }
 
     fn aggregator(&self) -> DBResult<Box<dyn Aggregator>> {
-        (self.agg_builder)(&self.arg)
+        let aggregator = (self.agg_builder)(&self.args, self.clauses.order_by())?;
+        match self.clauses.filter() {
+            Some(filter) => Ok(Box::new(FilteredAgg::new(filter, aggregator))),
+            None => Ok(aggregator),
+        }
+    }
+
+    fn to_expr_string(&self) -> String {
+        function_expr_string(&self.name, &self.args, &self.clauses)
     }
 
     fn name(&self) -> String {
         self.name.clone()
     }
 
+    /// args followed by the expressions of aggregate-level clauses, so that they are
+    /// resolved together
     fn args(&self) -> Vec<&Expression> {
-        vec![&self.arg]
+        self.args.iter().chain(self.clauses.exprs()).collect()
     }
 
     fn data_type(&self) -> DataType {
-        (self.data_type_extractor)(&self.arg)
+        (self.data_type_extractor)(&self.args)
     }
 
-    fn with_args(&self, args: Vec<Expression>) -> DBResult<Rc<dyn Function>> {
-        if args.len() == 1 {
-            let arg = args.into_iter().next().unwrap();
-            Ok(Rc::new(AggFunction {
+    fn with_args(&self, args: Vec<Expression>) -> DBResult<Arc<dyn Function>> {
+        if args.len() == self.args.len() + self.clauses.exprs().len() {
+            let mut args = args;
+            let clause_exprs = args.split_off(self.args.len());
+            Ok(Arc::new(AggFunction {
                 name: self.name.clone(),
                 data_type_extractor: self.data_type_extractor.clone(),
-                arg,
+                args,
+                clauses: self.clauses.with_exprs(clause_exprs),
                 agg_builder: self.agg_builder.clone(),
             }))
         } else {
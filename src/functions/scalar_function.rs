@@ -0,0 +1,88 @@
+use std::sync::Arc;
+
+use crate::{
+    aggregators::Aggregator,
+    data_types::DataType,
+    expressions::{Expression, Literal},
+    DBError, DBResult,
+};
+
+use super::{agg_function::DataTypeExtractor, Function};
+
+pub type ScalarEvaluator = dyn Fn(&[Literal]) -> DBResult<Literal> + Send + Sync;
+
+/// ScalarFunction evaluates a single value from the evaluated args of each row, e.g.
+/// `upper(name)`.
+#[derive(Clone)]
+pub struct ScalarFunction {
+    name: String,
+    data_type_extractor: Arc<DataTypeExtractor>,
+    args: Vec<Expression>,
+    evaluator: Arc<ScalarEvaluator>,
+}
+
+impl std::fmt::Debug for ScalarFunction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ScalarFunction")
+            .field("name", &self.name)
+            .field("data_type", &self.data_type())
+            .field("args", &self.args)
+            .finish()
+    }
+}
+
+impl ScalarFunction {
+    pub fn new(
+        name: &str,
+        data_type_extractor: Arc<DataTypeExtractor>,
+        args: Vec<Expression>,
+        evaluator: Arc<ScalarEvaluator>,
+    ) -> Self {
+        Self {
+            name: name.to_owned(),
+            data_type_extractor,
+            args,
+            evaluator,
+        }
+    }
+}
+
+impl Function for ScalarFunction {
+    fn is_aggregator(&self) -> bool {
+        false
+    }
+
+    fn aggregator(&self) -> DBResult<Box<dyn Aggregator>> {
+        Err(DBError::Unknown(format!(
+            "{} is not an aggregator",
+            self.name
+        )))
+    }
+
+    fn eval(&self, args: &[Literal]) -> DBResult<Literal> {
+        (self.evaluator)(args)
+    }
+
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn args(&self) -> Vec<&Expression> {
+        self.args.iter().collect()
+    }
+
+    fn data_type(&self) -> DataType {
+        (self.data_type_extractor)(&self.args)
+    }
+
+    fn with_args(&self, args: Vec<Expression>) -> DBResult<Arc<dyn Function>> {
+        if args.len() == self.args.len() {
+            Ok(Arc::new(ScalarFunction {
+                args,
+                ..self.clone()
+            }))
+        } else {
+            Err(DBError::Unknown("invalid args".to_string()))
+        }
+    }
+}
@@ -1,14 +1,181 @@
-use sqlparser::ast::Statement;
+use std::sync::{Arc, RwLock};
 
-use crate::{DBResult, ResultSet};
+use sqlparser::ast::{ObjectName, Statement};
 
+use crate::{
+    data_types::DataType,
+    expressions::{Expression, Literal},
+    interpreter::Interpreter,
+    logical_plans::{LogicalPlan, ScanSource},
+    optimizer::{Optimizer, OptimizerContextForExpr},
+    parser::{ast_expr_to_plan_expr, contains_aggregators},
+    row::Row,
+    tables::{inmem::InMemTable, FieldInfo, RelationSchema, Table, TableMeta},
+    Catalog, DBError, DBResult, ResultSet,
+};
+
+mod alter_table_handler;
+mod copy_handler;
 mod create_table_handler;
+mod create_view_handler;
+mod delete_handler;
+mod drop_handler;
+mod explain_handler;
 mod insert_handler;
+mod schema_handler;
 mod select_handler;
+mod show_handler;
+mod update_handler;
+pub use alter_table_handler::AlterTableHandler;
+pub use copy_handler::CopyHandler;
 pub use create_table_handler::CreateTableHandler;
+pub use create_view_handler::CreateViewHandler;
+pub use delete_handler::DeleteHandler;
+pub use drop_handler::DropHandler;
+pub use explain_handler::ExplainHandler;
 pub use insert_handler::InsertHandler;
+pub use schema_handler::SchemaHandler;
 pub use select_handler::SelectHandler;
+pub use show_handler::ShowHandler;
+pub use update_handler::UpdateHandler;
 
 pub trait QueryHandler {
     fn handle(&self, statement: Statement) -> DBResult<ResultSet>;
 }
+
+/// materialize the results of the query into a new InMemTable, the schema is derived
+/// from the query plan
+fn materialize_query(
+    select_handler: &SelectHandler,
+    logical_plan: LogicalPlan,
+) -> DBResult<InMemTable> {
+    let rs = select_handler.execute_plan(select_handler.optimize_plan(logical_plan)?)?;
+    let mut rows = rs.rows;
+
+    // literals without data types, e.g. `'x'` in `select 'x' as note from t`, are
+    // resolved into their default data types
+    let mut fields = Vec::with_capacity(rs.schema.num_fields());
+    for (idx, field) in rs.schema.get_fields().iter().enumerate() {
+        let mut data_type = field.data_type().clone();
+        if data_type == DataType::Unknown {
+            for row in rows.iter_mut() {
+                let value = row.get_field(idx)?;
+                let value = resolve_literal(&value, &DataType::Unknown)?.unwrap_or(value);
+                if data_type == DataType::Unknown && value != Literal::Null {
+                    data_type = value.data_type();
+                }
+                row.update_field(idx, value)?;
+            }
+        }
+        fields.push(FieldInfo::new(field.name().to_owned(), data_type));
+    }
+
+    let meta = TableMeta::new(RelationSchema::new(fields));
+    meta.validate()?;
+    let mut table = InMemTable::new(meta);
+    table.insert_data(rows)?;
+    Ok(table)
+}
+
+/// resolve expressions of a statement modifying the given table, e.g. the predicate of
+/// DELETE, by optimizing a projection of them over the table
+pub(crate) fn resolve_table_exprs(
+    catalog: &Arc<RwLock<Catalog>>,
+    table_name: &ObjectName,
+    exprs: Vec<Expression>,
+    statement_name: &str,
+) -> DBResult<Vec<Expression>> {
+    if contains_aggregators(&exprs.iter().collect::<Vec<_>>())? {
+        return Err(DBError::ParserError(format!(
+            "aggregators are not allowed in {statement_name}."
+        )));
+    }
+    let plan = LogicalPlan::Projection {
+        expressions: exprs,
+        child: Box::new(LogicalPlan::UnResolvedScan {
+            source: ScanSource::Name(table_name.clone()),
+        }),
+    };
+    match Optimizer::new(Arc::clone(catalog)).optimize(plan)? {
+        LogicalPlan::Projection { expressions, .. } => Ok(expressions),
+        _ => Err(DBError::should_never_happen()),
+    }
+}
+
+/// evaluate a constant expression into a value of the given field, e.g. `1 + 2` or
+/// `upper('x')` in INSERT VALUES or DEFAULT clauses
+fn eval_constant_expr(
+    expr: &sqlparser::ast::Expr,
+    field: &FieldInfo,
+) -> DBResult<Literal> {
+    let expr = ast_expr_to_plan_expr(expr)?;
+    if contains_aggregators(&[&expr])? {
+        return Err(DBError::ParserError(format!(
+            "aggregators are not allowed in constant expression {expr}"
+        )));
+    }
+
+    // resolve literals with the field data type as a hint, so that `1 + 2` is evaluated
+    // as Int32 for an Int32 field
+    let context = OptimizerContextForExpr::new(RelationSchema::empty());
+    let expr = expr
+        .transform_bottom_up(&context, &mut |node, _context| match node {
+            Expression::Literal(literal) => {
+                Ok(resolve_literal(literal, field.data_type())?.map(Expression::Literal))
+            }
+            _ => Ok(None),
+        })?
+        .unwrap_or(expr);
+    let expr = Optimizer::optimize_expr(expr, RelationSchema::empty())?;
+
+    let mut unresolved_field = None;
+    expr.transform_top_down(&context, &mut |node, _context| {
+        if let Expression::UnResolvedFieldRef(name) = node {
+            unresolved_field.get_or_insert(name.clone());
+        }
+        Ok(None)
+    })?;
+    if let Some(name) = unresolved_field {
+        return Err(DBError::ParserError(format!(
+            "column {name} cannot be referenced in constant expression {expr}"
+        )));
+    }
+
+    let value = Interpreter::eval(&expr, &Row::new(vec![]))?;
+    coerce_value(value, field)
+}
+
+/// resolve an unresolved literal with the data type hint, or into its default data type
+/// if the hint doesn't fit, e.g. a string literal in `length('abc')` for an Int64 field
+fn resolve_literal(literal: &Literal, type_hint: &DataType) -> DBResult<Option<Literal>> {
+    let default_type = match literal {
+        Literal::UnResolvedNumber(_) => DataType::Int64,
+        Literal::UnResolvedString(_) => DataType::String,
+        _ => return Ok(None),
+    };
+    match literal.cast_or_maintain_precision(type_hint.clone())? {
+        Some(resolved) => Ok(Some(resolved)),
+        None => literal.cast_or_maintain_precision(default_type),
+    }
+}
+
+/// check a value to be stored in the given field, unresolved literals are casted into
+/// the field data type
+fn coerce_value(value: Literal, field: &FieldInfo) -> DBResult<Literal> {
+    let value = match value {
+        Literal::Null => return Ok(value),
+        Literal::UnResolvedNumber(_) | Literal::UnResolvedString(_) => value
+            .cast_or_maintain_precision(field.data_type().clone())?
+            .unwrap_or(value),
+        _ => value.widen(field.data_type()).unwrap_or(value),
+    };
+    if value.data_type() == *field.data_type() {
+        Ok(value)
+    } else {
+        Err(DBError::ParserError(format!(
+            "cannot store {value} into column {} of {}",
+            field.name(),
+            field.data_type()
+        )))
+    }
+}
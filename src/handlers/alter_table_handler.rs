@@ -0,0 +1,84 @@
+use std::sync::{Arc, RwLock};
+
+use sqlparser::ast::{AlterTableOperation, ColumnOption, Ident, Statement};
+
+use crate::{Catalog, DBError, DBResult, ResultSet};
+
+use super::{create_table_handler::column_def_to_field, QueryHandler};
+
+pub struct AlterTableHandler {
+    catalog: Arc<RwLock<Catalog>>,
+}
+
+impl QueryHandler for AlterTableHandler {
+    fn handle(&self, statement: Statement) -> DBResult<ResultSet> {
+        match statement {
+            Statement::AlterTable { name, operation } => {
+                self.alter_table(name.to_string(), operation)?;
+                Ok(ResultSet::empty())
+            }
+            _ => Err(DBError::should_never_happen()),
+        }
+    }
+}
+
+impl AlterTableHandler {
+    pub(crate) fn new(catalog: Arc<RwLock<Catalog>>) -> Self {
+        Self { catalog }
+    }
+
+    fn alter_table(&self, name: String, operation: AlterTableOperation) -> DBResult<()> {
+        let catalog = RwLock::read(&self.catalog).map_err(|_e| {
+            DBError::Unknown("access catalog read lock failed.".to_string())
+        })?;
+        if let AlterTableOperation::RenameTable { table_name } = operation {
+            return catalog.rename_table(&name, table_name.to_string());
+        }
+
+        let table = catalog.try_get_table(&name)?;
+        let mut table = RwLock::write(Arc::as_ref(&table)).map_err(|_| {
+            DBError::Unknown("Access write lock of table failed!".to_string())
+        })?;
+        let schema = table.get_table_meta().get_schema().clone();
+        let column_index = |column: &Ident| {
+            schema
+                .index_of(&column.value)
+                .ok_or(DBError::ParserError(format!("column {column} not found")))
+        };
+        match operation {
+            AlterTableOperation::AddColumn { column_def, .. } => {
+                let has_constraints = column_def.options.iter().any(|option| {
+                    !matches!(
+                        option.option,
+                        ColumnOption::Null | ColumnOption::Default(_)
+                    )
+                });
+                if has_constraints {
+                    return Err(DBError::ParserError(format!(
+                        "constraints of column {} are not supported by ADD COLUMN",
+                        column_def.name
+                    )));
+                }
+                let (field, default) = column_def_to_field(column_def)?;
+                table.add_column(field, default)
+            }
+            AlterTableOperation::DropColumn {
+                column_name,
+                if_exists,
+                ..
+            } => match column_index(&column_name) {
+                Ok(idx) => table.drop_column(idx),
+                Err(_) if if_exists => Ok(()),
+                Err(e) => Err(e),
+            },
+            AlterTableOperation::RenameColumn {
+                old_column_name,
+                new_column_name,
+            } => table
+                .rename_column(column_index(&old_column_name)?, new_column_name.value),
+            _ => Err(DBError::ParserError(format!(
+                "ALTER TABLE operation not supported: {operation}"
+            ))),
+        }
+    }
+}
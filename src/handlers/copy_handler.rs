@@ -0,0 +1,371 @@
+use std::sync::{Arc, RwLock};
+
+use sqlparser::ast::{CopySource, Ident, ObjectName, Query, Statement};
+
+use crate::{
+    expressions::Literal,
+    parser::{parse_sql, CopyStatement, CrackDBStatement, FileOption},
+    row::Row,
+    tables::{
+        constraints::{RowChecker, TableConstraint, UniqueIndex},
+        csv::{
+            parse_csv_field, CsvOptions, CsvRecordReader, CsvWriteOptions, CsvWriter,
+            InvalidValuePolicy,
+        },
+        json::NdjsonWriter,
+        parquet::ParquetWriter,
+        RowWriter, Table, TableMeta,
+    },
+    Catalog, DBError, DBResult, ResultSet,
+};
+
+use super::{InsertHandler, SelectHandler};
+
+/// CopyHandler exports the results of a query, or the rows of a table, to a file by
+/// COPY TO, and loads rows of a csv file into a table by COPY FROM. Rows of COPY TO are
+/// streamed from the physical plan to the file one by one.
+pub struct CopyHandler {
+    catalog: Arc<RwLock<Catalog>>,
+    select_handler: SelectHandler,
+}
+
+/// the file formats COPY TO writes
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CopyFormat {
+    Csv,
+    Ndjson,
+    Parquet,
+}
+
+impl CopyFormat {
+    fn from_name(name: &str) -> DBResult<Self> {
+        match name.to_lowercase().as_str() {
+            "csv" => Ok(CopyFormat::Csv),
+            "ndjson" | "json" | "jsonl" => Ok(CopyFormat::Ndjson),
+            "parquet" => Ok(CopyFormat::Parquet),
+            _ => Err(DBError::ParserError(format!(
+                "COPY format {name} is not supported"
+            ))),
+        }
+    }
+
+    /// the format by the extension of the path, csv by default
+    fn from_path(path: &str) -> Self {
+        path.rsplit_once('.')
+            .and_then(|(_, ext)| Self::from_name(ext).ok())
+            .unwrap_or(CopyFormat::Csv)
+    }
+}
+
+/// Options of COPY FROM.
+struct CopyFromOptions {
+    csv: CsvOptions,
+    /// whether bad rows are skipped instead of failing the COPY
+    skip_bad_rows: bool,
+    /// the maximum number of bad rows to skip, unlimited if None
+    reject_limit: Option<usize>,
+}
+
+impl CopyHandler {
+    pub fn new(catalog: Arc<RwLock<Catalog>>) -> Self {
+        let select_handler = SelectHandler::new(Arc::clone(&catalog));
+        Self {
+            catalog,
+            select_handler,
+        }
+    }
+
+    pub(crate) fn handle(&self, statement: CopyStatement) -> DBResult<ResultSet> {
+        match (statement.source, statement.to) {
+            (CopySource::Query(query), true) => {
+                self.copy_to(*query, &statement.path, &statement.options)
+            }
+            (
+                CopySource::Table {
+                    table_name,
+                    columns,
+                },
+                true,
+            ) => {
+                let query = Self::table_query(&table_name, &columns)?;
+                self.copy_to(query, &statement.path, &statement.options)
+            }
+            (
+                CopySource::Table {
+                    table_name,
+                    columns,
+                },
+                false,
+            ) => {
+                self.copy_from(&table_name, &columns, &statement.path, &statement.options)
+            }
+            (CopySource::Query(_), false) => Err(DBError::ParserError(
+                "COPY FROM does not support queries".to_owned(),
+            )),
+        }
+    }
+
+    /// the query of the columns of a table, or all columns if none is given
+    fn table_query(table_name: &ObjectName, columns: &[Ident]) -> DBResult<Query> {
+        let columns = match columns {
+            [] => "*".to_owned(),
+            columns => columns
+                .iter()
+                .map(|c| c.to_string())
+                .collect::<Vec<_>>()
+                .join(", "),
+        };
+        match parse_sql(&format!("SELECT {columns} FROM {table_name}"))?.pop() {
+            Some(CrackDBStatement::Sql(statement)) => match *statement {
+                Statement::Query(query) => Ok(*query),
+                _ => Err(DBError::should_never_happen()),
+            },
+            _ => Err(DBError::should_never_happen()),
+        }
+    }
+
+    fn copy_to(
+        &self,
+        query: Query,
+        path: &str,
+        options: &[FileOption],
+    ) -> DBResult<ResultSet> {
+        let mut format = CopyFormat::from_path(path);
+        let mut csv_options = CsvWriteOptions::default();
+        for option in options {
+            match option.name() {
+                "format" => format = CopyFormat::from_name(option.string_value()?)?,
+                "header" => csv_options.header = option.bool_value()?,
+                "delimiter" => csv_options.delimiter = option.char_value()?,
+                "quote" => csv_options.quote = option.char_value()?,
+                "null" => csv_options.null = option.string_value()?.to_owned(),
+                _ => {
+                    return Err(DBError::ParserError(format!(
+                        "COPY TO option {option} is not supported"
+                    )))
+                }
+            }
+        }
+        if let Some(option) = options
+            .iter()
+            .find(|o| o.name() != "format" && format != CopyFormat::Csv)
+        {
+            return Err(DBError::ParserError(format!(
+                "COPY option {option} is only supported by csv"
+            )));
+        }
+
+        let logical_plan = self.select_handler.optimize_query(query)?;
+        let mut physical_plan = self.select_handler.planning(logical_plan, false)?;
+        physical_plan.setup()?;
+        let schema = physical_plan.schema()?;
+        let mut writer: Box<dyn RowWriter> = match format {
+            CopyFormat::Csv => Box::new(CsvWriter::try_new(path, &schema, csv_options)?),
+            CopyFormat::Ndjson => Box::new(NdjsonWriter::try_new(path, schema)?),
+            CopyFormat::Parquet => Box::new(ParquetWriter::try_new(path, schema)?),
+        };
+        let mut num_rows = 0;
+        while let Some(row) = physical_plan.next()? {
+            writer.write_row(&row)?;
+            num_rows += 1;
+        }
+        writer.finish()?;
+        Ok(ResultSet::affected_rows(num_rows))
+    }
+
+    fn copy_from_options(
+        path: &str,
+        options: &[FileOption],
+    ) -> DBResult<CopyFromOptions> {
+        let mut format = CopyFormat::from_path(path);
+        let mut copy_options = CopyFromOptions {
+            csv: CsvOptions {
+                nulls: vec![String::new()],
+                ..CsvOptions::default()
+            },
+            skip_bad_rows: false,
+            reject_limit: None,
+        };
+        for option in options {
+            if copy_options.csv.set_option(option)? {
+                continue;
+            }
+            match option.name() {
+                "format" => format = CopyFormat::from_name(option.string_value()?)?,
+                "on_error" => {
+                    copy_options.skip_bad_rows =
+                        match option.string_value()?.to_lowercase().as_str() {
+                            "stop" => false,
+                            "skip" | "ignore" => true,
+                            _ => {
+                                return Err(DBError::ParserError(format!(
+                                    "invalid option {option}: expected stop or skip"
+                                )))
+                            }
+                        }
+                }
+                "reject_limit" => {
+                    copy_options.skip_bad_rows = true;
+                    copy_options.reject_limit = Some(option.usize_value()?);
+                }
+                _ => {
+                    return Err(DBError::ParserError(format!(
+                        "COPY FROM option {option} is not supported"
+                    )))
+                }
+            }
+        }
+        if format != CopyFormat::Csv {
+            return Err(DBError::ParserError(
+                "COPY FROM only supports csv files".to_owned(),
+            ));
+        }
+        Ok(copy_options)
+    }
+
+    /// load rows of a csv file into the table, the number of loaded rows and the line
+    /// numbers of skipped bad rows are returned as `affected_rows` and `rejected_lines`.
+    ///
+    /// Each record is checked against the constraints of the table, a record violating
+    /// them is a bad row as one that cannot be read. The table is locked while loading
+    /// so that UNIQUE constraints are checked against its rows and the loaded ones.
+    fn copy_from(
+        &self,
+        table_name: &ObjectName,
+        columns: &[Ident],
+        path: &str,
+        options: &[FileOption],
+    ) -> DBResult<ResultSet> {
+        let options = Self::copy_from_options(path, options)?;
+        let table = RwLock::read(&self.catalog)
+            .map_err(|_e| {
+                DBError::Unknown("access catalog read lock failed".to_string())
+            })?
+            .try_get_table(table_name.to_string().as_str())?;
+        let mut table = RwLock::write(Arc::as_ref(&table)).map_err(|_| {
+            DBError::Unknown("Access write lock of table failed!".to_string())
+        })?;
+        let meta = table.get_table_meta();
+        let target_fields = InsertHandler::target_fields(meta.get_schema(), columns)?;
+        let checker = RowChecker::new(&meta)?;
+        let mut unique_indexes = Self::unique_indexes(table.as_ref(), &meta)?;
+
+        let mut reader = CsvRecordReader::with_options(path, &options.csv)?;
+        if options.csv.header {
+            let _ = reader.try_read_next()?;
+        }
+        let mut rows = vec![];
+        let mut rejected_lines = vec![];
+        while let Some(record) = reader.try_read_next()? {
+            let row = Self::record_to_row(&record, &target_fields, &meta, &options.csv)
+                .and_then(|row| {
+                    Self::check_row(&row, &checker, &mut unique_indexes)
+                        .map_err(|e| e.to_string())?;
+                    Ok(row)
+                });
+            match row {
+                Ok(row) => rows.push(row),
+                Err(msg) => {
+                    let msg = format!("line {}: {msg}", reader.line());
+                    if !options.skip_bad_rows
+                        || options
+                            .reject_limit
+                            .is_some_and(|limit| rejected_lines.len() >= limit)
+                    {
+                        return Err(DBError::StorageEngine(msg));
+                    }
+                    log::warn!("COPY {table_name} skipped bad row at {msg}");
+                    rejected_lines.push(reader.line() as u64);
+                }
+            }
+        }
+
+        let num_rows = rows.len();
+        table.insert_data(rows)?;
+        Ok(ResultSet::affected_rows_with_rejects(
+            num_rows,
+            rejected_lines,
+        ))
+    }
+
+    /// indexes of the UNIQUE constraints of the table built from its rows
+    fn unique_indexes(table: &dyn Table, meta: &TableMeta) -> DBResult<Vec<UniqueIndex>> {
+        if !meta
+            .get_constraints()
+            .iter()
+            .any(|c| matches!(c, TableConstraint::Unique { .. }))
+        {
+            return Ok(vec![]);
+        }
+        let mut scan = table.create_scan_op();
+        scan.setup()?;
+        let mut rows = vec![];
+        while let Some(row) = scan.next()? {
+            rows.push(row);
+        }
+        UniqueIndex::build_all(meta, &rows)
+    }
+
+    /// check the constraints of a row, its unique keys are added to the indexes if it
+    /// satisfies them
+    fn check_row(
+        row: &Row<'static>,
+        checker: &RowChecker,
+        unique_indexes: &mut [UniqueIndex],
+    ) -> DBResult<()> {
+        checker.check(row)?;
+        let keys = unique_indexes
+            .iter()
+            .map(|index| index.new_keys(std::iter::once(row)))
+            .collect::<DBResult<Vec<_>>>()?;
+        for (index, keys) in unique_indexes.iter_mut().zip(keys) {
+            index.add_keys(keys);
+        }
+        Ok(())
+    }
+
+    /// the row of a csv record whose fields are the values of the target fields, other
+    /// fields are filled by their DEFAULT values
+    fn record_to_row(
+        record: &[String],
+        target_fields: &[usize],
+        meta: &TableMeta,
+        options: &CsvOptions,
+    ) -> Result<Row<'static>, String> {
+        if record.len() != target_fields.len() {
+            return Err(format!(
+                "expected {} fields but got {}",
+                target_fields.len(),
+                record.len()
+            ));
+        }
+        let schema = meta.get_schema();
+        let mut cells = (0..schema.num_fields())
+            .map(|idx| {
+                meta.get_column_default(idx)
+                    .cloned()
+                    .unwrap_or(Literal::Null)
+            })
+            .collect::<Vec<_>>();
+        for (&field_idx, value) in Iterator::zip(target_fields.iter(), record) {
+            let field = &schema.get_fields()[field_idx];
+            cells[field_idx] = match value.as_str() {
+                v if options.is_null(v) => Literal::Null,
+                v => match parse_csv_field(v, field.data_type()) {
+                    Some(value) => value,
+                    None if options.invalid_values == InvalidValuePolicy::Null => {
+                        Literal::Null
+                    }
+                    None => {
+                        return Err(format!(
+                            "column {}: cannot read '{v}' as {}",
+                            field.name(),
+                            field.data_type()
+                        ))
+                    }
+                },
+            };
+        }
+        Ok(Row::new(cells))
+    }
+}
@@ -1,17 +1,31 @@
-use std::sync::{Arc, RwLock};
+use std::{
+    collections::HashSet,
+    sync::{Arc, RwLock},
+};
 
-use sqlparser::ast::Statement;
+use sqlparser::ast::{ColumnDef, ColumnOption, Ident, ObjectName, Statement};
 
 use crate::{
     data_types::DataType,
-    tables::{FieldInfo, RelationSchema, TableMeta},
-    Catalog, DBError, ResultSet,
+    expressions::Literal,
+    parser::{build_logical_plan, CreateExternalTable},
+    tables::{
+        arrow_ipc::ArrowTable,
+        constraints::{RowChecker, TableConstraint},
+        csv::{CsvOptions, CsvTable},
+        inmem::InMemTable,
+        json::{JsonOptions, JsonTable},
+        parquet::ParquetTable,
+        FieldInfo, RelationSchema, Table, TableMeta,
+    },
+    Catalog, DBError, DBResult, ResultSet,
 };
 
-use super::QueryHandler;
+use super::{eval_constant_expr, materialize_query, QueryHandler, SelectHandler};
 
 pub struct CreateTableHandler {
     catalog: Arc<RwLock<Catalog>>,
+    select_handler: SelectHandler,
 }
 
 impl QueryHandler for CreateTableHandler {
@@ -21,23 +35,23 @@ impl QueryHandler for CreateTableHandler {
     ) -> crate::DBResult<crate::ResultSet> {
         match statement {
             Statement::CreateTable {
-                or_replace: _,
+                or_replace,
                 temporary: _,
                 external: _,
                 global: _,
-                if_not_exists: _,
+                if_not_exists,
                 name,
                 columns,
-                constraints: _,
+                constraints,
                 hive_distribution: _,
                 hive_formats: _,
                 table_properties: _,
                 with_options: _,
                 file_format: _,
                 location: _,
-                query: _,
+                query,
                 without_rowid: _,
-                like: _,
+                like,
                 clone: _,
                 engine: _,
                 default_charset: _,
@@ -46,37 +60,344 @@ impl QueryHandler for CreateTableHandler {
                 on_cluster: _,
                 ..
             } => {
-                self.create_table(name, columns)?;
+                let name = name.to_string();
+                // skip creating the table, which is also checked when it is added in
+                // case of concurrent creates
+                if if_not_exists && self.read_catalog()?.table_exists(&name)? {
+                    return Ok(ResultSet::empty());
+                }
+
+                // `CREATE TABLE t2 (LIKE t1)` is parsed as a column named `like`
+                let (columns, like) = match (Self::parenthesized_like(&columns), like) {
+                    (Some(parenthesized), None) => (vec![], Some(parenthesized)),
+                    (_, like) => (columns, like),
+                };
+                let table: Box<dyn Table> = match (query, like) {
+                    _ if !constraints.is_empty() && columns.is_empty() => {
+                        return Err(DBError::ParserError(
+                            "table constraints require column definitions.".to_string(),
+                        ))
+                    }
+                    (Some(query), None) if columns.is_empty() => {
+                        let plan = build_logical_plan(*query)?;
+                        Box::new(materialize_query(&self.select_handler, plan)?)
+                    }
+                    (None, Some(like)) if columns.is_empty() => {
+                        Box::new(InMemTable::new(self.get_table_meta(&like)?))
+                    }
+                    (None, None) => Box::new(InMemTable::new(self.create_table_meta(
+                        &name,
+                        columns,
+                        constraints,
+                    )?)),
+                    _ => {
+                        return Err(DBError::ParserError(
+                            "CREATE TABLE only supports one of column definitions, AS SELECT or LIKE."
+                                .to_string(),
+                        ))
+                    }
+                };
+                self.read_catalog()?.add_new_table(
+                    name,
+                    table,
+                    or_replace,
+                    if_not_exists,
+                )?;
                 Ok(ResultSet::empty())
             }
-            _ => Err(DBError::Unknown("should never happen!".to_string())),
+            _ => Err(DBError::should_never_happen()),
         }
     }
 }
 
 impl CreateTableHandler {
     pub fn new(catalog: Arc<RwLock<Catalog>>) -> Self {
-        Self { catalog }
+        let select_handler = SelectHandler::new(Arc::clone(&catalog));
+        Self {
+            catalog,
+            select_handler,
+        }
     }
 
-    /// create a table in CrackDB
-    /// simply create a InMemTable and register it into a inmem map keyed by the table name
-    fn create_table(
+    fn read_catalog(&self) -> DBResult<std::sync::RwLockReadGuard<'_, Catalog>> {
+        RwLock::read(&self.catalog).map_err(|_e| {
+            DBError::Unknown("access catalog read lock failed.".to_string())
+        })
+    }
+
+    /// create the meta of a table in CrackDB from column definitions and table
+    /// constraints
+    fn create_table_meta(
         &self,
-        name: sqlparser::ast::ObjectName,
-        columns: Vec<sqlparser::ast::ColumnDef>,
-    ) -> Result<(), DBError> {
-        // TODO: validate against unsupported data types
-        let fields = columns
-            .into_iter()
-            .map(|c| FieldInfo::new(c.name.to_string(), DataType::from(c.data_type)))
-            .collect();
+        table_name: &str,
+        columns: Vec<ColumnDef>,
+        table_constraints: Vec<sqlparser::ast::TableConstraint>,
+    ) -> DBResult<TableMeta> {
+        let mut names = ConstraintNames::new(table_name);
+        let mut fields = Vec::with_capacity(columns.len());
+        let mut column_defaults = Vec::with_capacity(columns.len());
+        let mut constraints = Vec::new();
+        for c in columns {
+            constraints.extend(column_constraints(&c, &mut names)?);
+            let (field, default) = column_def_to_field(c)?;
+            fields.push(field);
+            column_defaults.push(default);
+        }
+        for c in table_constraints {
+            constraints.push(table_constraint(c, &mut names)?);
+        }
+
+        // columns of PRIMARY KEY are NOT NULL
+        let mut primary_keys = constraints.iter().filter_map(|c| match c {
+            TableConstraint::Unique {
+                columns,
+                is_primary: true,
+                ..
+            } => Some(columns.clone()),
+            _ => None,
+        });
+        let primary_key = primary_keys.next();
+        if primary_keys.next().is_some() {
+            return Err(DBError::ParserError(format!(
+                "multiple primary keys for table {table_name} are not allowed"
+            )));
+        }
+        for column in primary_key.unwrap_or_default() {
+            let is_not_null = constraints.iter().any(
+                |c| matches!(c, TableConstraint::NotNull { column: c, .. } if *c == column),
+            );
+            if !is_not_null {
+                let name = names.name(None, format!("{column}_not_null"));
+                constraints.push(TableConstraint::NotNull { name, column });
+            }
+        }
+
         let schema = RelationSchema::new(fields);
-        let meta = TableMeta::new(schema);
-        RwLock::read(&self.catalog)
-            .map_err(|_e| {
-                DBError::Unknown("access catalog read lock failed.".to_string())
-            })?
-            .create_table(name.to_string(), meta)
+        let meta = TableMeta::new(schema)
+            .with_column_defaults(column_defaults)
+            .with_constraints(constraints);
+        meta.validate()?;
+        // CHECK constraints must be valid expressions over the columns
+        RowChecker::new(&meta)?;
+        Ok(meta)
+    }
+
+    /// register a readonly table of a file, whose columns are either declared or read
+    /// from the file
+    pub(crate) fn create_external_table(
+        &self,
+        statement: CreateExternalTable,
+    ) -> DBResult<ResultSet> {
+        let name = statement.name.to_string();
+        if statement.if_not_exists && self.read_catalog()?.table_exists(&name)? {
+            return Ok(ResultSet::empty());
+        }
+        if statement.format != "csv" && !statement.columns.is_empty() {
+            return Err(DBError::ParserError(format!(
+                "external tables of {} do not support columns",
+                statement.format
+            )));
+        }
+        let path = statement.location;
+        let table: Box<dyn Table> = match statement.format.as_str() {
+            "csv" => {
+                let options = CsvOptions::try_from_options(&statement.options)?;
+                if statement.columns.is_empty() {
+                    Box::new(CsvTable::with_options(path, options)?)
+                } else {
+                    let fields = statement
+                        .columns
+                        .into_iter()
+                        .map(|column| match column.options.is_empty() {
+                            true => column_def_to_field(column).map(|(field, _)| field),
+                            false => Err(DBError::ParserError(format!(
+                                "column {} of an external table cannot have options",
+                                column.name
+                            ))),
+                        })
+                        .collect::<DBResult<Vec<_>>>()?;
+                    let schema = RelationSchema::new(fields);
+                    Box::new(CsvTable::with_schema(path, schema, options)?)
+                }
+            }
+            "json" | "ndjson" => {
+                let options = JsonOptions::try_from_options(&statement.options)?;
+                Box::new(JsonTable::with_options(path, options)?)
+            }
+            "parquet" | "arrow" | "feather" if !statement.options.is_empty() => {
+                return Err(DBError::ParserError(format!(
+                    "external tables of {} do not support options",
+                    statement.format
+                )))
+            }
+            "parquet" => Box::new(ParquetTable::new(path)?),
+            "arrow" | "feather" => Box::new(ArrowTable::new(path)?),
+            format => {
+                return Err(DBError::ParserError(format!(
+                    "external tables of {format} are not supported"
+                )))
+            }
+        };
+        self.read_catalog()?.add_new_table(
+            name,
+            table,
+            false,
+            statement.if_not_exists,
+        )?;
+        Ok(ResultSet::empty())
+    }
+
+    /// the meta of an existing table, including its DEFAULT values
+    fn get_table_meta(&self, name: &ObjectName) -> DBResult<TableMeta> {
+        let table = self.read_catalog()?.try_get_table(&name.to_string())?;
+        let table = RwLock::read(&table).map_err(|_| {
+            DBError::Unknown("Access read lock of table failed.".to_string())
+        })?;
+        Ok(table.get_table_meta())
+    }
+
+    /// the source table of `CREATE TABLE t2 (LIKE t1)`
+    fn parenthesized_like(columns: &[ColumnDef]) -> Option<ObjectName> {
+        match columns {
+            [ColumnDef {
+                name,
+                data_type: sqlparser::ast::DataType::Custom(like, modifiers),
+                options,
+                ..
+            }] if name.quote_style.is_none()
+                && name.value.eq_ignore_ascii_case("like")
+                && modifiers.is_empty()
+                && options.is_empty() =>
+            {
+                Some(like.clone())
+            }
+            _ => None,
+        }
+    }
+}
+
+/// the field and DEFAULT value of a column definition
+pub(crate) fn column_def_to_field(
+    column: ColumnDef,
+) -> DBResult<(FieldInfo, Option<Literal>)> {
+    // TODO: validate against unsupported data types
+    let field = FieldInfo::new(column.name.to_string(), DataType::from(column.data_type));
+    let default = column
+        .options
+        .iter()
+        .find_map(|option| match &option.option {
+            ColumnOption::Default(expr) => Some(expr),
+            _ => None,
+        })
+        .map(|expr| eval_constant_expr(expr, &field))
+        .transpose()?;
+    Ok((field, default))
+}
+
+/// the constraints of a column definition
+fn column_constraints(
+    column: &ColumnDef,
+    names: &mut ConstraintNames,
+) -> DBResult<Vec<TableConstraint>> {
+    let column_name = column.name.value.clone();
+    let mut constraints = Vec::new();
+    for option in column.options.iter() {
+        let constraint = match &option.option {
+            ColumnOption::Null | ColumnOption::Default(_) => continue,
+            ColumnOption::NotNull => TableConstraint::NotNull {
+                name: names.name(option.name.as_ref(), format!("{column_name}_not_null")),
+                column: column_name.clone(),
+            },
+            ColumnOption::Unique { is_primary } => TableConstraint::Unique {
+                name: match is_primary {
+                    true => names.name(option.name.as_ref(), "pkey".to_owned()),
+                    false => {
+                        names.name(option.name.as_ref(), format!("{column_name}_key"))
+                    }
+                },
+                columns: vec![column_name.clone()],
+                is_primary: *is_primary,
+            },
+            ColumnOption::Check(expr) => TableConstraint::Check {
+                name: names.name(option.name.as_ref(), format!("{column_name}_check")),
+                expr: expr.clone(),
+            },
+            option => {
+                return Err(DBError::ParserError(format!(
+                    "column option {option} is not supported."
+                )))
+            }
+        };
+        constraints.push(constraint);
+    }
+    Ok(constraints)
+}
+
+/// the constraint of a table constraint definition
+fn table_constraint(
+    constraint: sqlparser::ast::TableConstraint,
+    names: &mut ConstraintNames,
+) -> DBResult<TableConstraint> {
+    match constraint {
+        sqlparser::ast::TableConstraint::Unique {
+            name,
+            columns,
+            is_primary,
+        } => {
+            let columns = columns.into_iter().map(|c| c.value).collect::<Vec<_>>();
+            let name = match is_primary {
+                true => names.name(name.as_ref(), "pkey".to_owned()),
+                false => names.name(name.as_ref(), format!("{}_key", columns.join("_"))),
+            };
+            Ok(TableConstraint::Unique {
+                name,
+                columns,
+                is_primary,
+            })
+        }
+        sqlparser::ast::TableConstraint::Check { name, expr } => {
+            Ok(TableConstraint::Check {
+                name: names.name(name.as_ref(), "check".to_owned()),
+                expr: *expr,
+            })
+        }
+        constraint => Err(DBError::ParserError(format!(
+            "constraint {constraint} is not supported."
+        ))),
+    }
+}
+
+/// ConstraintNames names constraints of a table, constraints without explicit names
+/// are named `{table}_{suffix}`, followed by a number if the name is already taken
+struct ConstraintNames<'a> {
+    table_name: &'a str,
+    used: HashSet<String>,
+}
+
+impl<'a> ConstraintNames<'a> {
+    fn new(table_name: &'a str) -> Self {
+        Self {
+            table_name,
+            used: HashSet::new(),
+        }
+    }
+
+    fn name(&mut self, given: Option<&Ident>, suffix: String) -> String {
+        let name = match given {
+            Some(given) => given.value.clone(),
+            None => {
+                let name = format!("{}_{suffix}", self.table_name);
+                if self.used.contains(&name) {
+                    (1..)
+                        .map(|n| format!("{name}{n}"))
+                        .find(|n| !self.used.contains(n))
+                        .unwrap_or(name)
+                } else {
+                    name
+                }
+            }
+        };
+        self.used.insert(name.clone());
+        name
     }
 }
@@ -0,0 +1,126 @@
+use std::sync::{Arc, RwLock};
+
+use sqlparser::ast::{ObjectName, Statement};
+
+use crate::{
+    catalog::View,
+    logical_plans::{LogicalPlan, ScanSource},
+    optimizer::Optimizer,
+    parser::build_logical_plan,
+    tables::Table,
+    Catalog, DBError, DBResult, ResultSet,
+};
+
+use super::{materialize_query, QueryHandler, SelectHandler};
+
+pub struct CreateViewHandler {
+    catalog: Arc<RwLock<Catalog>>,
+    select_handler: SelectHandler,
+}
+
+impl QueryHandler for CreateViewHandler {
+    fn handle(&self, statement: Statement) -> DBResult<ResultSet> {
+        match statement {
+            Statement::CreateView {
+                or_replace,
+                materialized,
+                name,
+                columns,
+                query,
+                ..
+            } => {
+                if !columns.is_empty() {
+                    return Err(DBError::ParserError(
+                        "column lists of views are not supported.".to_string(),
+                    ));
+                }
+                let name = name.to_string();
+                let qualified = self.read_catalog()?.resolve_name(&name)?;
+                // names are resolved now, rather than by the search path of queries
+                let optimizer = Optimizer::new(Arc::clone(&self.catalog));
+                let plan = optimizer.qualify_names(&build_logical_plan(*query)?)?;
+                self.check_recursion(&qualified, &plan)?;
+
+                if materialized {
+                    let table = materialize_query(&self.select_handler, plan.clone())?;
+                    let schema = table.get_table_meta().get_schema().clone();
+                    let view = View::new(plan, schema, true);
+                    self.read_catalog()?.add_view(
+                        name,
+                        view,
+                        Some(Box::new(table)),
+                        or_replace,
+                    )?;
+                } else {
+                    // validate the query of the view before registering it
+                    let schema = optimizer.optimize(plan.clone())?.schema()?;
+                    let view = View::new(plan, schema, false);
+                    self.read_catalog()?
+                        .add_view(name, view, None, or_replace)?;
+                }
+                Ok(ResultSet::empty())
+            }
+            _ => Err(DBError::should_never_happen()),
+        }
+    }
+}
+
+impl CreateViewHandler {
+    pub fn new(catalog: Arc<RwLock<Catalog>>) -> Self {
+        let select_handler = SelectHandler::new(Arc::clone(&catalog));
+        Self {
+            catalog,
+            select_handler,
+        }
+    }
+
+    /// recompute the results of a materialized view
+    pub(crate) fn refresh(&self, name: &ObjectName) -> DBResult<ResultSet> {
+        let name = name.to_string();
+        let view = self
+            .read_catalog()?
+            .get_view(&name)?
+            .filter(|view| view.is_materialized())
+            .ok_or(DBError::ParserError(format!(
+                "{name} is not a materialized view"
+            )))?;
+        let table = materialize_query(&self.select_handler, view.plan().clone())?;
+        let schema = table.get_table_meta().get_schema().clone();
+        let view = View::new(view.plan().clone(), schema, true);
+        self.read_catalog()?
+            .add_view(name, view, Some(Box::new(table)), true)?;
+        Ok(ResultSet::empty())
+    }
+
+    fn read_catalog(&self) -> DBResult<std::sync::RwLockReadGuard<'_, Catalog>> {
+        RwLock::read(&self.catalog).map_err(|_e| {
+            DBError::Unknown("access catalog read lock failed.".to_string())
+        })
+    }
+
+    /// check that the plan of a view does not reference the view of the qualified name,
+    /// either directly or through other views. Names in the plans of views are
+    /// qualified when the views are created, so no search path of later queries makes
+    /// views reference themselves.
+    fn check_recursion(&self, name: &str, plan: &LogicalPlan) -> DBResult<()> {
+        let relations = std::iter::successors(Some(plan), |plan| plan.child())
+            .filter_map(|plan| match plan {
+                LogicalPlan::UnResolvedScan {
+                    source: ScanSource::Name(name),
+                } => Some(name),
+                _ => None,
+            });
+        for relation in relations {
+            let relation = self.read_catalog()?.resolve_object_name(relation)?;
+            if relation == name {
+                return Err(DBError::ParserError(format!(
+                    "view {name} cannot reference itself"
+                )));
+            }
+            if let Some(view) = self.read_catalog()?.get_view(&relation)? {
+                self.check_recursion(name, view.plan())?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,93 @@
+use std::sync::{Arc, RwLock};
+
+use sqlparser::ast::{Expr, ObjectName, Statement, TableFactor, TableWithJoins};
+
+use crate::{parser::ast_expr_to_plan_expr, Catalog, DBError, DBResult, ResultSet};
+
+use super::{resolve_table_exprs, QueryHandler};
+
+/// DeleteHandler handles both DELETE and TRUNCATE statements, TRUNCATE works as DELETE
+/// without WHERE clause.
+pub struct DeleteHandler {
+    catalog: Arc<RwLock<Catalog>>,
+}
+
+impl QueryHandler for DeleteHandler {
+    fn handle(&self, statement: Statement) -> DBResult<ResultSet> {
+        match statement {
+            Statement::Delete {
+                tables,
+                from,
+                using,
+                selection,
+                returning,
+            } => {
+                if !tables.is_empty() || using.is_some() || returning.is_some() {
+                    return Err(DBError::ParserError(
+                        "DELETE with multiple tables, USING or RETURNING is not supported."
+                            .to_string(),
+                    ));
+                }
+                let table_name = Self::single_table_name(from)?;
+                let num_deleted = self.process_delete(table_name, selection)?;
+                Ok(ResultSet::affected_rows(num_deleted))
+            }
+            Statement::Truncate {
+                table_name,
+                partitions,
+                ..
+            } => {
+                if partitions.is_some() {
+                    return Err(DBError::ParserError(
+                        "TRUNCATE with partitions is not supported.".to_string(),
+                    ));
+                }
+                let num_deleted = self.process_delete(table_name, None)?;
+                Ok(ResultSet::affected_rows(num_deleted))
+            }
+            _ => Err(DBError::should_never_happen()),
+        }
+    }
+}
+
+impl DeleteHandler {
+    pub(crate) fn new(catalog: Arc<RwLock<Catalog>>) -> Self {
+        Self { catalog }
+    }
+
+    fn single_table_name(from: Vec<TableWithJoins>) -> DBResult<ObjectName> {
+        match from.as_slice() {
+            [TableWithJoins {
+                relation: TableFactor::Table { name, .. },
+                joins,
+            }] if joins.is_empty() => Ok(name.clone()),
+            _ => Err(DBError::ParserError(
+                "DELETE only supports a single table.".to_string(),
+            )),
+        }
+    }
+
+    fn process_delete(
+        &self,
+        table_name: ObjectName,
+        selection: Option<Expr>,
+    ) -> DBResult<usize> {
+        let predicate = match selection {
+            Some(selection) => {
+                let exprs = vec![ast_expr_to_plan_expr(&selection)?];
+                resolve_table_exprs(&self.catalog, &table_name, exprs, "DELETE")?.pop()
+            }
+            None => None,
+        };
+
+        let table = RwLock::read(&self.catalog)
+            .map_err(|_e| {
+                DBError::Unknown("access catalog read lock failed.".to_string())
+            })?
+            .try_get_table(&table_name.to_string())?;
+        let mut table = RwLock::write(Arc::as_ref(&table)).map_err(|_| {
+            DBError::Unknown("Access write lock of table failed!".to_string())
+        })?;
+        table.delete(predicate.as_ref())
+    }
+}
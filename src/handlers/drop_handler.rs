@@ -0,0 +1,68 @@
+use std::sync::{Arc, RwLock};
+
+use sqlparser::ast::{ObjectType, Statement};
+
+use crate::{Catalog, DBError, DBResult, ResultSet};
+
+use super::QueryHandler;
+
+pub struct DropHandler {
+    catalog: Arc<RwLock<Catalog>>,
+}
+
+impl QueryHandler for DropHandler {
+    fn handle(&self, statement: Statement) -> DBResult<ResultSet> {
+        match statement {
+            Statement::Drop {
+                object_type: ObjectType::Table,
+                if_exists,
+                names,
+                ..
+            } => {
+                let catalog = RwLock::read(&self.catalog).map_err(|_e| {
+                    DBError::Unknown("access catalog read lock failed.".to_string())
+                })?;
+                // check all tables before dropping any of them
+                for name in names.iter().map(|n| n.to_string()) {
+                    if !if_exists && !catalog.table_exists(&name)? {
+                        return Err(DBError::TableNotFound(name));
+                    }
+                }
+                for name in names {
+                    catalog.drop_table(&name.to_string())?;
+                }
+                Ok(ResultSet::empty())
+            }
+            Statement::Drop {
+                object_type: ObjectType::View,
+                if_exists,
+                names,
+                ..
+            } => {
+                let catalog = RwLock::read(&self.catalog).map_err(|_e| {
+                    DBError::Unknown("access catalog read lock failed.".to_string())
+                })?;
+                // check all views before dropping any of them
+                for name in names.iter().map(|n| n.to_string()) {
+                    if !if_exists && catalog.get_view(&name)?.is_none() {
+                        return Err(DBError::TableNotFound(name));
+                    }
+                }
+                for name in names {
+                    catalog.drop_view(&name.to_string())?;
+                }
+                Ok(ResultSet::empty())
+            }
+            Statement::Drop { object_type, .. } => Err(DBError::ParserError(format!(
+                "DROP {object_type} is not supported."
+            ))),
+            _ => Err(DBError::should_never_happen()),
+        }
+    }
+}
+
+impl DropHandler {
+    pub(crate) fn new(catalog: Arc<RwLock<Catalog>>) -> Self {
+        Self { catalog }
+    }
+}
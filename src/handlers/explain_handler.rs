@@ -0,0 +1,79 @@
+use std::sync::{Arc, RwLock};
+
+use sqlparser::ast::Statement;
+
+use crate::{
+    data_types::DataType,
+    expressions::Literal,
+    physical_plans::explain_plan,
+    row::Row,
+    tables::{FieldInfo, RelationSchema},
+    Catalog, DBError, DBResult, ResultSet,
+};
+
+use super::{QueryHandler, SelectHandler};
+
+/// ExplainHandler describes the optimized logical plan and the physical plan of a query
+/// line by line. EXPLAIN ANALYZE executes the query, and reports the number of output
+/// rows and the elapsed time of every physical plan node.
+pub struct ExplainHandler {
+    select_handler: SelectHandler,
+}
+
+impl QueryHandler for ExplainHandler {
+    fn handle(&self, statement: Statement) -> DBResult<ResultSet> {
+        match statement {
+            Statement::Explain {
+                analyze, statement, ..
+            } => match *statement {
+                Statement::Query(query) => {
+                    let logical_plan = self.select_handler.optimize_query(*query)?;
+                    let logical_lines = logical_plan.explain();
+                    let mut physical_plan =
+                        self.select_handler.planning(logical_plan, analyze)?;
+                    if analyze {
+                        SelectHandler::execute_physical_plan(physical_plan.as_mut())?;
+                    }
+                    let physical_lines = explain_plan(physical_plan.as_ref());
+                    Ok(Self::plan_result_set(logical_lines, physical_lines))
+                }
+                statement => Err(DBError::ParserError(format!(
+                    "EXPLAIN is only supported for queries, but got {statement}"
+                ))),
+            },
+            _ => Err(DBError::should_never_happen()),
+        }
+    }
+}
+
+impl ExplainHandler {
+    pub fn new(catalog: Arc<RwLock<Catalog>>) -> Self {
+        Self {
+            select_handler: SelectHandler::new(catalog),
+        }
+    }
+
+    fn plan_result_set(
+        logical_lines: Vec<String>,
+        physical_lines: Vec<String>,
+    ) -> ResultSet {
+        let schema = RelationSchema::new(vec![
+            FieldInfo::new("plan_type".to_owned(), DataType::String),
+            FieldInfo::new("plan".to_owned(), DataType::String),
+        ]);
+        let logical_rows = logical_lines.into_iter().map(|line| ("logical_plan", line));
+        let physical_rows = physical_lines
+            .into_iter()
+            .map(|line| ("physical_plan", line));
+        let rows = logical_rows
+            .chain(physical_rows)
+            .map(|(plan_type, line)| {
+                Row::new(vec![
+                    Literal::String(plan_type.to_owned()),
+                    Literal::String(line),
+                ])
+            })
+            .collect();
+        ResultSet::new(schema, rows)
+    }
+}
@@ -1,16 +1,19 @@
 use std::sync::{Arc, RwLock};
 
-use sqlparser::ast::{Expr, SetExpr, Statement, Value, Values};
+use sqlparser::ast::{Expr, Ident, SetExpr, Statement, Values};
 
 use crate::{
-    data_types::DataType, expressions::Literal, row::Row, Catalog, DBError, DBResult,
-    ResultSet,
+    expressions::Literal,
+    row::Row,
+    tables::{RelationSchema, TableMeta},
+    Catalog, DBError, DBResult, ResultSet,
 };
 
-use super::QueryHandler;
+use super::{coerce_value, eval_constant_expr, QueryHandler, SelectHandler};
 
 pub struct InsertHandler {
     catalog: Arc<RwLock<Catalog>>,
+    select_handler: SelectHandler,
 }
 
 impl QueryHandler for InsertHandler {
@@ -32,17 +35,21 @@ impl QueryHandler for InsertHandler {
                 on: _,
                 returning: _,
             } => {
-                self.process_insert(table_name, columns, *source)?;
-                Ok(ResultSet::empty())
+                let num_rows = self.process_insert(table_name, columns, *source)?;
+                Ok(ResultSet::affected_rows(num_rows))
             }
-            _ => Err(DBError::Unknown("should never happen!".to_string())),
+            _ => Err(DBError::should_never_happen()),
         }
     }
 }
 
 impl InsertHandler {
     pub(crate) fn new(catalog: Arc<RwLock<Catalog>>) -> Self {
-        Self { catalog }
+        let select_handler = SelectHandler::new(Arc::clone(&catalog));
+        Self {
+            catalog,
+            select_handler,
+        }
     }
 
     fn process_insert(
@@ -50,13 +57,8 @@ impl InsertHandler {
         table_name: sqlparser::ast::ObjectName,
         columns: Vec<sqlparser::ast::Ident>,
         source: sqlparser::ast::Query,
-    ) -> Result<(), DBError> {
-        assert!(
-            columns.is_empty(),
-            "insert into with columns not supported by now!"
-        );
-
-        let schema = {
+    ) -> Result<usize, DBError> {
+        let meta = {
             let table = RwLock::read(&self.catalog)
                 .map_err(|_e| {
                     DBError::Unknown("access catalog read lock failed".to_string())
@@ -65,45 +67,62 @@ impl InsertHandler {
             let table = RwLock::read(&table).map_err(|_| {
                 DBError::Unknown("Access read lock of table failed.".to_string())
             })?;
-            table.get_table_meta().get_schema().clone()
+            table.get_table_meta()
         };
-        let readers: Vec<(usize, DataType)> = if columns.is_empty() {
-            schema
-                .get_fields()
-                .iter()
-                .zip(0..schema.get_fields().len())
-                .map(|(f, idx)| (idx, f.data_type().clone()))
-                .collect()
-        } else {
-            // TODO: support insert with columns
-            todo!();
-        };
-        let rows_to_insert: Vec<Row> = match *source.body {
+        let schema = meta.get_schema();
+        let target_fields = Self::target_fields(schema, &columns)?;
+
+        // values of target fields for each row to insert
+        let values_rows: Vec<Vec<Literal>> = match *source.body {
             SetExpr::Values(Values {
                 rows,
                 explicit_row: _,
-            }) => {
-                let mut rows_to_insert = Vec::with_capacity(rows.len());
-                for r in rows {
-                    let mut cells = Vec::with_capacity(readers.len());
-                    for (idx, data_type) in readers.iter() {
-                        match r.get(*idx) {
-                            Some(expr) => {
-                                let cell =
-                                    convert_insert_expr_to_cell_value(expr, data_type)?;
-                                cells.push(cell);
-                            }
-                            None => cells.push(Literal::Null),
-                        }
-                    }
-                    let row = Row::new(cells);
-                    rows_to_insert.push(row);
+            }) => rows
+                .iter()
+                .map(|r| self.eval_values_row(r, &target_fields, &meta))
+                .collect::<DBResult<Vec<_>>>()?,
+            _ => {
+                // INSERT ... SELECT
+                let rs = self.select_handler.process_query(source)?;
+                if rs.schema.num_fields() != target_fields.len() {
+                    return Err(DBError::ParserError(format!(
+                        "INSERT has {} target columns but the query returns {} columns",
+                        target_fields.len(),
+                        rs.schema.num_fields()
+                    )));
                 }
-                rows_to_insert
+                rs.rows
+                    .iter()
+                    .map(|r| {
+                        Iterator::zip(target_fields.iter(), 0..r.num_fields())
+                            .map(|(field_idx, idx)| {
+                                let field = schema.get_field_at(*field_idx).unwrap();
+                                r.get_field(idx).and_then(|v| coerce_value(v, field))
+                            })
+                            .collect::<DBResult<Vec<_>>>()
+                    })
+                    .collect::<DBResult<Vec<_>>>()?
             }
-            _ => panic!("unsupported insert statement type!"),
         };
 
+        // fill fields without values by their DEFAULT values
+        let rows_to_insert: Vec<Row> = values_rows
+            .into_iter()
+            .map(|values| {
+                let mut cells = (0..schema.num_fields())
+                    .map(|idx| {
+                        meta.get_column_default(idx)
+                            .cloned()
+                            .unwrap_or(Literal::Null)
+                    })
+                    .collect::<Vec<_>>();
+                for (field_idx, value) in Iterator::zip(target_fields.iter(), values) {
+                    cells[*field_idx] = value;
+                }
+                Row::new(cells)
+            })
+            .collect();
+
         let table = RwLock::read(&self.catalog)
             .map_err(|_e| {
                 DBError::Unknown("access catalog read lock failed.".to_string())
@@ -112,67 +131,69 @@ impl InsertHandler {
         let mut table = RwLock::write(Arc::as_ref(&table)).map_err(|_| {
             DBError::Unknown("Access write lock of table failed!".to_string())
         })?;
-        table.insert_data(rows_to_insert);
-        Ok(())
+        let num_rows = rows_to_insert.len();
+        table.insert_data(rows_to_insert)?;
+        Ok(num_rows)
     }
-}
 
-fn convert_insert_expr_to_cell_value(
-    expr: &Expr,
-    data_type: &DataType,
-) -> DBResult<Literal> {
-    match expr {
-        Expr::Value(value) => match value {
-            Value::Number(v, _) => match data_type {
-                DataType::UInt8 => Ok(v.parse::<u8>().map(Literal::UInt8)?),
-                DataType::UInt16 => Ok(v.parse::<u16>().map(Literal::UInt16)?),
-                DataType::UInt32 => Ok(v.parse::<u32>().map(Literal::UInt32)?),
-                DataType::UInt64 => Ok(v.parse::<u64>().map(Literal::UInt64)?),
-                DataType::Int8 => Ok(v.parse::<i8>().map(Literal::Int8)?),
-                DataType::Int16 => Ok(v.parse::<i16>().map(Literal::Int16)?),
-                DataType::Int32 => Ok(v.parse::<i32>().map(Literal::Int32)?),
-                DataType::Int64 => Ok(v.parse::<i64>().map(Literal::Int64)?),
-                DataType::Float32 => Ok(v.parse::<f32>().map(Literal::Float32)?),
-                DataType::Float64 => Ok(v.parse::<f64>().map(Literal::Float64)?),
-                _ => Err(DBError::ParserError(
-                    "Unsupported number data type.".to_string(),
-                )),
-            },
-            Value::SingleQuotedString(v) => match data_type {
-                DataType::String => Ok(Literal::String(v.to_string())),
-                // TODO: validate format
-                DataType::DateTime => Ok(Literal::DateTime(v.to_string())),
-                _ => Err(DBError::ParserError("Unexpected string.".to_string())),
-            },
-            Value::DollarQuotedString(_) => {
-                Err(DBError::ParserError("Unexpected $ string.".to_string()))
-            }
-            Value::EscapedStringLiteral(_) => Err(DBError::ParserError(
-                "Unexpected escapted string".to_string(),
-            )),
-            Value::NationalStringLiteral(_) => {
-                Err(DBError::ParserError("Unexpected value.".to_string()))
-            }
-            Value::HexStringLiteral(_) => {
-                Err(DBError::ParserError("Unexpected hex string.".to_string()))
-            }
-            Value::DoubleQuotedString(v) => match data_type {
-                DataType::String => Ok(Literal::String(v.to_string())),
-                DataType::DateTime => Ok(Literal::DateTime(v.to_string())),
-                _ => Err(DBError::ParserError("Unexpected string.".to_string())),
-            },
-            Value::Boolean(v) => match data_type {
-                DataType::Boolean => Ok(Literal::Bool(*v)),
-                _ => Err(DBError::ParserError("Unexpected boolean.".to_string())),
-            },
-            Value::Null => Ok(Literal::Null),
-            Value::Placeholder(_) => {
-                Err(DBError::ParserError("Unexpected placeholder".to_string()))
+    /// indexes of fields to insert values into, all fields if no columns are given
+    pub(crate) fn target_fields(
+        schema: &RelationSchema,
+        columns: &[Ident],
+    ) -> DBResult<Vec<usize>> {
+        if columns.is_empty() {
+            return Ok((0..schema.num_fields()).collect());
+        }
+        let mut target_fields = Vec::with_capacity(columns.len());
+        for column in columns {
+            let field_idx = schema
+                .index_of(&column.value)
+                .ok_or(DBError::ParserError(format!("column {column} not found")))?;
+            if target_fields.contains(&field_idx) {
+                return Err(DBError::ParserError(format!(
+                    "column {column} is specified more than once"
+                )));
             }
-            _ => Err(DBError::ParserError(
-                "Unexpected unquoted string.".to_string(),
-            )),
-        },
-        _ => panic!(""),
+            target_fields.push(field_idx);
+        }
+        Ok(target_fields)
+    }
+
+    /// evaluate a row of VALUES, the `DEFAULT` keyword results in the DEFAULT value of the
+    /// field
+    fn eval_values_row(
+        &self,
+        row: &[Expr],
+        target_fields: &[usize],
+        meta: &TableMeta,
+    ) -> DBResult<Vec<Literal>> {
+        if row.len() != target_fields.len() {
+            let more_or_fewer = if row.len() > target_fields.len() {
+                "more"
+            } else {
+                "fewer"
+            };
+            return Err(DBError::ParserError(format!(
+                "INSERT has {more_or_fewer} expressions than target columns: {}",
+                row.len()
+            )));
+        }
+        Iterator::zip(target_fields.iter(), row)
+            .map(|(field_idx, expr)| match expr {
+                Expr::Identifier(ident)
+                    if ident.quote_style.is_none()
+                        && ident.value.eq_ignore_ascii_case("default") =>
+                {
+                    Ok(meta
+                        .get_column_default(*field_idx)
+                        .cloned()
+                        .unwrap_or(Literal::Null))
+                }
+                _ => {
+                    let field = meta.get_schema().get_field_at(*field_idx).unwrap();
+                    eval_constant_expr(expr, field)
+                }
+            })
+            .collect()
     }
 }
@@ -0,0 +1,77 @@
+use std::sync::{Arc, RwLock};
+
+use sqlparser::ast::{Expr, SchemaName, Statement, Value};
+
+use crate::{catalog::DEFAULT_CATALOG, Catalog, DBError, DBResult, ResultSet};
+
+use super::QueryHandler;
+
+/// SchemaHandler creates schemas by CREATE SCHEMA, and changes the schemas to look up
+/// unqualified table names in by `SET search_path`.
+pub struct SchemaHandler {
+    catalog: Arc<RwLock<Catalog>>,
+}
+
+impl QueryHandler for SchemaHandler {
+    fn handle(&self, statement: Statement) -> DBResult<ResultSet> {
+        match statement {
+            Statement::CreateSchema {
+                schema_name: SchemaName::Simple(name),
+                if_not_exists,
+            } => {
+                let schema = match name.0.as_slice() {
+                    [schema] => schema.value.clone(),
+                    [catalog, schema] if catalog.value == DEFAULT_CATALOG => {
+                        schema.value.clone()
+                    }
+                    _ => {
+                        return Err(DBError::ParserError(format!(
+                            "invalid schema name {name}"
+                        )))
+                    }
+                };
+                self.read_catalog()?.create_schema(schema, if_not_exists)?;
+                Ok(ResultSet::empty())
+            }
+            Statement::CreateSchema { .. } => Err(DBError::ParserError(
+                "AUTHORIZATION of schemas is not supported.".to_string(),
+            )),
+            Statement::SetVariable {
+                variable, value, ..
+            } => {
+                if !variable.to_string().eq_ignore_ascii_case("search_path") {
+                    return Err(DBError::ParserError(format!(
+                        "unknown variable {variable}"
+                    )));
+                }
+                let search_path = value
+                    .iter()
+                    .map(|expr| match expr {
+                        Expr::Identifier(ident) => Ok(ident.value.clone()),
+                        Expr::Value(Value::SingleQuotedString(schema)) => {
+                            Ok(schema.clone())
+                        }
+                        _ => Err(DBError::ParserError(format!(
+                            "invalid schema name {expr} in search_path"
+                        ))),
+                    })
+                    .collect::<DBResult<Vec<_>>>()?;
+                self.read_catalog()?.set_search_path(search_path)?;
+                Ok(ResultSet::empty())
+            }
+            _ => Err(DBError::should_never_happen()),
+        }
+    }
+}
+
+impl SchemaHandler {
+    pub fn new(catalog: Arc<RwLock<Catalog>>) -> Self {
+        Self { catalog }
+    }
+
+    fn read_catalog(&self) -> DBResult<std::sync::RwLockReadGuard<'_, Catalog>> {
+        RwLock::read(&self.catalog).map_err(|_e| {
+            DBError::Unknown("access catalog read lock failed.".to_string())
+        })
+    }
+}
@@ -1,13 +1,18 @@
-use std::sync::{Arc, RwLock};
+use std::{
+    collections::BTreeSet,
+    sync::{Arc, RwLock},
+};
 
 use sqlparser::ast::Statement;
 
 use crate::{
+    expressions::Expression,
     logical_plans::LogicalPlan,
     optimizer::Optimizer,
     parser::build_logical_plan,
-    physical_plans::{Filter, Limit, PhysicalPlan, Sort},
+    physical_plans::{Analyze, Filter, Limit, PhysicalPlan, Sort},
     physical_plans::{HashAggregator, Projection},
+    tables::ScanOptions,
     Catalog, DBError, DBResult, ResultSet,
 };
 
@@ -21,7 +26,7 @@ impl QueryHandler for SelectHandler {
     fn handle(&self, statement: Statement) -> DBResult<ResultSet> {
         match statement {
             Statement::Query(query) => self.process_query(*query),
-            _ => Err(DBError::Unknown("Should never happen!".to_string())),
+            _ => Err(DBError::should_never_happen()),
         }
     }
 }
@@ -31,23 +36,52 @@ impl SelectHandler {
         Self { catalog }
     }
 
-    fn process_query(&self, query: sqlparser::ast::Query) -> DBResult<ResultSet> {
+    pub(crate) fn process_query(
+        &self,
+        query: sqlparser::ast::Query,
+    ) -> DBResult<ResultSet> {
+        let optimized_logical_plan = self.optimize_query(query)?;
+        self.execute_plan(optimized_logical_plan)
+    }
+
+    /// build the optimized logical plan of a query
+    pub(crate) fn optimize_query(
+        &self,
+        query: sqlparser::ast::Query,
+    ) -> DBResult<LogicalPlan> {
         // generate logical plan
         let logical_plan = build_logical_plan(query)?;
         log::debug!("logical plan: {logical_plan:?}");
+        self.optimize_plan(logical_plan)
+    }
 
+    /// optimize a logical plan built from a query
+    pub(crate) fn optimize_plan(
+        &self,
+        logical_plan: LogicalPlan,
+    ) -> DBResult<LogicalPlan> {
         // TODO: optimize logical plan before further planning
         let optimizer = Optimizer::new(Arc::clone(&self.catalog));
         let optimized_logical_plan = optimizer.optimize(logical_plan)?;
 
         log::debug!("optimized logical plan: {optimized_logical_plan:?}");
+        Ok(optimized_logical_plan)
+    }
 
+    /// plan and execute an optimized logical plan
+    pub(crate) fn execute_plan(
+        &self,
+        optimized_logical_plan: LogicalPlan,
+    ) -> DBResult<ResultSet> {
         // transform to physical plan by planning it
-        let mut physical_plan = self.planning(optimized_logical_plan)?;
-
-        // println!("physical plan: {:?}", physical_plan);
+        let mut physical_plan = self.planning(optimized_logical_plan, false)?;
+        Self::execute_physical_plan(physical_plan.as_mut())
+    }
 
-        // execute query
+    /// execute a physical plan and collect its output rows
+    pub(crate) fn execute_physical_plan(
+        physical_plan: &mut dyn PhysicalPlan,
+    ) -> DBResult<ResultSet> {
         physical_plan.setup()?;
         let mut rs = ResultSet::new(physical_plan.schema()?, Vec::new());
         while let Some(r) = physical_plan.next()? {
@@ -57,29 +91,86 @@ impl SelectHandler {
         Ok(rs)
     }
 
-    fn planning(&self, logical_plan: LogicalPlan) -> DBResult<Box<dyn PhysicalPlan>> {
+    /// transform a logical plan into a physical plan, every plan node is wrapped by
+    /// `Analyze` to collect its statistics if `analyze` is true
+    pub(crate) fn planning(
+        &self,
+        logical_plan: LogicalPlan,
+        analyze: bool,
+    ) -> DBResult<Box<dyn PhysicalPlan>> {
+        self.planning_with(logical_plan, analyze, ScanOptions::default())
+    }
+
+    /// `scan_options` are what the nodes above need from the scan below the plan, i.e.
+    /// the fields they reference, and the filters right above the scan
+    fn planning_with(
+        &self,
+        logical_plan: LogicalPlan,
+        analyze: bool,
+        scan_options: ScanOptions,
+    ) -> DBResult<Box<dyn PhysicalPlan>> {
+        let plan = self.plan_node(logical_plan, analyze, scan_options)?;
+        if analyze {
+            Ok(Box::new(Analyze::new(plan)))
+        } else {
+            Ok(plan)
+        }
+    }
+
+    fn plan_node(
+        &self,
+        logical_plan: LogicalPlan,
+        analyze: bool,
+        scan_options: ScanOptions,
+    ) -> DBResult<Box<dyn PhysicalPlan>> {
         match logical_plan {
             LogicalPlan::Filter { expression, child } => {
-                let child_plan = self.planning(*child)?;
+                // only filters right above the scan are passed down, as nodes like
+                // Limit change which rows are filtered
+                let mut filters = scan_options.filters;
+                filters.push(expression.clone());
+                let child_options = ScanOptions {
+                    projection: with_referenced_fields(
+                        scan_options.projection,
+                        [&expression],
+                    ),
+                    filters,
+                };
+                let child_plan = self.planning_with(*child, analyze, child_options)?;
                 Ok(Box::new(Filter::new(expression, child_plan)))
             }
-            LogicalPlan::Scan { table, .. } => {
+            LogicalPlan::Scan { table, schema } => {
+                let table_name = table;
                 let table = RwLock::read(&self.catalog)
                     .map_err(|_e| {
                         DBError::Unknown("access catalog read lock failed".to_string())
                     })?
-                    .try_get_table(&table)?;
+                    .try_get_table(&table_name)?;
                 let table = RwLock::read(&table).map_err(|_e| {
                     DBError::Unknown("access table read lock failed".to_string())
                 })?;
+                // the plan may be planned before the table is altered, e.g. the plan
+                // of a prepared statement
+                if table.get_table_meta().get_schema() != &schema {
+                    return Err(DBError::ParserError(format!(
+                        "table {table_name} has changed since the query was planned"
+                    )));
+                }
 
-                Ok(table.as_ref().create_scan_op())
+                Ok(table.as_ref().create_scan_op_with(&scan_options))
+            }
+            LogicalPlan::FunctionScan { table, .. } => {
+                Ok(table.0.create_scan_op_with(&scan_options))
             }
-            LogicalPlan::UnResolvedScan { table: _ } => {
+            LogicalPlan::UnResolvedScan { .. } => {
                 Err(DBError::Unknown("Scan is not resolved.".to_string()))
             }
             LogicalPlan::Projection { expressions, child } => {
-                let child_plan = self.planning(*child)?;
+                let child_options = ScanOptions {
+                    projection: with_referenced_fields(Some(vec![]), &expressions),
+                    filters: vec![],
+                };
+                let child_plan = self.planning_with(*child, analyze, child_options)?;
                 Ok(Box::new(Projection::new(expressions, child_plan)))
             }
             LogicalPlan::Aggregator {
@@ -87,7 +178,14 @@ impl SelectHandler {
                 groupings,
                 child,
             } => {
-                let child_plan = self.planning(*child)?;
+                let child_options = ScanOptions {
+                    projection: with_referenced_fields(
+                        Some(vec![]),
+                        aggregators.iter().chain(groupings.iter()),
+                    ),
+                    filters: vec![],
+                };
+                let child_plan = self.planning_with(*child, analyze, child_options)?;
                 Ok(Box::new(HashAggregator::new(
                     aggregators,
                     groupings,
@@ -95,15 +193,47 @@ impl SelectHandler {
                 )))
             }
             LogicalPlan::Sort { options, child } => {
-                let child_plan = self.planning(*child)?;
+                let child_options = ScanOptions {
+                    projection: with_referenced_fields(
+                        scan_options.projection,
+                        options.iter().map(|o| o.expr()),
+                    ),
+                    filters: vec![],
+                };
+                let child_plan = self.planning_with(*child, analyze, child_options)?;
                 Ok(Box::new(Sort::new(options, child_plan)))
             }
             LogicalPlan::Limit {
                 offset,
                 limit,
                 child,
-            } => Ok(Box::new(Limit::new(offset, limit, self.planning(*child)?))),
-            LogicalPlan::UnResolvedHaving { .. } => todo!(),
+            } => {
+                let child_options = ScanOptions {
+                    projection: scan_options.projection,
+                    filters: vec![],
+                };
+                Ok(Box::new(Limit::new(
+                    offset,
+                    limit,
+                    self.planning_with(*child, analyze, child_options)?,
+                )))
+            }
+            LogicalPlan::UnResolvedHaving { prediction, .. } => Err(
+                DBError::ParserError(format!("HAVING {prediction} is not resolved")),
+            ),
         }
     }
 }
+
+/// the fields referenced by the expressions in addition to the given fields, None if all
+/// fields are needed
+fn with_referenced_fields<'a>(
+    fields: Option<Vec<usize>>,
+    exprs: impl IntoIterator<Item = &'a Expression>,
+) -> Option<Vec<usize>> {
+    let mut fields = fields?.into_iter().collect::<BTreeSet<_>>();
+    for expr in exprs {
+        fields.extend(expr.referenced_fields()?);
+    }
+    Some(fields.into_iter().collect())
+}
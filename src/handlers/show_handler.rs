@@ -0,0 +1,109 @@
+use std::sync::{Arc, RwLock};
+
+use sqlparser::ast::{ObjectName, Statement};
+
+use crate::{
+    data_types::DataType,
+    expressions::Literal,
+    row::Row,
+    tables::{FieldInfo, RelationSchema},
+    Catalog, DBError, DBResult, ResultSet,
+};
+
+use super::QueryHandler;
+
+/// ShowHandler lists the tables and views of a schema by SHOW TABLES, and the
+/// columns of a table or view by DESCRIBE or SHOW COLUMNS.
+pub struct ShowHandler {
+    catalog: Arc<RwLock<Catalog>>,
+}
+
+impl QueryHandler for ShowHandler {
+    fn handle(&self, statement: Statement) -> DBResult<ResultSet> {
+        match statement {
+            Statement::ShowTables {
+                db_name, filter, ..
+            } => {
+                if filter.is_some() {
+                    return Err(DBError::ParserError(
+                        "SHOW TABLES does not support filters, query \
+                         information_schema.tables instead."
+                            .to_string(),
+                    ));
+                }
+                self.show_tables(db_name.map(|schema| schema.value))
+            }
+            Statement::ShowColumns {
+                table_name, filter, ..
+            } => {
+                if filter.is_some() {
+                    return Err(DBError::ParserError(
+                        "SHOW COLUMNS does not support filters, query \
+                         information_schema.columns instead."
+                            .to_string(),
+                    ));
+                }
+                self.show_columns(&table_name)
+            }
+            Statement::ExplainTable { table_name, .. } => self.show_columns(&table_name),
+            _ => Err(DBError::should_never_happen()),
+        }
+    }
+}
+
+impl ShowHandler {
+    pub fn new(catalog: Arc<RwLock<Catalog>>) -> Self {
+        Self { catalog }
+    }
+
+    /// tables of the schema, or of the first schema of the search path if not given
+    fn show_tables(&self, schema: Option<String>) -> DBResult<ResultSet> {
+        let catalog = self.read_catalog()?;
+        let schema = match schema {
+            Some(schema) if !catalog.schema_exists(&schema)? => {
+                return Err(DBError::SchemaNotFound(schema))
+            }
+            Some(schema) => schema,
+            None => catalog.search_path()?.swap_remove(0),
+        };
+        let rows = catalog
+            .list_tables()?
+            .into_iter()
+            .filter(|(table_schema, _, _)| *table_schema == schema)
+            .map(|(_, name, _)| Row::new(vec![Literal::String(name)]))
+            .collect();
+        let schema = RelationSchema::new(vec![FieldInfo::new(
+            "name".to_owned(),
+            DataType::String,
+        )]);
+        Ok(ResultSet::new(schema, rows))
+    }
+
+    fn show_columns(&self, table_name: &ObjectName) -> DBResult<ResultSet> {
+        let meta = self
+            .read_catalog()?
+            .get_relation_meta(&table_name.to_string())?;
+        let schema = RelationSchema::new(vec![
+            FieldInfo::new("name".to_owned(), DataType::String),
+            FieldInfo::new("data_type".to_owned(), DataType::String),
+        ]);
+        let rows = meta
+            .get_schema()
+            .get_fields()
+            .iter()
+            .map(|field| {
+                Row::new(vec![
+                    Literal::String(field.name().to_owned()),
+                    Literal::String(field.data_type().to_string()),
+                ])
+            })
+            .collect();
+        Ok(ResultSet::new(schema, rows))
+    }
+
+    fn read_catalog(&self) -> DBResult<std::sync::RwLockReadGuard<'_, Catalog>> {
+        RwLock::read(&self.catalog).map_err(|_e| {
+            DBError::Unknown("access catalog read lock failed.".to_string())
+        })
+    }
+}
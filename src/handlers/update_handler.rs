@@ -0,0 +1,139 @@
+use std::sync::{Arc, RwLock};
+
+use sqlparser::ast::{Assignment, Statement, TableFactor, TableWithJoins};
+
+use crate::{
+    data_types::DataType,
+    expressions::{Expression, Literal},
+    parser::ast_expr_to_plan_expr,
+    tables::FieldInfo,
+    Catalog, DBError, DBResult, ResultSet,
+};
+
+use super::{resolve_table_exprs, QueryHandler};
+
+pub struct UpdateHandler {
+    catalog: Arc<RwLock<Catalog>>,
+}
+
+impl QueryHandler for UpdateHandler {
+    fn handle(&self, statement: Statement) -> DBResult<ResultSet> {
+        match statement {
+            Statement::Update {
+                table,
+                assignments,
+                from,
+                selection,
+                returning,
+            } => {
+                if from.is_some() || returning.is_some() {
+                    return Err(DBError::ParserError(
+                        "UPDATE with FROM or RETURNING is not supported.".to_string(),
+                    ));
+                }
+                let num_updated = self.process_update(table, assignments, selection)?;
+                Ok(ResultSet::affected_rows(num_updated))
+            }
+            _ => Err(DBError::should_never_happen()),
+        }
+    }
+}
+
+impl UpdateHandler {
+    pub(crate) fn new(catalog: Arc<RwLock<Catalog>>) -> Self {
+        Self { catalog }
+    }
+
+    fn process_update(
+        &self,
+        table: TableWithJoins,
+        assignments: Vec<Assignment>,
+        selection: Option<sqlparser::ast::Expr>,
+    ) -> DBResult<usize> {
+        let table_name = match table.relation {
+            TableFactor::Table { name, .. } if table.joins.is_empty() => name,
+            _ => {
+                return Err(DBError::ParserError(
+                    "UPDATE only supports a single table.".to_string(),
+                ))
+            }
+        };
+        let table = RwLock::read(&self.catalog)
+            .map_err(|_e| {
+                DBError::Unknown("access catalog read lock failed.".to_string())
+            })?
+            .try_get_table(&table_name.to_string())?;
+        let schema = RwLock::read(&table)
+            .map_err(|_| {
+                DBError::Unknown("Access read lock of table failed.".to_string())
+            })?
+            .get_table_meta()
+            .get_schema()
+            .clone();
+
+        let mut field_indexes = Vec::with_capacity(assignments.len());
+        let mut exprs = Vec::with_capacity(assignments.len() + 1);
+        for Assignment { id, value } in assignments.iter() {
+            let name = id
+                .last()
+                .map(|ident| ident.value.as_str())
+                .unwrap_or_default();
+            let field_idx = schema
+                .index_of(name)
+                .ok_or(DBError::ParserError(format!("column {name} not found")))?;
+            if field_indexes.contains(&field_idx) {
+                return Err(DBError::ParserError(format!(
+                    "column {name} is assigned more than once"
+                )));
+            }
+            field_indexes.push(field_idx);
+            exprs.push(ast_expr_to_plan_expr(value)?);
+        }
+        if let Some(selection) = &selection {
+            exprs.push(ast_expr_to_plan_expr(selection)?);
+        }
+        let mut resolved_exprs =
+            resolve_table_exprs(&self.catalog, &table_name, exprs, "UPDATE")?;
+        let predicate = selection.and_then(|_| resolved_exprs.pop());
+        let assignments = Iterator::zip(field_indexes.into_iter(), resolved_exprs)
+            .map(|(field_idx, expr)| {
+                let field = schema.get_field_at(field_idx).unwrap();
+                coerce_assignment(expr, field).map(|expr| (field_idx, expr))
+            })
+            .collect::<DBResult<Vec<_>>>()?;
+
+        let mut table = RwLock::write(Arc::as_ref(&table)).map_err(|_| {
+            DBError::Unknown("Access write lock of table failed!".to_string())
+        })?;
+        table.update(predicate.as_ref(), &assignments)
+    }
+}
+
+/// align the data type of an assigned expression with the assigned field, e.g. a string
+/// literal assigned to a DateTime field. Values of narrower numeric types are widened
+/// when they are assigned, like values inserted into the field
+fn coerce_assignment(expr: Expression, field: &FieldInfo) -> DBResult<Expression> {
+    let expr = match &expr {
+        Expression::Literal(Literal::Null) => return Ok(expr),
+        Expression::Literal(literal) => literal
+            .cast_or_maintain_precision(field.data_type().clone())?
+            .map(Expression::Literal)
+            .unwrap_or(expr),
+        _ => expr,
+    };
+    let data_type = expr.data_type();
+    let is_unresolved_literal =
+        matches!(expr, Expression::Literal(_)) && data_type == DataType::Unknown;
+    if data_type == *field.data_type()
+        || data_type.can_widen_to(field.data_type())
+        || data_type == DataType::Unknown && !is_unresolved_literal
+    {
+        Ok(expr)
+    } else {
+        Err(DBError::ParserError(format!(
+            "cannot assign {expr} to column {} of {}",
+            field.name(),
+            field.data_type()
+        )))
+    }
+}
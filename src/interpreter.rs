@@ -53,8 +53,25 @@ impl Interpreter {
             Expression::UnResolvedFunction { .. } => Err(DBError::InterpretingError(
                 "Trying evaluate an unresolved function".to_string(),
             )),
-            Expression::Function(_) => todo!(),
-            Expression::Wildcard => todo!(),
+            Expression::Function(f) if f.is_aggregator() => {
+                Err(DBError::InterpretingError(format!(
+                    "Trying evaluate an aggregator outside of aggregation: {expr}"
+                )))
+            }
+            Expression::Function(f) => {
+                let args = f
+                    .args()
+                    .into_iter()
+                    .map(|arg| Self::eval(arg, row))
+                    .collect::<DBResult<Vec<_>>>()?;
+                f.eval(&args)
+            }
+            Expression::Parameter { .. } => Err(DBError::InterpretingError(format!(
+                "parameter {expr} is not bound"
+            ))),
+            Expression::Wildcard => Err(DBError::InterpretingError(
+                "Wildcard can only be used in count(*)".to_string(),
+            )),
         }
     }
 }
@@ -6,16 +6,37 @@ use crate::{
     DBResult,
 };
 
+/// the result of integer arithmetic, which fails if it overflows
+fn checked<T>(result: Option<T>, op: BinaryOp) -> DBResult<T> {
+    result.ok_or_else(|| InterpretingError(format!("{op:?} operator overflowed")))
+}
+
 pub fn plus_impl(left: Literal, right: Literal) -> DBResult<Literal> {
     match (left, right) {
-        (Literal::Int8(l), Literal::Int8(r)) => Ok(Literal::Int8(l + r)),
-        (Literal::Int16(l), Literal::Int16(r)) => Ok(Literal::Int16(l + r)),
-        (Literal::Int32(l), Literal::Int32(r)) => Ok(Literal::Int32(l + r)),
-        (Literal::Int64(l), Literal::Int64(r)) => Ok(Literal::Int64(l + r)),
-        (Literal::UInt8(l), Literal::UInt8(r)) => Ok(Literal::UInt8(l + r)),
-        (Literal::UInt16(l), Literal::UInt16(r)) => Ok(Literal::UInt16(l + r)),
-        (Literal::UInt32(l), Literal::UInt32(r)) => Ok(Literal::UInt32(l + r)),
-        (Literal::UInt64(l), Literal::UInt64(r)) => Ok(Literal::UInt64(l + r)),
+        (Literal::Int8(l), Literal::Int8(r)) => {
+            checked(l.checked_add(r), BinaryOp::Plus).map(Literal::Int8)
+        }
+        (Literal::Int16(l), Literal::Int16(r)) => {
+            checked(l.checked_add(r), BinaryOp::Plus).map(Literal::Int16)
+        }
+        (Literal::Int32(l), Literal::Int32(r)) => {
+            checked(l.checked_add(r), BinaryOp::Plus).map(Literal::Int32)
+        }
+        (Literal::Int64(l), Literal::Int64(r)) => {
+            checked(l.checked_add(r), BinaryOp::Plus).map(Literal::Int64)
+        }
+        (Literal::UInt8(l), Literal::UInt8(r)) => {
+            checked(l.checked_add(r), BinaryOp::Plus).map(Literal::UInt8)
+        }
+        (Literal::UInt16(l), Literal::UInt16(r)) => {
+            checked(l.checked_add(r), BinaryOp::Plus).map(Literal::UInt16)
+        }
+        (Literal::UInt32(l), Literal::UInt32(r)) => {
+            checked(l.checked_add(r), BinaryOp::Plus).map(Literal::UInt32)
+        }
+        (Literal::UInt64(l), Literal::UInt64(r)) => {
+            checked(l.checked_add(r), BinaryOp::Plus).map(Literal::UInt64)
+        }
         (Literal::Float32(l), Literal::Float32(r)) => Ok(Literal::Float32(l + r)),
         (Literal::Float64(l), Literal::Float64(r)) => Ok(Literal::Float64(l + r)),
         (left, right) => Err(InterpretingError(format!(
@@ -29,14 +50,30 @@ pub fn plus_impl(left: Literal, right: Literal) -> DBResult<Literal> {
 
 pub fn minus_impl(left: Literal, right: Literal) -> DBResult<Literal> {
     match (left, right) {
-        (Literal::Int8(l), Literal::Int8(r)) => Ok(Literal::Int8(l - r)),
-        (Literal::Int16(l), Literal::Int16(r)) => Ok(Literal::Int16(l - r)),
-        (Literal::Int32(l), Literal::Int32(r)) => Ok(Literal::Int32(l - r)),
-        (Literal::Int64(l), Literal::Int64(r)) => Ok(Literal::Int64(l - r)),
-        (Literal::UInt8(l), Literal::UInt8(r)) => Ok(Literal::UInt8(l - r)),
-        (Literal::UInt16(l), Literal::UInt16(r)) => Ok(Literal::UInt16(l - r)),
-        (Literal::UInt32(l), Literal::UInt32(r)) => Ok(Literal::UInt32(l - r)),
-        (Literal::UInt64(l), Literal::UInt64(r)) => Ok(Literal::UInt64(l - r)),
+        (Literal::Int8(l), Literal::Int8(r)) => {
+            checked(l.checked_sub(r), BinaryOp::Minus).map(Literal::Int8)
+        }
+        (Literal::Int16(l), Literal::Int16(r)) => {
+            checked(l.checked_sub(r), BinaryOp::Minus).map(Literal::Int16)
+        }
+        (Literal::Int32(l), Literal::Int32(r)) => {
+            checked(l.checked_sub(r), BinaryOp::Minus).map(Literal::Int32)
+        }
+        (Literal::Int64(l), Literal::Int64(r)) => {
+            checked(l.checked_sub(r), BinaryOp::Minus).map(Literal::Int64)
+        }
+        (Literal::UInt8(l), Literal::UInt8(r)) => {
+            checked(l.checked_sub(r), BinaryOp::Minus).map(Literal::UInt8)
+        }
+        (Literal::UInt16(l), Literal::UInt16(r)) => {
+            checked(l.checked_sub(r), BinaryOp::Minus).map(Literal::UInt16)
+        }
+        (Literal::UInt32(l), Literal::UInt32(r)) => {
+            checked(l.checked_sub(r), BinaryOp::Minus).map(Literal::UInt32)
+        }
+        (Literal::UInt64(l), Literal::UInt64(r)) => {
+            checked(l.checked_sub(r), BinaryOp::Minus).map(Literal::UInt64)
+        }
         (Literal::Float32(l), Literal::Float32(r)) => Ok(Literal::Float32(l - r)),
         (Literal::Float64(l), Literal::Float64(r)) => Ok(Literal::Float64(l - r)),
         (left, right) => Err(InterpretingError(format!(
@@ -71,14 +108,30 @@ pub fn divide_impl(left: Literal, right: Literal) -> DBResult<Literal> {
 
 pub fn multiply_impl(left: Literal, right: Literal) -> DBResult<Literal> {
     match (left, right) {
-        (Literal::Int8(l), Literal::Int8(r)) => Ok(Literal::Int8(l * r)),
-        (Literal::Int16(l), Literal::Int16(r)) => Ok(Literal::Int16(l * r)),
-        (Literal::Int32(l), Literal::Int32(r)) => Ok(Literal::Int32(l * r)),
-        (Literal::Int64(l), Literal::Int64(r)) => Ok(Literal::Int64(l * r)),
-        (Literal::UInt8(l), Literal::UInt8(r)) => Ok(Literal::UInt8(l * r)),
-        (Literal::UInt16(l), Literal::UInt16(r)) => Ok(Literal::UInt16(l * r)),
-        (Literal::UInt32(l), Literal::UInt32(r)) => Ok(Literal::UInt32(l * r)),
-        (Literal::UInt64(l), Literal::UInt64(r)) => Ok(Literal::UInt64(l * r)),
+        (Literal::Int8(l), Literal::Int8(r)) => {
+            checked(l.checked_mul(r), BinaryOp::Multiply).map(Literal::Int8)
+        }
+        (Literal::Int16(l), Literal::Int16(r)) => {
+            checked(l.checked_mul(r), BinaryOp::Multiply).map(Literal::Int16)
+        }
+        (Literal::Int32(l), Literal::Int32(r)) => {
+            checked(l.checked_mul(r), BinaryOp::Multiply).map(Literal::Int32)
+        }
+        (Literal::Int64(l), Literal::Int64(r)) => {
+            checked(l.checked_mul(r), BinaryOp::Multiply).map(Literal::Int64)
+        }
+        (Literal::UInt8(l), Literal::UInt8(r)) => {
+            checked(l.checked_mul(r), BinaryOp::Multiply).map(Literal::UInt8)
+        }
+        (Literal::UInt16(l), Literal::UInt16(r)) => {
+            checked(l.checked_mul(r), BinaryOp::Multiply).map(Literal::UInt16)
+        }
+        (Literal::UInt32(l), Literal::UInt32(r)) => {
+            checked(l.checked_mul(r), BinaryOp::Multiply).map(Literal::UInt32)
+        }
+        (Literal::UInt64(l), Literal::UInt64(r)) => {
+            checked(l.checked_mul(r), BinaryOp::Multiply).map(Literal::UInt64)
+        }
         (Literal::Float32(l), Literal::Float32(r)) => Ok(Literal::Float32(l * r)),
         (Literal::Float64(l), Literal::Float64(r)) => Ok(Literal::Float64(l * r)),
         (left, right) => Err(InterpretingError(format!(
@@ -40,6 +40,8 @@ pub fn cmp_impl(left: &Literal, right: &Literal) -> DBResult<Ordering> {
         (Literal::Float32(l), Literal::Float32(r)) => Ok(l.partial_cmp(r).unwrap()),
         (Literal::Float64(l), Literal::Float64(r)) => Ok(l.partial_cmp(r).unwrap()),
         (Literal::String(l), Literal::String(r)) => Ok(l.cmp(r)),
+        (Literal::Bool(l), Literal::Bool(r)) => Ok(l.cmp(r)),
+        (Literal::DateTime(l), Literal::DateTime(r)) => Ok(l.cmp(r)),
         (left, right) => Err(InterpretingError(format!(
             "{:?} operator not implemented for {:?} and {:?}",
             BinaryOp::Lte,
@@ -8,16 +8,25 @@ pub mod interpreter;
 pub mod logical_plans;
 mod parser;
 pub mod physical_plans;
+mod prepared_statement;
 pub mod tables;
 use catalog::Catalog;
+use data_types::DataType;
 pub use errors::*;
 use expressions::Literal;
-use handlers::{CreateTableHandler, InsertHandler, QueryHandler, SelectHandler};
+use handlers::{
+    AlterTableHandler, CopyHandler, CreateTableHandler, CreateViewHandler, DeleteHandler,
+    DropHandler, ExplainHandler, InsertHandler, QueryHandler, SchemaHandler,
+    SelectHandler, ShowHandler, UpdateHandler,
+};
+use parser::{parse_script, parse_sql, CrackDBStatement};
 use serde::{
     ser::{SerializeMap, SerializeSeq},
     Serialize,
 };
-use tables::RelationSchema;
+use tables::{FieldInfo, RelationSchema, Table};
+
+pub use prepared_statement::PreparedStatement;
 
 pub mod data_types;
 pub mod row;
@@ -28,12 +37,22 @@ use std::sync::{Arc, RwLock};
 
 use crate::row::Row;
 
-use sqlparser::{ast::Statement, dialect::GenericDialect, parser::Parser};
+use sqlparser::ast::Statement;
 
 pub struct CrackDB {
+    catalog: Arc<RwLock<Catalog>>,
     select_handler: Box<dyn QueryHandler>,
     insert_handler: Box<dyn QueryHandler>,
-    create_table_handler: Box<dyn QueryHandler>,
+    create_table_handler: CreateTableHandler,
+    create_view_handler: CreateViewHandler,
+    update_handler: Box<dyn QueryHandler>,
+    delete_handler: Box<dyn QueryHandler>,
+    alter_table_handler: Box<dyn QueryHandler>,
+    drop_handler: Box<dyn QueryHandler>,
+    explain_handler: Box<dyn QueryHandler>,
+    show_handler: Box<dyn QueryHandler>,
+    schema_handler: Box<dyn QueryHandler>,
+    copy_handler: CopyHandler,
 }
 
 impl Default for CrackDB {
@@ -58,6 +77,39 @@ impl ResultSet {
     pub fn new(schema: RelationSchema, rows: Vec<Row<'static>>) -> Self {
         ResultSet { schema, rows }
     }
+    /// write the result set in the arrow ipc file format, which can be read back as a
+    /// `.arrow` or `.feather` table
+    pub fn write_arrow_ipc(&self, writer: impl std::io::Write) -> DBResult<()> {
+        tables::arrow_ipc::write_ipc_file(self, writer)
+    }
+    /// a result set with a single row of the number of rows affected by a statement,
+    /// e.g. UPDATE
+    pub fn affected_rows(num_rows: usize) -> Self {
+        let schema = RelationSchema::new(vec![FieldInfo::new(
+            "affected_rows".to_owned(),
+            DataType::UInt64,
+        )]);
+        let row = Row::new(vec![Literal::UInt64(num_rows as u64)]);
+        ResultSet::new(schema, vec![row])
+    }
+    /// a result set of the number of rows affected by a statement and the line numbers
+    /// of rejected rows, e.g. COPY FROM. The first field is the same as of
+    /// `ResultSet::affected_rows`
+    pub fn affected_rows_with_rejects(num_rows: usize, rejected_lines: Vec<u64>) -> Self {
+        let schema = RelationSchema::new(vec![
+            FieldInfo::new("affected_rows".to_owned(), DataType::UInt64),
+            FieldInfo::new(
+                "rejected_lines".to_owned(),
+                DataType::List(Box::new(DataType::UInt64)),
+            ),
+        ]);
+        let rejected_lines = rejected_lines.into_iter().map(Literal::UInt64).collect();
+        let row = Row::new(vec![
+            Literal::UInt64(num_rows as u64),
+            Literal::List(rejected_lines),
+        ]);
+        ResultSet::new(schema, vec![row])
+    }
 }
 
 pub struct RowWithSchema<'a> {
@@ -103,32 +155,120 @@ impl CrackDB {
         let catalog = Arc::new(RwLock::new(Catalog::new()));
         let select_handler = Box::new(SelectHandler::new(Arc::clone(&catalog)));
         let insert_handler = Box::new(InsertHandler::new(Arc::clone(&catalog)));
-        let create_table_handler =
-            Box::new(CreateTableHandler::new(Arc::clone(&catalog)));
+        let create_table_handler = CreateTableHandler::new(Arc::clone(&catalog));
+        let create_view_handler = CreateViewHandler::new(Arc::clone(&catalog));
+        let update_handler = Box::new(UpdateHandler::new(Arc::clone(&catalog)));
+        let delete_handler = Box::new(DeleteHandler::new(Arc::clone(&catalog)));
+        let alter_table_handler = Box::new(AlterTableHandler::new(Arc::clone(&catalog)));
+        let drop_handler = Box::new(DropHandler::new(Arc::clone(&catalog)));
+        let explain_handler = Box::new(ExplainHandler::new(Arc::clone(&catalog)));
+        let show_handler = Box::new(ShowHandler::new(Arc::clone(&catalog)));
+        let schema_handler = Box::new(SchemaHandler::new(Arc::clone(&catalog)));
+        let copy_handler = CopyHandler::new(Arc::clone(&catalog));
         CrackDB {
+            catalog,
             select_handler,
             insert_handler,
             create_table_handler,
+            create_view_handler,
+            update_handler,
+            delete_handler,
+            alter_table_handler,
+            drop_handler,
+            explain_handler,
+            show_handler,
+            schema_handler,
+            copy_handler,
         }
     }
 
     pub fn execute(&self, query: &str) -> Result<ResultSet, DBError> {
-        let dialect = GenericDialect {};
-        let statements = Parser::parse_sql(&dialect, query)?;
+        let statement = Self::parse_single_statement(query)?;
+        self.execute_statement(statement)
+    }
+
+    /// register a table under the given name, e.g. a file table created with options
+    /// other than the defaults used for files in queries
+    pub fn register_table(&self, name: &str, table: Box<dyn Table>) -> DBResult<()> {
+        self.catalog
+            .read()
+            .map_err(|_| DBError::Unknown("Access db read lock failed.".to_string()))?
+            .add_new_table(name.to_owned(), table, false, false)
+    }
+
+    /// prepare a statement with placeholders `$1`, `$2`, ... or `?` to execute it many
+    /// times with different values
+    pub fn prepare(&self, query: &str) -> DBResult<PreparedStatement<'_>> {
+        let statement = Self::parse_single_statement(query)?;
+        PreparedStatement::new(self, Arc::clone(&self.catalog), statement)
+    }
+
+    fn parse_single_statement(query: &str) -> DBResult<CrackDBStatement> {
+        let statements = parse_sql(query)?;
         if statements.len() != 1 {
             return Err(DBError::ParserError(
                 "only single query statement is supported.".to_string(),
             ));
         }
-        let statement = Iterator::next(&mut statements.into_iter()).unwrap();
+        Ok(Iterator::next(&mut statements.into_iter()).unwrap())
+    }
+
+    /// execute a script of statements separated by semicolons in order, and return
+    /// their results.
+    ///
+    /// The whole script is parsed before executing any statement, so no statement is
+    /// executed if any of them fails to parse. Execution stops at the first failed
+    /// statement, whose (zero-based) index is reported in `DBError::StatementFailed`,
+    /// statements before it are not rolled back.
+    pub fn execute_batch(&self, script: &str) -> DBResult<Vec<ResultSet>> {
+        parse_script(script)?
+            .into_iter()
+            .enumerate()
+            .map(|(idx, statement)| {
+                self.execute_statement(statement)
+                    .map_err(|e| DBError::StatementFailed(idx, Box::new(e)))
+            })
+            .collect()
+    }
+
+    pub(crate) fn execute_statement(
+        &self,
+        statement: CrackDBStatement,
+    ) -> DBResult<ResultSet> {
         // TODO: log the AST in debug level
         // println!("AST: {statement:?}");
         // TODO: warn any present but unused nodes in AST
         // TODO: check and validate AST
+        let statement = match statement {
+            CrackDBStatement::Sql(statement) => *statement,
+            CrackDBStatement::RefreshMaterializedView(name) => {
+                return self.create_view_handler.refresh(&name)
+            }
+            CrackDBStatement::Copy(statement) => {
+                return self.copy_handler.handle(statement)
+            }
+            CrackDBStatement::CreateExternalTable(statement) => {
+                return self.create_table_handler.create_external_table(statement)
+            }
+        };
         match statement {
             Statement::CreateTable { .. } => self.create_table_handler.handle(statement),
+            Statement::CreateView { .. } => self.create_view_handler.handle(statement),
             Statement::Insert { .. } => self.insert_handler.handle(statement),
             Statement::Query(..) => self.select_handler.handle(statement),
+            Statement::Update { .. } => self.update_handler.handle(statement),
+            Statement::Delete { .. } | Statement::Truncate { .. } => {
+                self.delete_handler.handle(statement)
+            }
+            Statement::AlterTable { .. } => self.alter_table_handler.handle(statement),
+            Statement::Drop { .. } => self.drop_handler.handle(statement),
+            Statement::Explain { .. } => self.explain_handler.handle(statement),
+            Statement::ShowTables { .. }
+            | Statement::ShowColumns { .. }
+            | Statement::ExplainTable { .. } => self.show_handler.handle(statement),
+            Statement::CreateSchema { .. } | Statement::SetVariable { .. } => {
+                self.schema_handler.handle(statement)
+            }
             _ => Err(DBError::Unknown("statement not supported.".to_string())),
         }
     }
@@ -1,22 +1,35 @@
 use std::borrow::Borrow;
+use std::fmt::Display;
 use std::slice;
+use std::sync::Arc;
+
+use sqlparser::ast::ObjectName;
 
 use crate::aggregators::aggregator_schema;
 use crate::expressions::Expression;
 use crate::optimizer::rules::Rule;
 use crate::optimizer::{OptimizerContext, OptimizerContextForExpr, OptimizerNode};
-use crate::tables::{FieldInfo, RelationSchema};
+use crate::parser::TableFunction;
+use crate::physical_plans::exprs_to_string;
+use crate::tables::{FieldInfo, RelationSchema, Table};
 use crate::{DBError, DBResult};
 
 #[derive(Debug, Clone)]
 pub enum LogicalPlan {
     UnResolvedScan {
-        table: String,
+        source: ScanSource,
     },
     Scan {
         table: String,
         schema: RelationSchema,
     },
+    /// the scan of the table of a table function, which is created when the scan is
+    /// resolved and not registered in the catalog
+    FunctionScan {
+        function: TableFunction,
+        table: FunctionTable,
+        schema: RelationSchema,
+    },
     Filter {
         expression: Expression,
         child: Box<LogicalPlan>,
@@ -45,12 +58,49 @@ pub enum LogicalPlan {
     },
 }
 
+/// the relation read by a scan, which is resolved by the catalog
+#[derive(Debug, Clone)]
+pub enum ScanSource {
+    /// a table, view or file of the name, e.g. `analytics.orders`
+    Name(ObjectName),
+    /// a call of a table function, e.g. `read_csv('orders.csv', delim => ';')`
+    Function(TableFunction),
+}
+
+impl Display for ScanSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScanSource::Name(name) => name.fmt(f),
+            ScanSource::Function(function) => function.fmt(f),
+        }
+    }
+}
+
+/// the table scanned by a FunctionScan
+#[derive(Clone)]
+pub struct FunctionTable(pub(crate) Arc<dyn Table>);
+
+impl std::fmt::Debug for FunctionTable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("FunctionTable").finish_non_exhaustive()
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum LimitOption {
     Num(usize),
     All,
 }
 
+impl Display for LimitOption {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LimitOption::Num(n) => n.fmt(f),
+            LimitOption::All => write!(f, "ALL"),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct SortOption {
     expr: Expression,
@@ -71,6 +121,13 @@ impl SortOption {
     }
 }
 
+impl Display for SortOption {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let order = if self.asc { "ASC" } else { "DESC" };
+        write!(f, "{} {order}", self.expr)
+    }
+}
+
 impl OptimizerNode for LogicalPlan {
     type Context = OptimizerContext;
 }
@@ -78,10 +135,11 @@ impl OptimizerNode for LogicalPlan {
 impl LogicalPlan {
     pub fn schema(&self) -> DBResult<RelationSchema> {
         match self {
-            LogicalPlan::UnResolvedScan { table: _ } => {
+            LogicalPlan::UnResolvedScan { .. } => {
                 Err(DBError::Unknown("Scan is not resolved.".to_string()))
             }
             LogicalPlan::Scan { table: _, schema } => Ok(schema.clone()),
+            LogicalPlan::FunctionScan { schema, .. } => Ok(schema.clone()),
             LogicalPlan::Filter {
                 expression: _,
                 child,
@@ -108,6 +166,73 @@ impl LogicalPlan {
         }
     }
 
+    /// the child of the plan node, scans have no child
+    pub fn child(&self) -> Option<&LogicalPlan> {
+        match self {
+            LogicalPlan::UnResolvedScan { .. }
+            | LogicalPlan::Scan { .. }
+            | LogicalPlan::FunctionScan { .. } => None,
+            LogicalPlan::Filter { child, .. }
+            | LogicalPlan::Projection { child, .. }
+            | LogicalPlan::Aggregator { child, .. }
+            | LogicalPlan::Sort { child, .. }
+            | LogicalPlan::Limit { child, .. }
+            | LogicalPlan::UnResolvedHaving { child, .. } => Some(child),
+        }
+    }
+
+    /// describe the plan tree line by line, children are indented below their parents,
+    /// e.g. for EXPLAIN.
+    pub fn explain(&self) -> Vec<String> {
+        let (node, child) = match self {
+            LogicalPlan::UnResolvedScan { source } => {
+                (format!("UnResolvedScan: {source}"), None)
+            }
+            LogicalPlan::Scan { table, .. } => (format!("Scan: {table}"), None),
+            LogicalPlan::FunctionScan { function, .. } => {
+                (format!("FunctionScan: {function}"), None)
+            }
+            LogicalPlan::Filter { expression, child } => {
+                (format!("Filter: {expression}"), Some(child))
+            }
+            LogicalPlan::Projection { expressions, child } => (
+                format!("Projection: {}", exprs_to_string(expressions)),
+                Some(child),
+            ),
+            LogicalPlan::Aggregator {
+                aggregators,
+                groupings,
+                child,
+            } => (
+                format!(
+                    "Aggregator: groupings=[{}], aggregators=[{}]",
+                    exprs_to_string(groupings),
+                    exprs_to_string(aggregators)
+                ),
+                Some(child),
+            ),
+            LogicalPlan::Sort { options, child } => {
+                (format!("Sort: {}", exprs_to_string(options)), Some(child))
+            }
+            LogicalPlan::Limit {
+                offset,
+                limit,
+                child,
+            } => (
+                format!("Limit: offset={offset}, limit={limit}"),
+                Some(child),
+            ),
+            LogicalPlan::UnResolvedHaving { prediction, child } => {
+                (format!("UnResolvedHaving: {prediction}"), Some(child))
+            }
+        };
+        let mut lines = vec![node];
+        if let Some(child) = child {
+            lines.extend(child.explain().into_iter().map(|line| format!("  {line}")));
+        }
+        lines
+    }
+
     /// transform plan node in bottom-up style.
     pub fn transform_bottom_up(
         &self,
@@ -117,6 +242,7 @@ impl LogicalPlan {
         match self {
             LogicalPlan::UnResolvedScan { .. } => func(self, context),
             LogicalPlan::Scan { .. } => func(self, context),
+            LogicalPlan::FunctionScan { .. } => func(self, context),
             LogicalPlan::Filter { expression, child } => self
                 .transform_bottom_up_for_single_child_plan(
                     child,
@@ -216,6 +342,7 @@ impl LogicalPlan {
         match self {
             LogicalPlan::UnResolvedScan { .. } => Ok(None),
             LogicalPlan::Scan { .. } => Ok(None),
+            LogicalPlan::FunctionScan { .. } => Ok(None),
             LogicalPlan::Filter { expression, child } => {
                 let expressions = slice::from_ref(expression);
                 self.transform_exprs_for_single_child_plan(
@@ -274,7 +401,7 @@ impl LogicalPlan {
                     |expressions, child| {
                         let new_options = options
                             .iter()
-                            .zip(expressions.into_iter())
+                            .zip(expressions)
                             .map(|(opt, new_expr)| SortOption::new(new_expr, opt.asc()))
                             .collect();
                         LogicalPlan::Sort {
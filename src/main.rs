@@ -1,4 +1,4 @@
-use std::env;
+use std::{env, fs};
 
 use crackdb::CrackDB;
 
@@ -6,11 +6,17 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     env_logger::init();
     
     let args:Vec<String> = env::args().skip(1).collect();
-    let query = args.join(" ");
+    // `-f script.sql` runs the statements of a script file
+    let query = match args.as_slice() {
+        [flag, path] if flag == "-f" => fs::read_to_string(path)?,
+        _ => args.join(" "),
+    };
     log::debug!("query: {}", query);
     let db = CrackDB::new();
-    let rs = db.execute(query.as_str())?;
-    let output = serde_json::to_string(&rs)?;
-    println!("{}", output);
+    // results of statements are printed line by line
+    for rs in db.execute_batch(query.as_str())? {
+        let output = serde_json::to_string(&rs)?;
+        println!("{}", output);
+    }
     Ok(())
 }
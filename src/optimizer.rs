@@ -1,13 +1,20 @@
 use std::sync::{Arc, RwLock};
 
+use sqlparser::ast::ObjectName;
+
 use crate::{
+    catalog::View,
+    expressions::{Expression, Literal},
     functions::FunctionsRegistry,
     logical_plans::LogicalPlan,
     tables::{RelationSchema, Table},
     Catalog, DBError, DBResult,
 };
 
-use self::rules::{get_all_rules, Rule};
+use self::rules::{
+    get_all_rules, get_expr_rules, BindParametersRule, QualifyNamesRule,
+    ResolveLiteralTypesRule, Rule,
+};
 pub mod rules;
 
 /// Optimizer works on tree/graph of nodes, e.g. logical plan or expressions.
@@ -34,6 +41,30 @@ impl OptimizerContext {
             .map_err(|_e| DBError::Unknown("Access db read lock failed.".to_string()))?;
         catalog.try_get_table(table_name)
     }
+
+    fn resolve_object_name(&self, name: &ObjectName) -> DBResult<String> {
+        let catalog = self
+            .catalog
+            .read()
+            .map_err(|_e| DBError::Unknown("Access db read lock failed.".to_string()))?;
+        catalog.resolve_object_name(name)
+    }
+
+    fn qualify_object_name(&self, name: &ObjectName) -> DBResult<ObjectName> {
+        let catalog = self
+            .catalog
+            .read()
+            .map_err(|_e| DBError::Unknown("Access db read lock failed.".to_string()))?;
+        catalog.qualify_object_name(name)
+    }
+
+    fn get_view(&self, name: &str) -> DBResult<Option<View>> {
+        let catalog = self
+            .catalog
+            .read()
+            .map_err(|_e| DBError::Unknown("Access db read lock failed.".to_string()))?;
+        catalog.get_view(name)
+    }
 }
 
 impl OptimizerContextForExpr {
@@ -78,6 +109,61 @@ impl Optimizer {
         Ok(node_under_plan)
     }
 
+    /// bind values to parameters of an optimized plan, the values are checked against
+    /// the data types inferred for the parameters. Only the data types of literals are
+    /// resolved again, since values of parameters without inferred data types may be
+    /// used with them.
+    pub(crate) fn bind_parameters(
+        &self,
+        plan: &LogicalPlan,
+        values: &[Literal],
+    ) -> DBResult<LogicalPlan> {
+        let context = OptimizerContext {
+            catalog: Arc::clone(&self.catalog),
+        };
+        let node_under_plan = BindParametersRule::new(values)
+            .apply(plan, &context)?
+            .unwrap_or_else(|| plan.clone());
+        let rules: [Box<dyn Rule<LogicalPlan>>; 1] =
+            [Box::new(ResolveLiteralTypesRule {})];
+        Self::optimize_with_stage_rules(node_under_plan, &context, &rules)
+    }
+
+    /// qualify the names of the relations scanned by an unresolved plan, e.g. the plan
+    /// of a view, which then reads the same relations whatever the search path is
+    pub(crate) fn qualify_names(&self, plan: &LogicalPlan) -> DBResult<LogicalPlan> {
+        let context = OptimizerContext {
+            catalog: Arc::clone(&self.catalog),
+        };
+        Ok(QualifyNamesRule {}
+            .apply(plan, &context)?
+            .unwrap_or_else(|| plan.clone()))
+    }
+
+    /// optimize a standalone expression against the given schema
+    pub fn optimize_expr(
+        expr: Expression,
+        schema: RelationSchema,
+    ) -> DBResult<Expression> {
+        let context = OptimizerContextForExpr::new(schema);
+        let rules = get_expr_rules();
+        let mut expr_under_plan = expr;
+        let mut changed = true;
+
+        // FIXME: prevent potential infinite looping
+        while changed {
+            changed = false;
+            for rule in rules.iter() {
+                if let Some(new_expr) = rule.apply(&expr_under_plan, &context)? {
+                    expr_under_plan = new_expr;
+                    changed = true;
+                }
+            }
+        }
+
+        Ok(expr_under_plan)
+    }
+
     fn optimize_with_stage_rules(
         node: LogicalPlan,
         context: &OptimizerContext,
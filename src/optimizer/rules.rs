@@ -1,19 +1,27 @@
-use crate::{logical_plans::LogicalPlan, optimizer::OptimizerNode, DBResult};
+use crate::{
+    expressions::Expression, logical_plans::LogicalPlan, optimizer::OptimizerNode,
+    DBResult,
+};
+mod bind_parameters_rule;
 mod push_down_aggregators_rule;
+mod qualify_names_rule;
 mod resolve_expr_rule;
 mod resolve_functions_rule;
 mod resolve_literal_types_rule;
 mod resolve_plan_rule;
+mod validate_plan_rule;
 
 pub use crate::optimizer::rules::resolve_expr_rule::ResolveExprRule;
 use resolve_plan_rule::ResolvePlanRule;
 
-use self::{
-    resolve_functions_rule::ResolveFunctionsRule,
-    resolve_literal_types_rule::ResolveLiteralTypesRule,
-};
+pub(crate) use bind_parameters_rule::BindParametersRule;
+pub(crate) use qualify_names_rule::QualifyNamesRule;
+pub(crate) use resolve_literal_types_rule::ResolveLiteralTypesRule;
+
+use self::resolve_functions_rule::ResolveFunctionsRule;
 
 use push_down_aggregators_rule::PushDownAggregatorsRule;
+use validate_plan_rule::ValidatePlanRule;
 
 /// Optimizer works by applying various rules on tree/graph and transforming the target.
 /// Rule is a interface for all rules.
@@ -32,5 +40,15 @@ pub(crate) fn get_all_rules() -> Vec<Vec<Box<dyn Rule<LogicalPlan>>>> {
             Box::new(ResolveLiteralTypesRule {}),
             Box::new(ResolveFunctionsRule {}),
         ],
+        vec![Box::new(ValidatePlanRule {})],
+    ]
+}
+
+/// rules to resolve a standalone expression, e.g. a constant in INSERT VALUES
+pub(crate) fn get_expr_rules() -> Vec<Box<dyn Rule<Expression>>> {
+    vec![
+        Box::new(ResolveExprRule {}),
+        Box::new(ResolveLiteralTypesRule {}),
+        Box::new(ResolveFunctionsRule {}),
     ]
 }
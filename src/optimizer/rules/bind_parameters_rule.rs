@@ -0,0 +1,84 @@
+use crate::{
+    data_types::DataType,
+    expressions::{Expression, Literal},
+    logical_plans::LogicalPlan,
+    optimizer::{OptimizerContext, OptimizerContextForExpr},
+    DBError, DBResult,
+};
+
+use super::Rule;
+
+/// Replace parameters of a prepared statement by their values, the values are checked
+/// against the data types inferred for the parameters.
+pub(crate) struct BindParametersRule<'a> {
+    values: &'a [Literal],
+}
+
+impl<'a> BindParametersRule<'a> {
+    pub(crate) fn new(values: &'a [Literal]) -> Self {
+        Self { values }
+    }
+}
+
+impl<'a> Rule<LogicalPlan> for BindParametersRule<'a> {
+    fn apply(
+        &self,
+        node: &LogicalPlan,
+        context: &OptimizerContext,
+    ) -> DBResult<Option<LogicalPlan>> {
+        node.transform_exprs(self, context)
+    }
+}
+
+impl<'a> Rule<Expression> for BindParametersRule<'a> {
+    fn apply(
+        &self,
+        node: &Expression,
+        context: &OptimizerContextForExpr,
+    ) -> DBResult<Option<Expression>> {
+        node.transform_bottom_up(context, &mut |expr, _context| match expr {
+            Expression::Parameter { index, data_type } => {
+                let value = self.values.get(*index).ok_or(DBError::ParserError(
+                    format!("no value is bound to parameter {expr}"),
+                ))?;
+                Self::check_value(expr, data_type, value)
+                    .map(|v| Some(Expression::Literal(v)))
+            }
+            _ => Ok(None),
+        })
+    }
+}
+
+impl<'a> BindParametersRule<'a> {
+    /// the value of the parameter in its data type, numbers are converted into the data
+    /// type if it doesn't lose precision, and strings into date times
+    pub(crate) fn check_value(
+        parameter: &Expression,
+        data_type: &DataType,
+        value: &Literal,
+    ) -> DBResult<Literal> {
+        if *data_type == DataType::Unknown
+            || *value == Literal::Null
+            || value.data_type() == *data_type
+        {
+            return Ok(value.clone());
+        }
+        let unresolved = match value {
+            Literal::String(v) => Some(Literal::UnResolvedString(v.clone())),
+            Literal::Bool(_) | Literal::DateTime(_) | Literal::List(_) => None,
+            _ => Some(Literal::UnResolvedNumber(value.to_string())),
+        };
+        let converted = match unresolved {
+            Some(v) => v
+                .cast_or_maintain_precision(data_type.clone())
+                .unwrap_or(None),
+            None => None,
+        };
+        converted
+            .filter(|v| v.data_type() == *data_type)
+            .ok_or(DBError::ParserError(format!(
+                "cannot bind {value} of {} to parameter {parameter} of {data_type}",
+                value.data_type()
+            )))
+    }
+}
@@ -11,7 +11,7 @@ use crate::{
 use super::Rule;
 
 /// This rule can help resolve aggregators in Having, Sort, Projection clause by pushing them down into
-/// Aggregator node.
+/// Aggregator node. Having clauses are turned into Filter nodes once their aggregators are resolved.
 pub struct PushDownAggregatorsRule {}
 
 impl Rule<LogicalPlan> for PushDownAggregatorsRule {
@@ -53,10 +53,9 @@ impl PushDownAggregatorsRule {
                     &expressions,
                     child,
                     |expressions, updated_child| {
-                        let new_options =
-                            Iterator::zip(options.iter(), expressions.into_iter())
-                                .map(|(option, expr)| SortOption::new(expr, option.asc()))
-                                .collect();
+                        let new_options = Iterator::zip(options.iter(), expressions)
+                            .map(|(option, expr)| SortOption::new(expr, option.asc()))
+                            .collect();
                         LogicalPlan::Sort {
                             options: new_options,
                             child: Box::new(updated_child),
@@ -65,6 +64,16 @@ impl PushDownAggregatorsRule {
                 )
             }
             LogicalPlan::UnResolvedHaving { prediction, child } => {
+                let mut aggregators = HashMap::new();
+                Self::extract_aggregators(&context, prediction, &mut aggregators)?;
+                if aggregators.is_empty() {
+                    // nothing to push down, HAVING works as a plain filter
+                    return Ok(Some(LogicalPlan::Filter {
+                        expression: prediction.clone(),
+                        child: child.clone(),
+                    }));
+                }
+
                 let expressions = vec![prediction];
                 Self::resolve_or_push_down_aggregators_helpers(
                     &context,
@@ -208,6 +217,7 @@ impl PushDownAggregatorsRule {
         match node {
             LogicalPlan::UnResolvedScan { .. } => Ok((None, HashMap::new())),
             LogicalPlan::Scan { .. } => Ok((None, HashMap::new())),
+            LogicalPlan::FunctionScan { .. } => Ok((None, HashMap::new())),
             LogicalPlan::Projection { expressions, child } => {
                 let (resolved_exprs, push_down_exprs) =
                     Self::resolve_exprs_against_node_outputs(exprs, node);
@@ -288,7 +298,7 @@ impl PushDownAggregatorsRule {
     ) -> HashMap<String, Expression> {
         let mut resolved_map = HashMap::new();
         for (unresolved, resolved) in
-            Iterator::zip(unresolved_exprs.iter(), resolved_exprs.into_iter())
+            Iterator::zip(unresolved_exprs.iter(), resolved_exprs)
         {
             resolved_map.insert(unresolved.sematic_id(), resolved);
         }
@@ -345,6 +355,7 @@ impl PushDownAggregatorsRule {
         match node {
             LogicalPlan::UnResolvedScan { .. } => vec![],
             LogicalPlan::Scan { .. } => vec![],
+            LogicalPlan::FunctionScan { .. } => vec![],
             LogicalPlan::Filter { child, .. } => Self::plan_outputs(child),
             LogicalPlan::Projection { expressions, .. } => expressions.iter().collect(),
             LogicalPlan::Aggregator {
@@ -364,6 +375,7 @@ impl PushDownAggregatorsRule {
             node,
             LogicalPlan::UnResolvedScan { .. }
                 | LogicalPlan::Scan { .. }
+                | LogicalPlan::FunctionScan { .. }
                 | LogicalPlan::Projection { .. }
                 | LogicalPlan::Aggregator { .. }
         )
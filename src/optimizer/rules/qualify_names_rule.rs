@@ -0,0 +1,37 @@
+use crate::{
+    logical_plans::{LogicalPlan, ScanSource},
+    optimizer::OptimizerContext,
+    DBResult,
+};
+
+use super::Rule;
+
+/// Qualify the names of the relations of UnResolvedScan nodes by their schemas, e.g.
+/// `orders` into `main.orders`.
+pub(crate) struct QualifyNamesRule {}
+
+impl Rule<LogicalPlan> for QualifyNamesRule {
+    fn apply(
+        &self,
+        node: &LogicalPlan,
+        context: &OptimizerContext,
+    ) -> DBResult<Option<LogicalPlan>> {
+        node.transform_bottom_up(context, Self::qualify_names)
+    }
+}
+
+impl QualifyNamesRule {
+    fn qualify_names(
+        logical_plan: &LogicalPlan,
+        context: &OptimizerContext,
+    ) -> DBResult<Option<LogicalPlan>> {
+        match logical_plan {
+            LogicalPlan::UnResolvedScan {
+                source: ScanSource::Name(name),
+            } => Ok(Some(LogicalPlan::UnResolvedScan {
+                source: ScanSource::Name(context.qualify_object_name(name)?),
+            })),
+            _ => Ok(None),
+        }
+    }
+}
@@ -33,11 +33,20 @@ impl ResolveFunctionsRule {
         context: &OptimizerContextForExpr,
     ) -> DBResult<Option<Expression>> {
         match expr {
-            Expression::UnResolvedFunction { name, args } => {
+            Expression::UnResolvedFunction {
+                name,
+                args,
+                clauses,
+            } => {
                 let func = context
                     .functions_registry
-                    .get_function(name.as_str(), args)?
+                    .get_function(name.as_str(), args, clauses)?
                     .ok_or(DBError::Unknown(format!("Unrecognized function {name}")))?;
+                if !func.is_aggregator() && !clauses.is_empty() {
+                    return Err(DBError::ParserError(format!(
+                        "ORDER BY and FILTER are only supported for aggregators: {expr}"
+                    )));
+                }
                 Ok(Some(Expression::Function(func)))
             }
             _ => Ok(None),
@@ -93,7 +93,8 @@ impl ResolveLiteralTypesRule {
     }
 
     /// transform a expression (focus on literal expressions) with given data type hint.
-    /// For example, transform an UnresolvedString into a String or DataTime etc.
+    /// For example, transform an UnresolvedString into a String or DataTime etc, or
+    /// infer the data type of a parameter.
     fn transform_expression_with_type_hint(
         expr: &Expression,
         type_hint: DataType,
@@ -102,6 +103,13 @@ impl ResolveLiteralTypesRule {
             Expression::Literal(literal) => literal
                 .cast_or_maintain_precision(type_hint)
                 .map(|opt_literal| opt_literal.map(Expression::Literal)),
+            Expression::Parameter {
+                index,
+                data_type: DataType::Unknown,
+            } => Ok(Some(Expression::Parameter {
+                index: *index,
+                data_type: type_hint,
+            })),
             // TODO: do we want validate the expression data type with type_hint?
             _ => Ok(None),
         }
@@ -1,10 +1,23 @@
-use std::sync::RwLock;
+use std::{
+    collections::HashSet,
+    sync::{Arc, RwLock},
+};
 
-use crate::{logical_plans::LogicalPlan, optimizer::OptimizerContext, DBError, DBResult};
+use crate::{
+    catalog::{create_function_table, View},
+    logical_plans::{FunctionTable, LogicalPlan, ScanSource},
+    optimizer::OptimizerContext,
+    DBError, DBResult,
+};
 
 use super::Rule;
 
-/// Resolve UnResolvedScan node.
+/// Resolve UnResolvedScan node, the plan of a (not materialized) view is inlined, and
+/// the table of a table function is created for a FunctionScan.
+///
+/// Table names of `schema.table` or `crackdb.schema.table` are qualified names, while
+/// unqualified names are looked up in the schemas of the search path. Scan nodes always
+/// reference tables by qualified names.
 pub struct ResolvePlanRule {}
 
 impl Rule<LogicalPlan> for ResolvePlanRule {
@@ -23,21 +36,75 @@ impl ResolvePlanRule {
         context: &OptimizerContext,
     ) -> DBResult<Option<LogicalPlan>> {
         match logical_plan {
-            LogicalPlan::UnResolvedScan { table } => {
-                context.try_get_table(table.as_str()).and_then(|tbl| {
-                    RwLock::read(&tbl)
-                        .map_err(|_e| {
-                            DBError::Unknown("Access tabl read lock failed.".to_string())
-                        })
-                        .map(|tbl| {
-                            Some(LogicalPlan::Scan {
-                                table: table.to_string(),
-                                schema: tbl.get_table_meta().get_schema().clone(),
-                            })
-                        })
-                })
+            LogicalPlan::UnResolvedScan { source } => {
+                let table = match source {
+                    ScanSource::Name(name) => context.resolve_object_name(name)?,
+                    ScanSource::Function(function) => {
+                        let table = create_function_table(function)?;
+                        return Ok(Some(LogicalPlan::FunctionScan {
+                            function: function.clone(),
+                            schema: table.get_table_meta().get_schema().clone(),
+                            table: FunctionTable(Arc::from(table)),
+                        }));
+                    }
+                };
+                if let Some(view) = Self::inlined_view(&table, context)? {
+                    Self::check_cycle(&table, context)?;
+                    let plan = view.plan();
+                    let resolved =
+                        plan.transform_bottom_up(context, Self::resolve_logical_plan)?;
+                    return Ok(Some(resolved.unwrap_or_else(|| plan.clone())));
+                }
+                let tbl = context.try_get_table(&table).map_err(|e| match e {
+                    // report the name as given in the query
+                    DBError::TableNotFound(_) => {
+                        DBError::TableNotFound(source.to_string())
+                    }
+                    e => e,
+                })?;
+                let tbl = RwLock::read(&tbl).map_err(|_e| {
+                    DBError::Unknown("Access tabl read lock failed.".to_string())
+                })?;
+                Ok(Some(LogicalPlan::Scan {
+                    table,
+                    schema: tbl.get_table_meta().get_schema().clone(),
+                }))
             }
             _ => Ok(None),
         }
     }
+
+    /// the view of the qualified name, unless it is materialized
+    fn inlined_view(name: &str, context: &OptimizerContext) -> DBResult<Option<View>> {
+        Ok(context
+            .get_view(name)?
+            .filter(|view| !view.is_materialized()))
+    }
+
+    /// fail if inlining the view would never end, i.e. it reads itself through the
+    /// views it reads
+    fn check_cycle(name: &str, context: &OptimizerContext) -> DBResult<()> {
+        let mut visited = HashSet::new();
+        let mut next = Some(name.to_owned());
+        while let Some(view_name) = next {
+            if !visited.insert(view_name.clone()) {
+                return Err(DBError::ParserError(format!(
+                    "view {name} cannot reference itself"
+                )));
+            }
+            next = None;
+            if let Some(view) = Self::inlined_view(&view_name, context)? {
+                let scans = std::iter::successors(Some(view.plan()), |p| p.child());
+                for plan in scans {
+                    if let LogicalPlan::UnResolvedScan {
+                        source: ScanSource::Name(name),
+                    } = plan
+                    {
+                        next = Some(context.resolve_object_name(name)?);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,84 @@
+use crate::{
+    expressions::Expression,
+    logical_plans::LogicalPlan,
+    optimizer::{OptimizerContext, OptimizerContextForExpr},
+    tables::RelationSchema,
+    DBError, DBResult,
+};
+
+use super::Rule;
+
+/// This rule checks that every field reference of the plan is resolved after other rules,
+/// so that mistakes like projecting a column which is neither grouped nor aggregated are
+/// reported before execution.
+///
+/// It never transforms the plan.
+pub struct ValidatePlanRule {}
+
+impl Rule<LogicalPlan> for ValidatePlanRule {
+    fn apply(
+        &self,
+        node: &LogicalPlan,
+        context: &<LogicalPlan as crate::optimizer::OptimizerNode>::Context,
+    ) -> crate::DBResult<Option<LogicalPlan>> {
+        node.transform_bottom_up(context, Self::validate_field_refs)
+    }
+}
+
+impl ValidatePlanRule {
+    fn validate_field_refs(
+        node: &LogicalPlan,
+        _context: &OptimizerContext,
+    ) -> DBResult<Option<LogicalPlan>> {
+        let (expressions, child): (Vec<&Expression>, _) = match node {
+            LogicalPlan::UnResolvedScan { .. }
+            | LogicalPlan::Scan { .. }
+            | LogicalPlan::FunctionScan { .. } => return Ok(None),
+            LogicalPlan::Filter { expression, child } => (vec![expression], child),
+            LogicalPlan::Projection { expressions, child } => {
+                (expressions.iter().collect(), child)
+            }
+            LogicalPlan::Aggregator {
+                aggregators,
+                groupings,
+                child,
+            } => (aggregators.iter().chain(groupings).collect(), child),
+            LogicalPlan::Sort { options, child } => {
+                (options.iter().map(|option| option.expr()).collect(), child)
+            }
+            LogicalPlan::Limit { .. } => return Ok(None),
+            LogicalPlan::UnResolvedHaving { prediction, child } => {
+                (vec![prediction], child)
+            }
+        };
+
+        let context = OptimizerContextForExpr::new(RelationSchema::empty());
+        for expr in expressions {
+            expr.transform_top_down(&context, &mut |expr, _context| match expr {
+                Expression::UnResolvedFieldRef(name) if Self::is_aggregated(child) => {
+                    Err(DBError::ParserError(format!(
+                        "column {name} must appear in the GROUP BY clause or be used in \
+                         an aggregate function"
+                    )))
+                }
+                Expression::UnResolvedFieldRef(name) => {
+                    Err(DBError::ParserError(format!("column {name} not found")))
+                }
+                _ => Ok(None),
+            })?;
+        }
+        Ok(None)
+    }
+
+    /// whether the outputs of the given node are results of an aggregation
+    fn is_aggregated(node: &LogicalPlan) -> bool {
+        match node {
+            LogicalPlan::Aggregator { .. } => true,
+            LogicalPlan::Filter { child, .. }
+            | LogicalPlan::Sort { child, .. }
+            | LogicalPlan::Limit { child, .. }
+            | LogicalPlan::UnResolvedHaving { child, .. } => Self::is_aggregated(child),
+            _ => false,
+        }
+    }
+}
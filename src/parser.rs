@@ -1,27 +1,492 @@
+use std::fmt::Display;
+use std::ops::ControlFlow;
+
 use sqlparser::ast::{
-    BinaryOperator, Expr, Function, FunctionArgExpr, OrderByExpr, SelectItem, SetExpr,
-    TableFactor, UnaryOperator, Value,
+    visit_expressions_mut, ArrayAgg, BinaryOperator, ColumnDef, CopySource, Expr,
+    Function, FunctionArg, FunctionArgExpr, ObjectName, OrderByExpr, SelectItem, SetExpr,
+    Statement, TableFactor, UnaryOperator, Value,
 };
 
+use sqlparser::dialect::Dialect;
+use sqlparser::keywords::Keyword;
+use sqlparser::parser::{IsOptional, Parser, ParserError};
+use sqlparser::tokenizer::{Token, Tokenizer};
+
 use crate::{
-    expressions::{BinaryOp, Expression, Literal, UnaryOp},
-    logical_plans::{LimitOption, LogicalPlan, SortOption},
+    data_types::DataType,
+    expressions::{AggregateClauses, BinaryOp, Expression, Literal, UnaryOp},
+    logical_plans::{LimitOption, LogicalPlan, ScanSource, SortOption},
+    optimizer::OptimizerContextForExpr,
+    tables::RelationSchema,
     DBError, DBResult,
 };
 
-fn remove_quotes(value: String) -> String {
-    if is_quoted_str(value.as_str()) {
-        let bytes = &value.as_bytes()[1..value.len() - 1];
-        String::from_utf8_lossy(bytes).to_string()
+/// The SQL dialect of CrackDB.
+///
+/// It follows the identifier rules of `GenericDialect`, and additionally supports the
+/// aggregate `FILTER (WHERE ...)` clause in the select list, and `WITHIN GROUP (ORDER BY
+/// ...)` of `array_agg` besides its inline `ORDER BY`.
+#[derive(Debug, Default)]
+pub struct CrackDBDialect {}
+
+impl Dialect for CrackDBDialect {
+    fn is_identifier_start(&self, ch: char) -> bool {
+        ch.is_alphabetic() || ch == '_' || ch == '#' || ch == '@'
+    }
+
+    fn is_identifier_part(&self, ch: char) -> bool {
+        ch.is_alphabetic()
+            || ch.is_ascii_digit()
+            || ch == '@'
+            || ch == '$'
+            || ch == '#'
+            || ch == '_'
+    }
+
+    fn supports_filter_during_aggregation(&self) -> bool {
+        true
+    }
+
+    fn parse_prefix(&self, parser: &mut Parser) -> Option<Result<Expr, ParserError>> {
+        let is_array_agg = matches!(
+            &parser.peek_token().token,
+            Token::Word(word) if word.keyword == Keyword::ARRAY_AGG
+        ) && parser.peek_nth_token(1).token == Token::LParen;
+        if !is_array_agg {
+            return None;
+        }
+        parser.next_token();
+        Some(Self::parse_array_agg(parser))
+    }
+}
+
+impl CrackDBDialect {
+    /// `array_agg(expr [ORDER BY ...]) [WITHIN GROUP (ORDER BY ...)]`, the ORDER BY of
+    /// WITHIN GROUP is parsed as the inline one
+    fn parse_array_agg(parser: &mut Parser) -> Result<Expr, ParserError> {
+        let mut array_agg = match parser.parse_array_agg_expr()? {
+            Expr::ArrayAgg(array_agg) => array_agg,
+            expr => return Ok(expr),
+        };
+        if parser.parse_keywords(&[Keyword::WITHIN, Keyword::GROUP]) {
+            if array_agg.order_by.is_some() {
+                return Err(ParserError::ParserError(
+                    "array_agg cannot have both ORDER BY and WITHIN GROUP".to_owned(),
+                ));
+            }
+            parser.expect_token(&Token::LParen)?;
+            parser.expect_keywords(&[Keyword::ORDER, Keyword::BY])?;
+            array_agg.order_by =
+                Some(parser.parse_comma_separated(Parser::parse_order_by_expr)?);
+            parser.expect_token(&Token::RParen)?;
+            array_agg.within_group = true;
+        }
+        Ok(Expr::ArrayAgg(array_agg))
+    }
+}
+
+/// A statement of CrackDB, which is either a statement of sqlparser or one of the
+/// statements sqlparser does not support.
+#[derive(Debug, Clone)]
+pub(crate) enum CrackDBStatement {
+    Sql(Box<Statement>),
+    /// `REFRESH MATERIALIZED VIEW name`
+    RefreshMaterializedView(ObjectName),
+    /// `COPY`, which is parsed by CrackDB to support options sqlparser does not know
+    Copy(CopyStatement),
+    CreateExternalTable(CreateExternalTable),
+}
+
+/// `CREATE EXTERNAL TABLE [IF NOT EXISTS] name [(columns)] STORED AS format
+/// LOCATION 'file' [OPTIONS (options)]`
+#[derive(Debug, Clone)]
+pub(crate) struct CreateExternalTable {
+    pub(crate) name: ObjectName,
+    pub(crate) if_not_exists: bool,
+    /// the declared columns, or empty if the columns are read from the file
+    pub(crate) columns: Vec<ColumnDef>,
+    pub(crate) format: String,
+    pub(crate) location: String,
+    pub(crate) options: Vec<FileOption>,
+}
+
+/// A table function in FROM reading a file, e.g. `read_csv('file.csv', delim => '|')`.
+///
+/// The table of a table function is created when its scan is resolved, see
+/// `create_function_table`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableFunction {
+    pub(crate) name: String,
+    pub(crate) path: String,
+    pub(crate) options: Vec<FileOption>,
+}
+
+impl Display for TableFunction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}('{}'", self.name, self.path.replace('\'', "''"))?;
+        for option in &self.options {
+            match &option.value {
+                Some(value) => {
+                    write!(f, ", {} => '{}'", option.name, value.replace('\'', "''"))?
+                }
+                None => write!(f, ", {} => true", option.name)?,
+            }
+        }
+        f.write_str(")")
+    }
+}
+
+impl TableFunction {
+    /// the table function of the name and arguments of a table in FROM, the first
+    /// argument is the path and the others are named options
+    fn try_new(name: &ObjectName, args: &[FunctionArg]) -> DBResult<Self> {
+        let invalid = || {
+            DBError::ParserError(format!(
+                "invalid arguments of {name}, expected a path and named options"
+            ))
+        };
+        let value_of = |arg: &FunctionArgExpr| match arg {
+            FunctionArgExpr::Expr(Expr::Value(value)) => match value {
+                Value::SingleQuotedString(s) => Ok(s.clone()),
+                Value::Number(n, _) => Ok(n.clone()),
+                Value::Boolean(b) => Ok(b.to_string()),
+                _ => Err(invalid()),
+            },
+            FunctionArgExpr::Expr(Expr::UnaryOp {
+                op: UnaryOperator::Minus,
+                expr,
+            }) => match expr.as_ref() {
+                Expr::Value(Value::Number(n, _)) => Ok(format!("-{n}")),
+                _ => Err(invalid()),
+            },
+            _ => Err(invalid()),
+        };
+        let (path, options) = match args {
+            [FunctionArg::Unnamed(path), options @ ..] => (value_of(path)?, options),
+            _ => return Err(invalid()),
+        };
+        let options = options
+            .iter()
+            .map(|arg| match arg {
+                FunctionArg::Named { name, arg } => {
+                    Ok(FileOption::new(&name.value, Some(value_of(arg)?)))
+                }
+                FunctionArg::Unnamed(_) => Err(invalid()),
+            })
+            .collect::<DBResult<Vec<_>>>()?;
+        Ok(Self {
+            name: name.to_string().to_lowercase(),
+            path,
+            options,
+        })
+    }
+}
+
+/// `COPY {table [(columns)] | (query)} {FROM | TO} 'file' [WITH] [(options)]`
+#[derive(Debug, Clone)]
+pub(crate) struct CopyStatement {
+    pub(crate) source: CopySource,
+    /// whether it is COPY TO, or COPY FROM
+    pub(crate) to: bool,
+    pub(crate) path: String,
+    pub(crate) options: Vec<FileOption>,
+}
+
+/// An option of reading or writing files, e.g. `delimiter '|'`, `format csv` or
+/// `header`. Names are case insensitive.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct FileOption {
+    name: String,
+    value: Option<String>,
+}
+
+impl Display for FileOption {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.value {
+            Some(value) => write!(f, "{} '{value}'", self.name),
+            None => self.name.fmt(f),
+        }
+    }
+}
+
+impl FileOption {
+    pub(crate) fn new(name: &str, value: Option<String>) -> Self {
+        Self {
+            name: name.to_lowercase(),
+            value,
+        }
+    }
+
+    /// the lower case name of the option
+    pub(crate) fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub(crate) fn string_value(&self) -> DBResult<&str> {
+        self.value
+            .as_deref()
+            .ok_or_else(|| self.invalid("a value is required"))
+    }
+
+    /// the value as a boolean, options without values are true, e.g. `header`
+    pub(crate) fn bool_value(&self) -> DBResult<bool> {
+        match self.value.as_deref().map(str::to_lowercase).as_deref() {
+            None | Some("true") | Some("on") | Some("1") => Ok(true),
+            Some("false") | Some("off") | Some("0") => Ok(false),
+            Some(_) => Err(self.invalid("expected a boolean")),
+        }
+    }
+
+    /// the value as a single ascii character, e.g. a delimiter, or `\t` for tabs
+    pub(crate) fn char_value(&self) -> DBResult<u8> {
+        match self.string_value()?.as_bytes() {
+            [c] if c.is_ascii() => Ok(*c),
+            b"\\t" => Ok(b'\t'),
+            _ => Err(self.invalid("expected a single ascii character")),
+        }
+    }
+
+    pub(crate) fn usize_value(&self) -> DBResult<usize> {
+        self.string_value()?
+            .parse()
+            .map_err(|_| self.invalid("expected a non-negative integer"))
+    }
+
+    /// the number of records to sample, None for all records of `-1` or `all`
+    pub(crate) fn sample_size_value(&self) -> DBResult<Option<usize>> {
+        match self.string_value()?.to_lowercase().as_str() {
+            "-1" | "all" => Ok(None),
+            _ => self.usize_value().map(Some),
+        }
+    }
+
+    fn invalid(&self, msg: &str) -> DBError {
+        DBError::ParserError(format!("invalid option {self}: {msg}"))
+    }
+}
+
+/// parse the types of columns, e.g. `id int, note String`
+pub(crate) fn parse_column_types(text: &str) -> DBResult<Vec<(String, DataType)>> {
+    let dialect = CrackDBDialect::default();
+    let mut parser = Parser::new(&dialect).try_with_sql(text)?;
+    let column_types = parser.parse_comma_separated(|parser| {
+        let name = parser.parse_identifier()?.value;
+        Ok((name, DataType::from(parser.parse_data_type()?)))
+    })?;
+    if parser.peek_token().token != Token::EOF {
+        parser.expected("end of column types", parser.peek_token())?;
+    }
+    match column_types
+        .iter()
+        .find(|(_, data_type)| *data_type == DataType::Unknown)
+    {
+        Some((name, _)) => Err(DBError::ParserError(format!(
+            "the type of column {name} is not supported"
+        ))),
+        None => Ok(column_types),
+    }
+}
+
+/// parse SQL text of one or more statements separated by semicolons
+pub(crate) fn parse_sql(sql: &str) -> DBResult<Vec<CrackDBStatement>> {
+    parse_statements(sql).map_err(|(_, e)| e)
+}
+
+/// parse SQL text of one or more statements like `parse_sql`, but the error of a
+/// statement failing to parse is reported in `DBError::StatementFailed` with the
+/// (zero-based) index of the statement.
+pub(crate) fn parse_script(sql: &str) -> DBResult<Vec<CrackDBStatement>> {
+    parse_statements(sql).map_err(|(idx, e)| DBError::StatementFailed(idx, Box::new(e)))
+}
+
+/// the statements of SQL text, or the index of the statement failing to parse and its
+/// error
+fn parse_statements(sql: &str) -> Result<Vec<CrackDBStatement>, (usize, DBError)> {
+    let dialect = CrackDBDialect::default();
+    let tokens = Tokenizer::new(&dialect, sql).tokenize().map_err(|e| {
+        let idx = statement_index_at(&dialect, sql, e.line, e.col);
+        (idx, DBError::from(ParserError::from(e)))
+    })?;
+    let mut parser = Parser::new(&dialect).with_tokens(tokens);
+    let mut statements = Vec::new();
+    let mut expecting_statement_delimiter = false;
+    loop {
+        while parser.consume_token(&Token::SemiColon) {
+            expecting_statement_delimiter = false;
+        }
+        if parser.peek_token().token == Token::EOF {
+            break;
+        }
+        if expecting_statement_delimiter {
+            // the last statement is followed by more than a statement
+            let e = parser.expected::<()>("end of statement", parser.peek_token());
+            return Err((statements.len() - 1, e.unwrap_err().into()));
+        }
+        let statement =
+            parse_statement(&mut parser).map_err(|e| (statements.len(), e))?;
+        statements.push(statement);
+        expecting_statement_delimiter = true;
+    }
+    Ok(statements)
+}
+
+/// the index of the statement at the line and column of SQL text, i.e. the number of
+/// statements ended by semicolons before it
+fn statement_index_at(dialect: &dyn Dialect, sql: &str, line: u64, col: u64) -> usize {
+    let offset = sql
+        .split_inclusive('\n')
+        .take(line.saturating_sub(1) as usize)
+        .map(str::len)
+        .sum::<usize>();
+    let offset = sql[offset..]
+        .char_indices()
+        .nth(col.saturating_sub(1) as usize)
+        .map_or(sql.len(), |(idx, _)| offset + idx);
+    // the text before the location is tokenized, as tokens before it are valid
+    let tokens = Tokenizer::new(dialect, &sql[..offset])
+        .tokenize()
+        .unwrap_or_default();
+    let mut num_statements = 0;
+    let mut in_statement = false;
+    for token in tokens {
+        match token {
+            Token::SemiColon if in_statement => {
+                num_statements += 1;
+                in_statement = false;
+            }
+            Token::SemiColon | Token::Whitespace(_) => {}
+            _ => in_statement = true,
+        }
+    }
+    num_statements
+}
+
+/// parse a statement, which may be a statement only CrackDB supports
+fn parse_statement(parser: &mut Parser) -> DBResult<CrackDBStatement> {
+    let token = parser.peek_token().token;
+    let statement = match token {
+        Token::Word(word) if word.value.eq_ignore_ascii_case("refresh") => {
+            parser.next_token();
+            parser.expect_keywords(&[Keyword::MATERIALIZED, Keyword::VIEW])?;
+            CrackDBStatement::RefreshMaterializedView(parser.parse_object_name()?)
+        }
+        Token::Word(word) if word.keyword == Keyword::COPY => {
+            parser.next_token();
+            CrackDBStatement::Copy(parse_copy(parser)?)
+        }
+        Token::Word(word)
+            if word.keyword == Keyword::CREATE
+                && matches!(
+                    parser.peek_nth_token(1).token,
+                    Token::Word(w) if w.keyword == Keyword::EXTERNAL
+                ) =>
+        {
+            parser.expect_keywords(&[Keyword::CREATE, Keyword::EXTERNAL])?;
+            CrackDBStatement::CreateExternalTable(parse_create_external_table(parser)?)
+        }
+        _ => CrackDBStatement::Sql(Box::new(parser.parse_statement()?)),
+    };
+    Ok(statement)
+}
+
+/// parse COPY after the keyword, like sqlparser but with options of any names
+fn parse_copy(parser: &mut Parser) -> DBResult<CopyStatement> {
+    let source = if parser.consume_token(&Token::LParen) {
+        let query = parser.parse_query()?;
+        parser.expect_token(&Token::RParen)?;
+        CopySource::Query(Box::new(query))
     } else {
-        value
+        CopySource::Table {
+            table_name: parser.parse_object_name()?,
+            columns: parser
+                .parse_parenthesized_column_list(IsOptional::Optional, false)?,
+        }
+    };
+    let to = match parser.parse_one_of_keywords(&[Keyword::FROM, Keyword::TO]) {
+        Some(Keyword::TO) => true,
+        Some(_) => false,
+        None => parser.expected("FROM or TO", parser.peek_token())?,
+    };
+    if !to && matches!(source, CopySource::Query(_)) {
+        return Err(DBError::ParserError(
+            "COPY FROM does not support queries".to_owned(),
+        ));
     }
+    let path = parser.parse_literal_string()?;
+    let _ = parser.parse_keyword(Keyword::WITH);
+    let mut options = vec![];
+    if parser.consume_token(&Token::LParen) {
+        options = parser.parse_comma_separated(parse_file_option)?;
+        parser.expect_token(&Token::RParen)?;
+    }
+    Ok(CopyStatement {
+        source,
+        to,
+        path,
+        options,
+    })
+}
+
+/// parse CREATE EXTERNAL TABLE after the keywords
+fn parse_create_external_table(parser: &mut Parser) -> DBResult<CreateExternalTable> {
+    parser.expect_keyword(Keyword::TABLE)?;
+    let if_not_exists =
+        parser.parse_keywords(&[Keyword::IF, Keyword::NOT, Keyword::EXISTS]);
+    let name = parser.parse_object_name()?;
+    let (columns, constraints) = parser.parse_columns()?;
+    if !constraints.is_empty() {
+        return Err(DBError::ParserError(
+            "external tables do not support constraints".to_owned(),
+        ));
+    }
+    parser.expect_keywords(&[Keyword::STORED, Keyword::AS])?;
+    let format = parser.parse_identifier()?.value.to_lowercase();
+    parser.expect_keyword(Keyword::LOCATION)?;
+    let location = parser.parse_literal_string()?;
+    let mut options = vec![];
+    if parser.parse_keyword(Keyword::OPTIONS) {
+        parser.expect_token(&Token::LParen)?;
+        options = parser.parse_comma_separated(parse_file_option)?;
+        parser.expect_token(&Token::RParen)?;
+    }
+    Ok(CreateExternalTable {
+        name,
+        if_not_exists,
+        columns,
+        format,
+        location,
+        options,
+    })
 }
 
-fn is_quoted_str(value: &str) -> bool {
-    value.len() > 1
-        && (value.starts_with('\'') && value.ends_with('\'')
-            || value.starts_with('"') && value.ends_with('"'))
+/// parse `name [value]`, the value is a word, a string or a number. Names may be
+/// quoted, e.g. `'delimiter' '|'`.
+fn parse_file_option(parser: &mut Parser) -> Result<FileOption, ParserError> {
+    let name = match parser.peek_token().token {
+        Token::SingleQuotedString(_) => parser.parse_literal_string()?,
+        _ => parser.parse_identifier()?.value,
+    };
+    let value = match parser.peek_token().token {
+        Token::Comma | Token::RParen => None,
+        _ => {
+            let token = parser.next_token();
+            match token.token {
+                Token::Word(word) => Some(word.value),
+                Token::SingleQuotedString(s) => Some(s),
+                Token::Number(n, _) => Some(n),
+                _ => parser.expected("option value", token)?,
+            }
+        }
+    };
+    Ok(FileOption::new(&name, value))
+}
+
+/// the name of SQL text, e.g. `analytics."daily.orders"`, None if the text is not a name
+pub(crate) fn parse_object_name(text: &str) -> Option<ObjectName> {
+    let dialect = CrackDBDialect::default();
+    let mut parser = Parser::new(&dialect).try_with_sql(text).ok()?;
+    let name = parser.parse_object_name().ok()?;
+    (parser.peek_token().token == Token::EOF).then_some(name)
 }
 
 pub(crate) fn build_logical_plan(query: sqlparser::ast::Query) -> DBResult<LogicalPlan> {
@@ -36,10 +501,18 @@ pub(crate) fn build_logical_plan(query: sqlparser::ast::Query) -> DBResult<Logic
                 TableFactor::Table {
                     name,
                     alias: _,
-                    args: _,
+                    args: Some(args),
                     with_hints: _,
                 } => LogicalPlan::UnResolvedScan {
-                    table: remove_quotes(name.to_string()),
+                    source: ScanSource::Function(TableFunction::try_new(name, args)?),
+                },
+                TableFactor::Table {
+                    name,
+                    alias: _,
+                    args: None,
+                    with_hints: _,
+                } => LogicalPlan::UnResolvedScan {
+                    source: ScanSource::Name(name.clone()),
                 },
                 _ => todo!(),
             };
@@ -53,8 +526,30 @@ pub(crate) fn build_logical_plan(query: sqlparser::ast::Query) -> DBResult<Logic
                 };
             }
 
-            // create Aggregator node
-            if !select.group_by.is_empty() {
+            let projection_exprs = if is_projection_empty(&select.projection) {
+                None
+            } else {
+                let exprs = select
+                    .projection
+                    .into_iter()
+                    .map(ast_projection_to_plan_expr)
+                    .collect::<DBResult<Vec<_>>>()?;
+                Some(exprs)
+            };
+            let having_expr = select
+                .having
+                .as_ref()
+                .map(ast_expr_to_plan_expr)
+                .transpose()?;
+
+            // create Aggregator node, aggregators in projection or having clause without
+            // GROUP BY aggregate all rows as a single group
+            let aggregated_exprs = projection_exprs
+                .iter()
+                .flatten()
+                .chain(having_expr.iter())
+                .collect::<Vec<_>>();
+            if !select.group_by.is_empty() || contains_aggregators(&aggregated_exprs)? {
                 let groupings = select
                     .group_by
                     .iter()
@@ -70,13 +565,7 @@ pub(crate) fn build_logical_plan(query: sqlparser::ast::Query) -> DBResult<Logic
             }
 
             // create Projection node
-            let projections = select.projection;
-            if !is_projection_empty(&projections) {
-                let projection_exprs = projections
-                    .into_iter()
-                    .map(ast_projection_to_plan_expr)
-                    .collect::<DBResult<Vec<_>>>()?;
-
+            if let Some(projection_exprs) = projection_exprs {
                 plan = LogicalPlan::Projection {
                     expressions: projection_exprs,
                     child: Box::new(plan),
@@ -84,9 +573,9 @@ pub(crate) fn build_logical_plan(query: sqlparser::ast::Query) -> DBResult<Logic
             }
 
             // create Filter node for HAVING clause
-            if let Some(having) = select.having {
+            if let Some(having) = having_expr {
                 plan = LogicalPlan::UnResolvedHaving {
-                    prediction: ast_expr_to_plan_expr(&having)?,
+                    prediction: having,
                     child: Box::new(plan),
                 };
             }
@@ -100,14 +589,7 @@ pub(crate) fn build_logical_plan(query: sqlparser::ast::Query) -> DBResult<Logic
     };
 
     if !query.order_by.is_empty() {
-        let options = query
-            .order_by
-            .iter()
-            .map(|OrderByExpr { expr, asc, .. }| {
-                ast_expr_to_plan_expr(expr)
-                    .map(|expr| SortOption::new(expr, asc.unwrap_or(true)))
-            })
-            .collect::<DBResult<Vec<_>>>()?;
+        let options = ast_order_by_to_sort_options(&query.order_by)?;
         logical_plan = LogicalPlan::Sort {
             options,
             child: Box::new(logical_plan),
@@ -145,7 +627,32 @@ pub(crate) fn build_logical_plan(query: sqlparser::ast::Query) -> DBResult<Logic
     Ok(logical_plan)
 }
 
-pub(crate) fn is_projection_empty(projections: &Vec<SelectItem>) -> bool {
+pub(crate) fn ast_order_by_to_sort_options(
+    order_by: &[OrderByExpr],
+) -> DBResult<Vec<SortOption>> {
+    order_by
+        .iter()
+        .map(|OrderByExpr { expr, asc, .. }| {
+            ast_expr_to_plan_expr(expr)
+                .map(|expr| SortOption::new(expr, asc.unwrap_or(true)))
+        })
+        .collect::<DBResult<Vec<_>>>()
+}
+
+/// whether any of the given expressions calls an aggregator, e.g. `sum(amount) > 100`
+pub(crate) fn contains_aggregators(exprs: &[&Expression]) -> DBResult<bool> {
+    let context = OptimizerContextForExpr::new(RelationSchema::empty());
+    let mut found = false;
+    for expr in exprs {
+        expr.transform_top_down(&context, &mut |node, context| {
+            found |= context.functions_registry().is_aggregator(node);
+            Ok(None)
+        })?;
+    }
+    Ok(found)
+}
+
+pub(crate) fn is_projection_empty(projections: &[SelectItem]) -> bool {
     projections.is_empty()
         || projections.len() == 1
             && projections
@@ -218,6 +725,55 @@ pub(crate) fn ast_unary_op_to_plan_unary_op(op: &UnaryOperator) -> DBResult<Unar
     }
 }
 
+/// convert a placeholder `$n` into the parameter of (zero-based) index n-1, placeholders
+/// `?` are numbered by `number_placeholders` beforehand
+fn placeholder_to_parameter(placeholder: &str) -> DBResult<Expression> {
+    let number = placeholder
+        .strip_prefix('$')
+        .and_then(|n| n.parse::<usize>().ok())
+        .filter(|n| *n > 0)
+        .ok_or(DBError::ParserError(format!(
+            "unsupported placeholder: {placeholder}"
+        )))?;
+    Ok(Expression::Parameter {
+        index: number - 1,
+        data_type: DataType::Unknown,
+    })
+}
+
+/// number placeholders `?` of the statement as `$1`, `$2`, ... in the order they appear,
+/// returns the number of parameters of the statement
+pub(crate) fn number_placeholders(statement: &mut Statement) -> DBResult<usize> {
+    let mut num_anonymous = 0;
+    let mut num_numbered = 0;
+    let mut invalid = None;
+    let _ = visit_expressions_mut(statement, |expr| {
+        if let Expr::Value(Value::Placeholder(p)) = expr {
+            if p == "?" {
+                num_anonymous += 1;
+                *p = format!("${num_anonymous}");
+            } else {
+                match placeholder_to_parameter(p) {
+                    Ok(Expression::Parameter { index, .. }) => {
+                        num_numbered = num_numbered.max(index + 1);
+                    }
+                    _ => invalid = Some(p.clone()),
+                }
+            }
+        }
+        ControlFlow::<()>::Continue(())
+    });
+    match (invalid, num_anonymous, num_numbered) {
+        (Some(p), _, _) => Err(DBError::ParserError(format!(
+            "unsupported placeholder: {p}"
+        ))),
+        (None, 0, n) | (None, n, 0) => Ok(n),
+        _ => Err(DBError::ParserError(
+            "placeholders $n and ? cannot be mixed in a statement".to_string(),
+        )),
+    }
+}
+
 pub(crate) fn ast_expr_to_plan_expr(expr: &Expr) -> DBResult<Expression> {
     match expr {
         Expr::BinaryOp { left, op, right } => {
@@ -235,6 +791,14 @@ pub(crate) fn ast_expr_to_plan_expr(expr: &Expr) -> DBResult<Expression> {
         Expr::Identifier(identifier) => {
             Ok(Expression::UnResolvedFieldRef(identifier.value.to_string()))
         }
+        // dotted names reference fields of nested objects, e.g. `address.city` of json
+        Expr::CompoundIdentifier(identifiers) => Ok(Expression::UnResolvedFieldRef(
+            identifiers
+                .iter()
+                .map(|ident| ident.value.as_str())
+                .collect::<Vec<_>>()
+                .join("."),
+        )),
         Expr::Value(v) => {
             let literal = match v {
                 Value::Number(v, _) => Literal::UnResolvedNumber(v.to_string()),
@@ -242,7 +806,8 @@ pub(crate) fn ast_expr_to_plan_expr(expr: &Expr) -> DBResult<Expression> {
                 Value::DoubleQuotedString(v) => Literal::UnResolvedString(v.to_string()),
                 Value::Boolean(v) => Literal::Bool(*v),
                 Value::Null => Literal::Null,
-                _ => todo!(),
+                Value::Placeholder(p) => return placeholder_to_parameter(p),
+                _ => return Err(DBError::ParserError(format!("unsupported value: {v}"))),
             };
             Ok(Expression::Literal(literal))
         }
@@ -256,7 +821,18 @@ pub(crate) fn ast_expr_to_plan_expr(expr: &Expr) -> DBResult<Expression> {
                 input: Box::new(expr),
             })
         }
-        Expr::Function(Function { name, args, .. }) => {
+        Expr::Function(Function {
+            name,
+            args,
+            distinct,
+            order_by,
+            ..
+        }) => {
+            if *distinct {
+                return Err(DBError::ParserError(format!(
+                    "DISTINCT is not supported in function {name}"
+                )));
+            }
             let arg_exprs = args
                 .iter()
                 .map(|arg| match arg {
@@ -271,8 +847,54 @@ pub(crate) fn ast_expr_to_plan_expr(expr: &Expr) -> DBResult<Expression> {
             Ok(Expression::UnResolvedFunction {
                 name: name.to_string(),
                 args: arg_exprs,
+                clauses: AggregateClauses::new(
+                    ast_order_by_to_sort_options(order_by)?,
+                    None,
+                ),
             })
         }
+        Expr::ArrayAgg(ArrayAgg {
+            distinct,
+            expr,
+            order_by,
+            limit,
+            // the ORDER BY of WITHIN GROUP is parsed into order_by
+            within_group: _,
+        }) => {
+            if *distinct || limit.is_some() {
+                return Err(DBError::ParserError(
+                    "DISTINCT and LIMIT are not supported in array_agg".to_string(),
+                ));
+            }
+            let order_by = match order_by {
+                Some(order_by) => ast_order_by_to_sort_options(order_by)?,
+                None => vec![],
+            };
+            Ok(Expression::UnResolvedFunction {
+                name: "array_agg".to_string(),
+                args: vec![ast_expr_to_plan_expr(expr)?],
+                clauses: AggregateClauses::new(order_by, None),
+            })
+        }
+        Expr::AggregateExpressionWithFilter { expr, filter } => {
+            match ast_expr_to_plan_expr(expr)? {
+                Expression::UnResolvedFunction {
+                    name,
+                    args,
+                    clauses,
+                } => Ok(Expression::UnResolvedFunction {
+                    name,
+                    args,
+                    clauses: AggregateClauses::new(
+                        clauses.order_by().to_vec(),
+                        Some(ast_expr_to_plan_expr(filter)?),
+                    ),
+                }),
+                other => Err(DBError::ParserError(format!(
+                    "FILTER is only supported for aggregate functions, got {other}"
+                ))),
+            }
+        }
         _ => todo!(),
     }
 }
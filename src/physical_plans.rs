@@ -1,18 +1,28 @@
+mod analyze;
+mod arrow_scan;
 mod csv_scan;
 mod filter;
 mod hash_aggregator;
 mod inmem_scan;
+mod json_scan;
 mod limit;
+mod multi_file_scan;
+mod parquet_scan;
 mod projection;
 mod sort;
 
 use crate::{errors::DBResult, row::Row, tables::RelationSchema};
 
+pub use analyze::Analyze;
+pub use arrow_scan::ArrowScan;
 pub use csv_scan::CsvScan;
 pub use filter::Filter;
 pub use hash_aggregator::HashAggregator;
 pub use inmem_scan::InMemTableScan;
+pub use json_scan::JsonScan;
 pub use limit::Limit;
+pub use multi_file_scan::MultiFileScan;
+pub use parquet_scan::ParquetScan;
 pub use projection::Projection;
 pub use sort::Sort;
 
@@ -23,4 +33,33 @@ pub trait PhysicalPlan {
     fn next(&mut self) -> DBResult<Option<Row<'static>>>;
     /// Return the schema/shape of the output rows.
     fn schema(&self) -> DBResult<RelationSchema>;
+    /// Describe this plan node in a single line, e.g. for EXPLAIN.
+    fn explain(&self) -> String;
+    /// Return the children plan nodes.
+    fn children(&self) -> Vec<&dyn PhysicalPlan> {
+        vec![]
+    }
+}
+
+/// Describe the tree of plan nodes line by line, children are indented below their
+/// parents.
+pub fn explain_plan(plan: &dyn PhysicalPlan) -> Vec<String> {
+    let mut lines = vec![plan.explain()];
+    for child in plan.children() {
+        lines.extend(
+            explain_plan(child)
+                .into_iter()
+                .map(|line| format!("  {line}")),
+        );
+    }
+    lines
+}
+
+/// join expressions into a comma separated list
+pub(crate) fn exprs_to_string<T: ToString>(exprs: &[T]) -> String {
+    exprs
+        .iter()
+        .map(|e| e.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
 }
@@ -0,0 +1,60 @@
+use std::time::{Duration, Instant};
+
+use crate::{errors::DBResult, row::Row, tables::RelationSchema};
+
+use super::PhysicalPlan;
+
+/// Analyze wraps a plan node to collect the number of rows it outputs and the time
+/// elapsed in it, which includes the time elapsed in its children. It is used by
+/// EXPLAIN ANALYZE.
+pub struct Analyze {
+    child: Box<dyn PhysicalPlan>,
+    rows: usize,
+    elapsed: Duration,
+}
+
+impl Analyze {
+    pub fn new(child: Box<dyn PhysicalPlan>) -> Self {
+        Self {
+            child,
+            rows: 0,
+            elapsed: Duration::ZERO,
+        }
+    }
+}
+
+impl PhysicalPlan for Analyze {
+    fn setup(&mut self) -> DBResult<()> {
+        let start = Instant::now();
+        let result = self.child.setup();
+        self.elapsed += start.elapsed();
+        result
+    }
+
+    fn next(&mut self) -> DBResult<Option<Row<'static>>> {
+        let start = Instant::now();
+        let result = self.child.next();
+        self.elapsed += start.elapsed();
+        if let Ok(Some(_)) = result {
+            self.rows += 1;
+        }
+        result
+    }
+
+    fn schema(&self) -> DBResult<RelationSchema> {
+        self.child.schema()
+    }
+
+    fn explain(&self) -> String {
+        format!(
+            "{} (rows={}, elapsed={:?})",
+            self.child.explain(),
+            self.rows,
+            self.elapsed
+        )
+    }
+
+    fn children(&self) -> Vec<&dyn PhysicalPlan> {
+        self.child.children()
+    }
+}
@@ -0,0 +1,95 @@
+use arrow::array::RecordBatch;
+
+use crate::{
+    expressions::Literal,
+    row::Row,
+    tables::{
+        arrow_ipc::IpcBatchReader, arrow_types::literal_from_array, RelationSchema,
+    },
+    DBError, DBResult,
+};
+
+use super::PhysicalPlan;
+
+/// ArrowScan reads rows of an arrow ipc file batch by batch, fields not in the
+/// projection are null.
+pub struct ArrowScan {
+    schema: RelationSchema,
+    path: String,
+    /// indexes of the fields read, aligned with the columns of record batches
+    fields_read: Vec<usize>,
+    reader: Option<IpcBatchReader>,
+    batch: Option<RecordBatch>,
+    /// the index of the next row in the batch
+    row_idx: usize,
+}
+
+impl ArrowScan {
+    pub fn new(
+        path: String,
+        schema: RelationSchema,
+        projection: Option<Vec<usize>>,
+    ) -> Self {
+        let fields_read =
+            projection.unwrap_or_else(|| (0..schema.num_fields()).collect());
+        Self {
+            schema,
+            path,
+            fields_read,
+            reader: None,
+            batch: None,
+            row_idx: 0,
+        }
+    }
+}
+
+impl PhysicalPlan for ArrowScan {
+    fn setup(&mut self) -> DBResult<()> {
+        self.reader = Some(IpcBatchReader::new(
+            &self.path,
+            Some(self.fields_read.clone()),
+        )?);
+        Ok(())
+    }
+
+    fn next(&mut self) -> DBResult<Option<Row<'static>>> {
+        loop {
+            if let Some(batch) =
+                self.batch.as_ref().filter(|b| self.row_idx < b.num_rows())
+            {
+                let mut cells = vec![Literal::Null; self.schema.num_fields()];
+                for (column, &field_idx) in self.fields_read.iter().enumerate() {
+                    cells[field_idx] =
+                        literal_from_array(batch.column(column).as_ref(), self.row_idx)?;
+                }
+                self.row_idx += 1;
+                return Ok(Some(Row::new(cells)));
+            }
+            let reader = self
+                .reader
+                .as_mut()
+                .ok_or_else(DBError::should_never_happen)?;
+            match reader.next_batch()? {
+                Some(batch) => {
+                    self.batch = Some(batch);
+                    self.row_idx = 0;
+                }
+                None => return Ok(None),
+            }
+        }
+    }
+
+    fn schema(&self) -> DBResult<RelationSchema> {
+        Ok(self.schema.clone())
+    }
+
+    fn explain(&self) -> String {
+        let columns = self
+            .fields_read
+            .iter()
+            .map(|&idx| self.schema.get_fields()[idx].name())
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!("ArrowScan: {}, columns: [{columns}]", self.path)
+    }
+}
@@ -1,9 +1,11 @@
 use crate::{
-    data_types::DataType,
     expressions::Literal,
     row::Row,
-    tables::{csv::CsvRecordReader, RelationSchema},
-    DBResult,
+    tables::{
+        csv::{parse_csv_field, CsvOptions, CsvRecordReader, InvalidValuePolicy},
+        RelationSchema,
+    },
+    DBError, DBResult,
 };
 
 use super::PhysicalPlan;
@@ -11,50 +13,65 @@ use super::PhysicalPlan;
 pub struct CsvScan {
     schema: RelationSchema,
     path: String,
+    options: CsvOptions,
     reader: Option<CsvRecordReader>,
 }
 
 impl CsvScan {
-    pub fn new(path: String, schema: RelationSchema) -> Self {
+    pub fn new(path: String, schema: RelationSchema, options: CsvOptions) -> Self {
         Self {
             schema,
             path,
+            options,
             reader: None,
         }
     }
-
-    fn parse_value(value: String, data_type: &DataType) -> DBResult<Literal> {
-        match data_type {
-            DataType::Int64 => Ok(Literal::Int64(value.parse::<i64>()?)),
-            DataType::Float64 => Ok(Literal::Float64(value.parse::<f64>()?)),
-            DataType::String => Ok(Literal::String(value)) as DBResult<Literal>,
-            DataType::Boolean => {
-                Ok(Literal::Bool(value.as_str().to_lowercase() == "true"))
-            }
-            _ => unreachable!(),
-        }
-    }
 }
 
 impl PhysicalPlan for CsvScan {
     fn setup(&mut self) -> DBResult<()> {
-        let mut reader = CsvRecordReader::new(self.path.as_str())?;
-        // skip header
-        let _ = reader.try_read_next()?;
+        let mut reader =
+            CsvRecordReader::with_options(self.path.as_str(), &self.options)?;
+        if self.options.header {
+            let _ = reader.try_read_next()?;
+        }
         self.reader = Some(reader);
         Ok(())
     }
 
     fn next(&mut self) -> DBResult<Option<Row<'static>>> {
-        if let Some(record) = self.reader.as_mut().unwrap().try_read_next()? {
+        let reader = self
+            .reader
+            .as_mut()
+            .ok_or_else(DBError::should_never_happen)?;
+        if let Some(record) = reader.try_read_next()? {
             if record.len() != self.schema.num_fields() {
-                return Err(crate::DBError::StorageEngine(
-                    "The csv record is not aligned with schema.".to_owned(),
-                ));
+                return Err(reader.malformed(format!(
+                    "expected {} fields but got {}",
+                    self.schema.num_fields(),
+                    record.len()
+                )));
             }
 
             let cells = Iterator::zip(record.into_iter(), self.schema.get_fields())
-                .map(|(r, f)| Self::parse_value(r, f.data_type()))
+                .map(|(value, field)| {
+                    if self.options.is_null(&value) {
+                        return Ok(Literal::Null);
+                    }
+                    match parse_csv_field(&value, field.data_type()) {
+                        Some(value) => Ok(value),
+                        None if self.options.invalid_values
+                            == InvalidValuePolicy::Null =>
+                        {
+                            Ok(Literal::Null)
+                        }
+                        None => Err(reader.malformed(format!(
+                            "column {}: cannot read '{value}' as {}",
+                            field.name(),
+                            field.data_type()
+                        ))),
+                    }
+                })
                 .collect::<DBResult<Vec<_>>>()?;
             Ok(Some(Row::new(cells)))
         } else {
@@ -65,4 +82,8 @@ impl PhysicalPlan for CsvScan {
     fn schema(&self) -> DBResult<RelationSchema> {
         Ok(self.schema.clone())
     }
+
+    fn explain(&self) -> String {
+        format!("CsvScan: {}", self.path)
+    }
 }
@@ -33,4 +33,12 @@ impl PhysicalPlan for Filter {
     fn schema(&self) -> DBResult<RelationSchema> {
         self.child.schema()
     }
+
+    fn explain(&self) -> String {
+        format!("Filter: {}", self.expression)
+    }
+
+    fn children(&self) -> Vec<&dyn PhysicalPlan> {
+        vec![self.child.as_ref()]
+    }
 }
@@ -9,7 +9,7 @@ use crate::{
     DBError, DBResult,
 };
 
-use super::PhysicalPlan;
+use super::{exprs_to_string, PhysicalPlan};
 
 /// first part is grouping values, later part is aggregation results
 type AggregatorResult = (Vec<Literal>, Vec<Row<'static>>);
@@ -73,6 +73,16 @@ impl HashAggregator {
                 }
             };
         }
+        // aggregation without groupings always results in a single row, even on empty
+        // input, e.g. `select count(*) from t` returns 0
+        if self.grouping_exprs.is_empty() && buffers.is_empty() {
+            let result_rows = self
+                .aggregators
+                .iter()
+                .map(|agg| agg.initial_row())
+                .collect::<DBResult<Vec<_>>>()?;
+            buffers.insert(vec![], result_rows);
+        }
         self.iter = self.buffers.take().map(|buffers| {
             Box::new(buffers.into_iter()) as Box<dyn Iterator<Item = AggregatorResult>>
         });
@@ -134,4 +144,16 @@ impl PhysicalPlan for HashAggregator {
             &self.aggregator_exprs,
         ))
     }
+
+    fn explain(&self) -> String {
+        format!(
+            "HashAggregator: groupings=[{}], aggregators=[{}]",
+            exprs_to_string(&self.grouping_exprs),
+            exprs_to_string(&self.aggregator_exprs)
+        )
+    }
+
+    fn children(&self) -> Vec<&dyn PhysicalPlan> {
+        vec![self.child.as_ref()]
+    }
 }
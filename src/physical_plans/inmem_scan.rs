@@ -36,4 +36,8 @@ impl PhysicalPlan for InMemTableScan {
     fn schema(&self) -> DBResult<RelationSchema> {
         Ok(self.schema.clone())
     }
+
+    fn explain(&self) -> String {
+        format!("InMemTableScan: {} rows", self.data.len())
+    }
 }
@@ -0,0 +1,98 @@
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use crate::{
+    data_types::DataType,
+    expressions::Literal,
+    row::Row,
+    tables::{json::JsonRecordReader, RelationSchema},
+    DBError, DBResult,
+};
+
+use super::PhysicalPlan;
+
+/// JsonScan reads records of a json file one at a time.
+pub struct JsonScan {
+    schema: RelationSchema,
+    path: String,
+    reader: Option<JsonRecordReader>,
+    /// the index of every field in the schema by name
+    field_indexes: HashMap<String, usize>,
+}
+
+impl JsonScan {
+    pub fn new(path: String, schema: RelationSchema) -> Self {
+        let field_indexes = schema
+            .get_fields()
+            .iter()
+            .enumerate()
+            .map(|(idx, field)| (field.name().to_owned(), idx))
+            .collect();
+        Self {
+            schema,
+            path,
+            reader: None,
+            field_indexes,
+        }
+    }
+
+    /// the value as the data type, None if the value cannot be read as the data type
+    fn parse_value(value: &Value, data_type: &DataType) -> Option<Literal> {
+        match (value, data_type) {
+            (Value::Null, _) => Some(Literal::Null),
+            (Value::Bool(v), DataType::Boolean) => Some(Literal::Bool(*v)),
+            (Value::Number(v), DataType::Int64) => v.as_i64().map(Literal::Int64),
+            (Value::Number(v), DataType::Float64) => v.as_f64().map(Literal::Float64),
+            (Value::String(v), DataType::String) => Some(Literal::String(v.clone())),
+            // values of conflicting types are read as json text
+            (value, DataType::String) => Some(Literal::String(value.to_string())),
+            (Value::Array(values), DataType::List(data_type)) => values
+                .iter()
+                .map(|v| Self::parse_value(v, data_type))
+                .collect::<Option<Vec<_>>>()
+                .map(Literal::List),
+            _ => None,
+        }
+    }
+}
+
+impl PhysicalPlan for JsonScan {
+    fn setup(&mut self) -> DBResult<()> {
+        self.reader = Some(JsonRecordReader::new(self.path.as_str())?);
+        Ok(())
+    }
+
+    fn next(&mut self) -> DBResult<Option<Row<'static>>> {
+        let reader = self
+            .reader
+            .as_mut()
+            .ok_or_else(DBError::should_never_happen)?;
+        let Some(record) = reader.try_read_next()? else {
+            return Ok(None);
+        };
+        let mut cells = vec![Literal::Null; self.schema.num_fields()];
+        for (name, value) in record {
+            // fields not in the schema are ignored, e.g. those absent from the sample
+            if let Some(&idx) = self.field_indexes.get(&name) {
+                let data_type = self.schema.get_fields()[idx].data_type();
+                cells[idx] = Self::parse_value(&value, data_type).ok_or_else(|| {
+                    DBError::StorageEngine(format!(
+                        "json record {}: field {name}: json value {value} cannot be read \
+                         as {data_type}",
+                        reader.num_records()
+                    ))
+                })?;
+            }
+        }
+        Ok(Some(Row::new(cells)))
+    }
+
+    fn schema(&self) -> DBResult<RelationSchema> {
+        Ok(self.schema.clone())
+    }
+
+    fn explain(&self) -> String {
+        format!("JsonScan: {}", self.path)
+    }
+}
@@ -55,4 +55,12 @@ impl PhysicalPlan for Limit {
     fn schema(&self) -> DBResult<RelationSchema> {
         self.child.schema()
     }
+
+    fn explain(&self) -> String {
+        format!("Limit: offset={}, limit={}", self.offset, self.limit)
+    }
+
+    fn children(&self) -> Vec<&dyn PhysicalPlan> {
+        vec![self.child.as_ref()]
+    }
 }
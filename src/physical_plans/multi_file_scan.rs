@@ -0,0 +1,76 @@
+use crate::{expressions::Literal, row::Row, tables::RelationSchema, DBResult};
+
+use super::PhysicalPlan;
+
+/// Scan the files of a multi file table one after another, the values of the
+/// partitions of a file are appended to its rows.
+pub struct MultiFileScan {
+    pattern: String,
+    schema: RelationSchema,
+    /// the scans of files not pruned, and the partition values of the files
+    scans: Vec<(Box<dyn PhysicalPlan>, Vec<Literal>)>,
+    total_files: usize,
+    /// the position of the scan being read
+    pos: usize,
+    /// whether the scan being read has been setup
+    is_open: bool,
+}
+
+impl MultiFileScan {
+    pub fn new(
+        pattern: String,
+        schema: RelationSchema,
+        scans: Vec<(Box<dyn PhysicalPlan>, Vec<Literal>)>,
+        total_files: usize,
+    ) -> Self {
+        Self {
+            pattern,
+            schema,
+            scans,
+            total_files,
+            pos: 0,
+            is_open: false,
+        }
+    }
+}
+
+impl PhysicalPlan for MultiFileScan {
+    fn setup(&mut self) -> DBResult<()> {
+        // files are opened when they are read
+        self.pos = 0;
+        self.is_open = false;
+        Ok(())
+    }
+
+    fn next(&mut self) -> DBResult<Option<Row<'static>>> {
+        while let Some((scan, partition_values)) = self.scans.get_mut(self.pos) {
+            if !self.is_open {
+                scan.setup()?;
+                self.is_open = true;
+            }
+            if let Some(row) = scan.next()? {
+                let mut cells = (0..row.num_fields())
+                    .map(|idx| row.get_field(idx))
+                    .collect::<DBResult<Vec<_>>>()?;
+                cells.extend(partition_values.iter().cloned());
+                return Ok(Some(Row::new(cells)));
+            }
+            self.pos += 1;
+            self.is_open = false;
+        }
+        Ok(None)
+    }
+
+    fn schema(&self) -> DBResult<RelationSchema> {
+        Ok(self.schema.clone())
+    }
+
+    fn explain(&self) -> String {
+        format!(
+            "MultiFileScan: {}, files: {} of {}",
+            self.pattern,
+            self.scans.len(),
+            self.total_files
+        )
+    }
+}
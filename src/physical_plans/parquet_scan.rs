@@ -0,0 +1,125 @@
+use arrow::array::RecordBatch;
+use parquet::arrow::{arrow_reader::ParquetRecordBatchReader, ProjectionMask};
+
+use crate::{
+    expressions::Literal,
+    row::Row,
+    tables::{
+        arrow_types::literal_from_array,
+        parquet::{open_parquet, prune_row_groups},
+        RelationSchema, ScanOptions,
+    },
+    DBError, DBResult,
+};
+
+use super::PhysicalPlan;
+
+/// ParquetScan reads rows of the selected row groups of a parquet file batch by batch,
+/// fields not in the projection are null.
+pub struct ParquetScan {
+    schema: RelationSchema,
+    path: String,
+    options: ScanOptions,
+    /// indexes of the fields read, aligned with the columns of record batches
+    fields_read: Vec<usize>,
+    reader: Option<ParquetRecordBatchReader>,
+    batch: Option<RecordBatch>,
+    /// the index of the next row in the batch
+    row_idx: usize,
+    /// the number of row groups selected to read and the total number of row groups
+    row_groups: Option<(usize, usize)>,
+}
+
+impl ParquetScan {
+    pub fn new(path: String, schema: RelationSchema, options: ScanOptions) -> Self {
+        let fields_read = options
+            .projection
+            .clone()
+            .unwrap_or_else(|| (0..schema.num_fields()).collect());
+        Self {
+            schema,
+            path,
+            options,
+            fields_read,
+            reader: None,
+            batch: None,
+            row_idx: 0,
+            row_groups: None,
+        }
+    }
+}
+
+impl PhysicalPlan for ParquetScan {
+    fn setup(&mut self) -> DBResult<()> {
+        let builder = open_parquet(&self.path)?;
+        let row_groups = prune_row_groups(
+            builder.metadata().row_groups(),
+            builder.parquet_schema(),
+            builder.schema(),
+            &self.options.filters,
+        );
+        self.row_groups = Some((row_groups.len(), builder.metadata().num_row_groups()));
+        // the first column is read to count rows if no field is needed
+        let roots = match self.fields_read.is_empty() {
+            true => vec![0],
+            false => self.fields_read.clone(),
+        };
+        let mask = ProjectionMask::roots(builder.parquet_schema(), roots);
+        let reader = builder
+            .with_projection(mask)
+            .with_row_groups(row_groups)
+            .build()
+            .map_err(|e| {
+                DBError::StorageEngine(format!("read parquet file failed: {e}"))
+            })?;
+        self.reader = Some(reader);
+        Ok(())
+    }
+
+    fn next(&mut self) -> DBResult<Option<Row<'static>>> {
+        loop {
+            if let Some(batch) =
+                self.batch.as_ref().filter(|b| self.row_idx < b.num_rows())
+            {
+                let mut cells = vec![Literal::Null; self.schema.num_fields()];
+                for (column, &field_idx) in self.fields_read.iter().enumerate() {
+                    cells[field_idx] =
+                        literal_from_array(batch.column(column).as_ref(), self.row_idx)?;
+                }
+                self.row_idx += 1;
+                return Ok(Some(Row::new(cells)));
+            }
+            let reader = self
+                .reader
+                .as_mut()
+                .ok_or_else(DBError::should_never_happen)?;
+            match reader.next() {
+                Some(batch) => {
+                    self.batch = Some(batch.map_err(|e| {
+                        DBError::StorageEngine(format!("read parquet file error: {e}"))
+                    })?);
+                    self.row_idx = 0;
+                }
+                None => return Ok(None),
+            }
+        }
+    }
+
+    fn schema(&self) -> DBResult<RelationSchema> {
+        Ok(self.schema.clone())
+    }
+
+    fn explain(&self) -> String {
+        let columns = self
+            .fields_read
+            .iter()
+            .map(|&idx| self.schema.get_fields()[idx].name())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let mut explain = format!("ParquetScan: {}, columns: [{columns}]", self.path);
+        if let Some((selected, total)) = self.row_groups {
+            explain.push_str(&format!(", row groups: {selected} of {total}"));
+        }
+        explain
+    }
+}
@@ -5,7 +5,7 @@ use crate::{
     tables::{FieldInfo, RelationSchema},
 };
 
-use super::PhysicalPlan;
+use super::{exprs_to_string, PhysicalPlan};
 
 pub struct Projection {
     projections: Vec<Expression>,
@@ -46,4 +46,12 @@ impl PhysicalPlan for Projection {
         let schema = RelationSchema::new(fields);
         Ok(schema)
     }
+
+    fn explain(&self) -> String {
+        format!("Projection: {}", exprs_to_string(&self.projections))
+    }
+
+    fn children(&self) -> Vec<&dyn PhysicalPlan> {
+        vec![self.child.as_ref()]
+    }
 }
@@ -7,7 +7,7 @@ use crate::{
     DBError, DBResult,
 };
 
-use super::PhysicalPlan;
+use super::{exprs_to_string, PhysicalPlan};
 
 pub struct Sort {
     sort_options: Vec<SortOption>,
@@ -85,4 +85,12 @@ impl PhysicalPlan for Sort {
     fn schema(&self) -> crate::DBResult<crate::tables::RelationSchema> {
         self.child.schema()
     }
+
+    fn explain(&self) -> String {
+        format!("Sort: {}", exprs_to_string(&self.sort_options))
+    }
+
+    fn children(&self) -> Vec<&dyn PhysicalPlan> {
+        vec![self.child.as_ref()]
+    }
 }
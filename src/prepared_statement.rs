@@ -0,0 +1,313 @@
+use std::{
+    ops::ControlFlow,
+    sync::{Arc, RwLock},
+};
+
+use sqlparser::ast::{
+    visit_expressions_mut, Assignment, Expr, SetExpr, Statement, TableFactor,
+    TableWithJoins, Value,
+};
+
+use crate::{
+    data_types::DataType,
+    expressions::{Expression, Literal},
+    handlers::{resolve_table_exprs, InsertHandler, SelectHandler},
+    logical_plans::LogicalPlan,
+    optimizer::{rules::BindParametersRule, Optimizer, OptimizerContextForExpr},
+    parser::{
+        ast_expr_to_plan_expr, build_logical_plan, number_placeholders, CrackDBStatement,
+    },
+    tables::RelationSchema,
+    Catalog, CrackDB, DBError, DBResult, ResultSet,
+};
+
+/// PreparedStatement is a statement which is parsed once, and executed many times with
+/// values bound to its placeholders `$1`, `$2`, ... or `?`.
+///
+/// A query is optimized when it is prepared, and its optimized logical plan is reused by
+/// every execution. Values are checked against the data types inferred for the
+/// parameters, e.g. the value of `$1` in `id = $1` must fit into the data type of `id`.
+/// Other statements are executed with the checked values substituted into them, the
+/// data types of their parameters are inferred from the columns of INSERT, UPDATE or
+/// DELETE.
+pub struct PreparedStatement<'a> {
+    db: &'a CrackDB,
+    optimizer: Optimizer,
+    select_handler: SelectHandler,
+    prepared: Prepared,
+    num_parameters: usize,
+    is_bound: bool,
+}
+
+enum Prepared {
+    Query {
+        plan: LogicalPlan,
+        bound_plan: Option<LogicalPlan>,
+    },
+    Statement {
+        statement: CrackDBStatement,
+        /// the data types inferred for the parameters, Unknown if not inferred
+        parameter_types: Vec<DataType>,
+        values: Vec<Value>,
+    },
+}
+
+impl<'a> PreparedStatement<'a> {
+    pub(crate) fn new(
+        db: &'a CrackDB,
+        catalog: Arc<RwLock<Catalog>>,
+        statement: CrackDBStatement,
+    ) -> DBResult<Self> {
+        let optimizer = Optimizer::new(Arc::clone(&catalog));
+        let select_handler = SelectHandler::new(Arc::clone(&catalog));
+        let (prepared, num_parameters) = match statement {
+            CrackDBStatement::Sql(mut statement) => {
+                let num_parameters = number_placeholders(&mut statement)?;
+                let prepared = match *statement {
+                    Statement::Query(query) => Prepared::Query {
+                        plan: optimizer.optimize(build_logical_plan(*query)?)?,
+                        bound_plan: None,
+                    },
+                    statement => Prepared::Statement {
+                        parameter_types: infer_parameter_types(
+                            &catalog,
+                            &statement,
+                            num_parameters,
+                        )?,
+                        statement: CrackDBStatement::Sql(Box::new(statement)),
+                        values: vec![],
+                    },
+                };
+                (prepared, num_parameters)
+            }
+            statement => (
+                Prepared::Statement {
+                    statement,
+                    parameter_types: vec![],
+                    values: vec![],
+                },
+                0,
+            ),
+        };
+        Ok(Self {
+            db,
+            optimizer,
+            select_handler,
+            prepared,
+            num_parameters,
+            is_bound: num_parameters == 0,
+        })
+    }
+
+    /// the number of parameters of the statement
+    pub fn num_parameters(&self) -> usize {
+        self.num_parameters
+    }
+
+    /// bind values to the parameters, the value of `$n` is the n-th value
+    pub fn bind(&mut self, values: &[Literal]) -> DBResult<()> {
+        if values.len() != self.num_parameters {
+            return Err(DBError::ParserError(format!(
+                "the statement has {} parameters but {} values are given",
+                self.num_parameters,
+                values.len()
+            )));
+        }
+        match &mut self.prepared {
+            Prepared::Query { plan, bound_plan } => {
+                *bound_plan = Some(self.optimizer.bind_parameters(plan, values)?);
+            }
+            Prepared::Statement {
+                parameter_types,
+                values: bound_values,
+                ..
+            } => {
+                *bound_values = Iterator::zip(values.iter(), parameter_types.iter())
+                    .enumerate()
+                    .map(|(index, (value, data_type))| {
+                        let parameter = Expression::Parameter {
+                            index,
+                            data_type: data_type.clone(),
+                        };
+                        let value = BindParametersRule::check_value(
+                            &parameter, data_type, value,
+                        )?;
+                        literal_to_value(&value)
+                    })
+                    .collect::<DBResult<_>>()?;
+            }
+        }
+        self.is_bound = true;
+        Ok(())
+    }
+
+    /// execute the statement with the bound values
+    pub fn execute(&self) -> DBResult<ResultSet> {
+        if !self.is_bound {
+            return Err(DBError::ParserError(
+                "parameters of the statement are not bound".to_string(),
+            ));
+        }
+        match &self.prepared {
+            Prepared::Query { plan, bound_plan } => {
+                let plan = bound_plan.as_ref().unwrap_or(plan).clone();
+                self.select_handler.execute_plan(plan)
+            }
+            Prepared::Statement {
+                statement: CrackDBStatement::Sql(statement),
+                values,
+                ..
+            } => {
+                let mut statement = statement.clone();
+                let _ = visit_expressions_mut(statement.as_mut(), |expr| {
+                    if let Expr::Value(Value::Placeholder(p)) = expr {
+                        // placeholders are numbered as `$n` when prepared
+                        let index = p[1..].parse::<usize>().unwrap_or_default();
+                        if let Some(value) =
+                            index.checked_sub(1).and_then(|i| values.get(i))
+                        {
+                            *expr = Expr::Value(value.clone());
+                        }
+                    }
+                    ControlFlow::<()>::Continue(())
+                });
+                self.db.execute_statement(CrackDBStatement::Sql(statement))
+            }
+            Prepared::Statement { statement, .. } => {
+                self.db.execute_statement(statement.clone())
+            }
+        }
+    }
+}
+
+/// the data types of the parameters of a statement, which are inferred from the columns
+/// their values are inserted into, assigned to or compared with
+fn infer_parameter_types(
+    catalog: &Arc<RwLock<Catalog>>,
+    statement: &Statement,
+    num_parameters: usize,
+) -> DBResult<Vec<DataType>> {
+    let mut data_types = vec![DataType::Unknown; num_parameters];
+    let (table_name, exprs) = match statement {
+        Statement::Insert {
+            table_name,
+            columns,
+            source,
+            ..
+        } => {
+            let SetExpr::Values(values) = source.body.as_ref() else {
+                return Ok(data_types);
+            };
+            let schema = table_schema(catalog, &table_name.to_string())?;
+            let target_fields = InsertHandler::target_fields(&schema, columns)?;
+            for row in values.rows.iter() {
+                for (expr, field_idx) in Iterator::zip(row.iter(), target_fields.iter()) {
+                    if let Some(index) = parameter_index(expr)? {
+                        let field = schema
+                            .get_field_at(*field_idx)
+                            .ok_or_else(DBError::should_never_happen)?;
+                        data_types[index] = field.data_type().clone();
+                    }
+                }
+            }
+            return Ok(data_types);
+        }
+        Statement::Update {
+            table:
+                TableWithJoins {
+                    relation: TableFactor::Table { name, .. },
+                    ..
+                },
+            assignments,
+            selection,
+            ..
+        } => {
+            let schema = table_schema(catalog, &name.to_string())?;
+            let mut exprs = Vec::with_capacity(assignments.len() + 1);
+            for Assignment { id, value } in assignments {
+                let field = id
+                    .last()
+                    .and_then(|ident| schema.index_of(&ident.value))
+                    .and_then(|field_idx| schema.get_field_at(field_idx));
+                match (parameter_index(value)?, field) {
+                    (Some(index), Some(field)) => {
+                        data_types[index] = field.data_type().clone();
+                    }
+                    _ => exprs.push(ast_expr_to_plan_expr(value)?),
+                }
+            }
+            if let Some(selection) = selection {
+                exprs.push(ast_expr_to_plan_expr(selection)?);
+            }
+            (name, exprs)
+        }
+        Statement::Delete {
+            from,
+            selection: Some(selection),
+            ..
+        } => match from.as_slice() {
+            [TableWithJoins {
+                relation: TableFactor::Table { name, .. },
+                ..
+            }] => (name, vec![ast_expr_to_plan_expr(selection)?]),
+            _ => return Ok(data_types),
+        },
+        _ => return Ok(data_types),
+    };
+
+    // data types of parameters in expressions are inferred when the expressions are
+    // resolved against the table
+    let context = OptimizerContextForExpr::new(RelationSchema::empty());
+    for expr in resolve_table_exprs(catalog, table_name, exprs, "prepared statements")? {
+        expr.transform_bottom_up(&context, &mut |expr, _context| {
+            if let Expression::Parameter { index, data_type } = expr {
+                if *data_type != DataType::Unknown {
+                    data_types[*index] = data_type.clone();
+                }
+            }
+            Ok(None)
+        })?;
+    }
+    Ok(data_types)
+}
+
+/// the index of the parameter if the expression is a placeholder
+fn parameter_index(expr: &Expr) -> DBResult<Option<usize>> {
+    match expr {
+        Expr::Value(Value::Placeholder(_)) => match ast_expr_to_plan_expr(expr)? {
+            Expression::Parameter { index, .. } => Ok(Some(index)),
+            _ => Ok(None),
+        },
+        _ => Ok(None),
+    }
+}
+
+fn table_schema(
+    catalog: &Arc<RwLock<Catalog>>,
+    table_name: &str,
+) -> DBResult<RelationSchema> {
+    let table = RwLock::read(catalog)
+        .map_err(|_e| DBError::Unknown("access catalog read lock failed.".to_string()))?
+        .try_get_table(table_name)?;
+    let schema = RwLock::read(&table)
+        .map_err(|_| DBError::Unknown("Access read lock of table failed.".to_string()))?
+        .get_table_meta()
+        .get_schema()
+        .clone();
+    Ok(schema)
+}
+
+/// the SQL value of a literal
+fn literal_to_value(literal: &Literal) -> DBResult<Value> {
+    match literal {
+        Literal::Null => Ok(Value::Null),
+        Literal::Bool(v) => Ok(Value::Boolean(*v)),
+        Literal::String(v) | Literal::DateTime(v) | Literal::UnResolvedString(v) => {
+            Ok(Value::SingleQuotedString(v.clone()))
+        }
+        Literal::List(_) => Err(DBError::ParserError(format!(
+            "cannot bind {literal} to a parameter"
+        ))),
+        _ => Ok(Value::Number(literal.to_string(), false)),
+    }
+}
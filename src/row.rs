@@ -72,4 +72,17 @@ impl<'a> Row<'a> {
             }
         }
     }
+
+    /// mutable access to a field, so that large values (e.g. lists) can be updated in
+    /// place
+    pub(crate) fn get_field_mut(&mut self, index: usize) -> DBResult<&mut Literal> {
+        match self {
+            Self::Simple { fields } => fields
+                .get_mut(index)
+                .ok_or(DBError::Unknown("index out of bound".to_string())),
+            Self::Combined { .. } => Err(DBError::Unknown(
+                "cannot mutate fields of a combined row".to_string(),
+            )),
+        }
+    }
 }
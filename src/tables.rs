@@ -1,31 +1,237 @@
+pub mod arrow_ipc;
+pub mod constraints;
 pub mod csv;
+pub mod information_schema;
 pub mod inmem;
+pub mod json;
+pub mod multi_file;
+pub mod parquet;
+
+pub(crate) mod arrow_types;
+pub(crate) mod compression;
+
+use constraints::TableConstraint;
 
 use crate::data_types::DataType;
+use crate::expressions::{Expression, Literal};
 use crate::physical_plans::PhysicalPlan;
 use crate::row::Row;
+use crate::{DBError, DBResult};
 
-pub trait Table {
-    fn insert_data(&mut self, data: Vec<Row<'static>>);
+pub trait Table: Send + Sync {
+    /// insert rows into the table, either all rows are inserted or none of them if any
+    /// row violates a constraint of the table
+    fn insert_data(&mut self, data: Vec<Row<'static>>) -> DBResult<()>;
 
     fn get_table_meta(&self) -> TableMeta;
 
     fn create_scan_op(&self) -> Box<dyn PhysicalPlan>;
+
+    /// create a scan which only needs to read what the options ask for, all fields and
+    /// rows are read by default
+    fn create_scan_op_with(&self, _options: &ScanOptions) -> Box<dyn PhysicalPlan> {
+        self.create_scan_op()
+    }
+
+    /// append a column to the table, existing rows are filled by the default value or
+    /// null
+    fn add_column(
+        &mut self,
+        _field: FieldInfo,
+        _default: Option<Literal>,
+    ) -> DBResult<()> {
+        Err(DBError::StorageEngine(
+            "adding columns is not supported by the table.".to_owned(),
+        ))
+    }
+
+    /// remove the column at the given index from the table
+    fn drop_column(&mut self, _idx: usize) -> DBResult<()> {
+        Err(DBError::StorageEngine(
+            "dropping columns is not supported by the table.".to_owned(),
+        ))
+    }
+
+    /// rename the column at the given index
+    fn rename_column(&mut self, _idx: usize, _name: String) -> DBResult<()> {
+        Err(DBError::StorageEngine(
+            "renaming columns is not supported by the table.".to_owned(),
+        ))
+    }
+
+    /// update rows matching the predicate (or all rows if no predicate is given) by
+    /// assigning the evaluated expressions to the fields at the given indexes, returns
+    /// the number of updated rows.
+    ///
+    /// Expressions are resolved against the table schema, and evaluated against the
+    /// rows before updating.
+    fn update(
+        &mut self,
+        _predicate: Option<&Expression>,
+        _assignments: &[(usize, Expression)],
+    ) -> DBResult<usize> {
+        Err(DBError::StorageEngine(
+            "update is not supported by the table.".to_owned(),
+        ))
+    }
+
+    /// delete rows matching the predicate (or all rows if no predicate is given),
+    /// returns the number of deleted rows.
+    fn delete(&mut self, _predicate: Option<&Expression>) -> DBResult<usize> {
+        Err(DBError::StorageEngine(
+            "delete is not supported by the table.".to_owned(),
+        ))
+    }
+}
+
+/// RowWriter writes rows to a file of some format one by one, e.g. for COPY TO.
+pub(crate) trait RowWriter {
+    fn write_row(&mut self, row: &Row<'static>) -> DBResult<()>;
+
+    /// write the rows buffered and the footer if any, and flush the file
+    fn finish(self: Box<Self>) -> DBResult<()>;
+}
+
+/// create a file to write, an existing file is truncated
+pub(crate) fn create_file(path: &str) -> DBResult<std::fs::File> {
+    std::fs::File::create(path)
+        .map_err(|e| DBError::StorageEngine(format!("create file {path} failed: {e}")))
+}
+
+/// ScanOptions tell a scan what the plan above it needs, tables are free to ignore
+/// them.
+#[derive(Debug, Clone, Default)]
+pub struct ScanOptions {
+    /// indexes of the fields to read, fields not read are null in the output rows. All
+    /// fields are read if None.
+    pub projection: Option<Vec<usize>>,
+    /// predicates the output rows are filtered by afterwards, rows which cannot satisfy
+    /// any of them may be skipped
+    pub filters: Vec<Expression>,
 }
 
 #[derive(Debug, Clone)]
 pub struct TableMeta {
     schema: RelationSchema,
+    /// DEFAULT values of columns, aligned with the fields of the schema
+    column_defaults: Vec<Option<Literal>>,
+    constraints: Vec<TableConstraint>,
 }
 
 impl TableMeta {
     pub fn new(schema: RelationSchema) -> Self {
-        TableMeta { schema }
+        let column_defaults = vec![None; schema.num_fields()];
+        TableMeta {
+            schema,
+            column_defaults,
+            constraints: Vec::new(),
+        }
+    }
+
+    pub fn with_column_defaults(mut self, column_defaults: Vec<Option<Literal>>) -> Self {
+        self.column_defaults = column_defaults;
+        self
+    }
+
+    pub fn with_constraints(mut self, constraints: Vec<TableConstraint>) -> Self {
+        self.constraints = constraints;
+        self
     }
 
     pub fn get_schema(&self) -> &RelationSchema {
         &self.schema
     }
+
+    /// the DEFAULT value of the column at the given index, if any
+    pub fn get_column_default(&self, idx: usize) -> Option<&Literal> {
+        self.column_defaults.get(idx).and_then(|d| d.as_ref())
+    }
+
+    pub fn get_constraints(&self) -> &[TableConstraint] {
+        &self.constraints
+    }
+
+    /// validate that the table has columns, the names of columns and constraints are
+    /// unique, and constraints only reference existing columns
+    pub fn validate(&self) -> DBResult<()> {
+        let fields = self.schema.get_fields();
+        if fields.is_empty() {
+            return Err(DBError::ParserError(
+                "table must have at least one column".to_owned(),
+            ));
+        }
+        for (idx, field) in fields.iter().enumerate() {
+            if fields[..idx].iter().any(|f| f.name() == field.name()) {
+                return Err(DBError::ParserError(format!(
+                    "column {} is defined more than once",
+                    field.name()
+                )));
+            }
+        }
+        for (idx, constraint) in self.constraints.iter().enumerate() {
+            if self.constraints[..idx]
+                .iter()
+                .any(|c| c.name() == constraint.name())
+            {
+                return Err(DBError::ParserError(format!(
+                    "constraint {} is defined more than once",
+                    constraint.name()
+                )));
+            }
+            if let Some(column) = constraint
+                .columns()
+                .into_iter()
+                .find(|c| self.schema.index_of(c).is_none())
+            {
+                return Err(DBError::ParserError(format!(
+                    "column {column} referenced by constraint {} not found",
+                    constraint.name()
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// a new meta with the column appended
+    pub fn with_new_column(
+        &self,
+        field: FieldInfo,
+        default: Option<Literal>,
+    ) -> DBResult<TableMeta> {
+        let mut meta = self.clone();
+        meta.schema.fields.push(field);
+        meta.column_defaults.push(default);
+        meta.validate().map(|_| meta)
+    }
+
+    /// a new meta without the column at the given index
+    pub fn without_column(&self, idx: usize) -> DBResult<TableMeta> {
+        let name = self.schema.fields[idx].name();
+        if let Some(constraint) = self
+            .constraints
+            .iter()
+            .find(|c| c.columns().iter().any(|c| c == name))
+        {
+            return Err(DBError::ParserError(format!(
+                "cannot drop column {name} referenced by constraint {}",
+                constraint.name()
+            )));
+        }
+        let mut meta = self.clone();
+        meta.schema.fields.remove(idx);
+        meta.column_defaults.remove(idx);
+        meta.validate().map(|_| meta)
+    }
+
+    /// a new meta with the column at the given index renamed
+    pub fn with_renamed_column(&self, idx: usize, name: String) -> DBResult<TableMeta> {
+        let mut meta = self.clone();
+        for constraint in meta.constraints.iter_mut() {
+            constraint.rename_column(self.schema.fields[idx].name(), &name);
+        }
+        meta.schema.fields[idx].name = name;
+        meta.validate().map(|_| meta)
+    }
 }
 #[derive(Debug, Clone, PartialEq)]
 pub struct RelationSchema {
@@ -49,6 +255,11 @@ impl RelationSchema {
         self.fields.get(idx)
     }
 
+    /// the index of the field with the given name
+    pub fn index_of(&self, name: &str) -> Option<usize> {
+        self.fields.iter().position(|f| f.name() == name)
+    }
+
     pub(crate) fn empty() -> RelationSchema {
         RelationSchema::new(vec![])
     }
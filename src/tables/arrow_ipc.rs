@@ -0,0 +1,141 @@
+use std::{
+    fs::File,
+    io::{BufReader, Read, Seek, SeekFrom, Write},
+};
+
+use arrow::{
+    array::RecordBatch,
+    datatypes::SchemaRef,
+    ipc::{
+        reader::{FileReader, StreamReader},
+        writer::FileWriter,
+    },
+};
+
+use crate::{
+    physical_plans::{ArrowScan, PhysicalPlan},
+    row::Row,
+    DBError, DBResult, ResultSet,
+};
+
+use super::{
+    arrow_types::{arrow_schema_of, data_type_from_arrow, record_batch_of},
+    FieldInfo, RelationSchema, ScanOptions, Table, TableMeta,
+};
+
+/// the magic bytes at the start of arrow ipc files, files without them are read as
+/// arrow ipc streams
+const ARROW_MAGIC: &[u8; 6] = b"ARROW1";
+
+/// Note, ArrowTable is readonly to an arrow ipc file, of either the file format (e.g.
+/// feather v2) or the streaming format.
+///
+/// Columns are read by their arrow types like parquet columns, and scans only read the
+/// columns a query needs.
+pub struct ArrowTable {
+    schema: RelationSchema,
+    path: String,
+}
+
+impl ArrowTable {
+    pub fn new(path: String) -> DBResult<Self> {
+        let reader = IpcBatchReader::new(&path, None)?;
+        let fields = reader
+            .schema()
+            .fields()
+            .iter()
+            .map(|field| {
+                data_type_from_arrow(field)
+                    .map(|data_type| FieldInfo::new(field.name().clone(), data_type))
+            })
+            .collect::<DBResult<Vec<_>>>()?;
+        Ok(ArrowTable {
+            schema: RelationSchema::new(fields),
+            path,
+        })
+    }
+}
+
+/// IpcBatchReader reads record batches of an arrow ipc file one by one.
+pub(crate) enum IpcBatchReader {
+    File(FileReader<BufReader<File>>),
+    Stream(StreamReader<BufReader<File>>),
+}
+
+impl IpcBatchReader {
+    /// open the file to read the columns at the indexes of the projection, or all
+    /// columns if None
+    pub(crate) fn new(path: &str, projection: Option<Vec<usize>>) -> DBResult<Self> {
+        let mut f = File::open(path).map_err(read_error)?;
+        let mut magic = [0; ARROW_MAGIC.len()];
+        let is_file_format =
+            f.read_exact(&mut magic).is_ok() && magic.as_slice() == ARROW_MAGIC;
+        f.seek(SeekFrom::Start(0)).map_err(read_error)?;
+        let reader = BufReader::new(f);
+        if is_file_format {
+            FileReader::try_new(reader, projection)
+                .map(IpcBatchReader::File)
+                .map_err(read_error)
+        } else {
+            StreamReader::try_new(reader, projection)
+                .map(IpcBatchReader::Stream)
+                .map_err(read_error)
+        }
+    }
+
+    pub(crate) fn schema(&self) -> SchemaRef {
+        match self {
+            IpcBatchReader::File(reader) => reader.schema(),
+            IpcBatchReader::Stream(reader) => reader.schema(),
+        }
+    }
+
+    pub(crate) fn next_batch(&mut self) -> DBResult<Option<RecordBatch>> {
+        let batch = match self {
+            IpcBatchReader::File(reader) => reader.next(),
+            IpcBatchReader::Stream(reader) => reader.next(),
+        };
+        batch.transpose().map_err(read_error)
+    }
+}
+
+fn read_error(e: impl std::fmt::Display) -> DBError {
+    DBError::StorageEngine(format!("read arrow file failed: {e}"))
+}
+
+/// write the result set in the arrow ipc file format, DateTime values are written as
+/// timestamps of microseconds
+pub(crate) fn write_ipc_file(result: &ResultSet, writer: impl Write) -> DBResult<()> {
+    let write_error = |e: arrow::error::ArrowError| {
+        DBError::StorageEngine(format!("write arrow failed: {e}"))
+    };
+    let batch = record_batch_of(&result.schema, &result.rows)?;
+    let mut writer = FileWriter::try_new(writer, &arrow_schema_of(&result.schema))
+        .map_err(write_error)?;
+    writer.write(&batch).map_err(write_error)?;
+    writer.finish().map_err(write_error)
+}
+
+impl Table for ArrowTable {
+    fn insert_data(&mut self, _data: Vec<Row<'static>>) -> DBResult<()> {
+        Err(DBError::StorageEngine(
+            "insert is not supported by arrow tables.".to_owned(),
+        ))
+    }
+
+    fn get_table_meta(&self) -> TableMeta {
+        TableMeta::new(self.schema.clone())
+    }
+
+    fn create_scan_op(&self) -> Box<dyn PhysicalPlan> {
+        self.create_scan_op_with(&ScanOptions::default())
+    }
+
+    fn create_scan_op_with(&self, options: &ScanOptions) -> Box<dyn PhysicalPlan> {
+        Box::new(ArrowScan::new(
+            self.path.clone(),
+            self.schema.clone(),
+            options.projection.clone(),
+        ))
+    }
+}
@@ -0,0 +1,384 @@
+use std::sync::Arc;
+
+use arrow::{
+    array::{
+        Array, ArrayRef, AsArray, BooleanArray, ListArray, NullArray, PrimitiveArray,
+        RecordBatch, RecordBatchOptions, StringArray,
+    },
+    buffer::{NullBuffer, OffsetBuffer},
+    compute::{cast_with_options, CastOptions},
+    datatypes::{
+        ArrowPrimitiveType, DataType as ArrowDataType, Date32Type, Date64Type,
+        Decimal128Type, Field, Float32Type, Float64Type, Int16Type, Int32Type, Int64Type,
+        Int8Type, Schema, TimeUnit, TimestampMicrosecondType, TimestampMillisecondType,
+        TimestampNanosecondType, TimestampSecondType, UInt16Type, UInt32Type, UInt64Type,
+        UInt8Type,
+    },
+};
+
+use crate::{data_types::DataType, expressions::Literal, row::Row, DBError, DBResult};
+
+use super::RelationSchema;
+
+/// the DataType of an arrow field. Timestamps and dates are mapped to DateTime, and
+/// decimals to Float64.
+pub(crate) fn data_type_from_arrow(field: &Field) -> DBResult<DataType> {
+    from_arrow_type(field.data_type()).ok_or_else(|| {
+        DBError::StorageEngine(format!(
+            "unsupported type {} of column {}",
+            field.data_type(),
+            field.name()
+        ))
+    })
+}
+
+fn from_arrow_type(data_type: &ArrowDataType) -> Option<DataType> {
+    match data_type {
+        ArrowDataType::Boolean => Some(DataType::Boolean),
+        ArrowDataType::Int8 => Some(DataType::Int8),
+        ArrowDataType::Int16 => Some(DataType::Int16),
+        ArrowDataType::Int32 => Some(DataType::Int32),
+        ArrowDataType::Int64 => Some(DataType::Int64),
+        ArrowDataType::UInt8 => Some(DataType::UInt8),
+        ArrowDataType::UInt16 => Some(DataType::UInt16),
+        ArrowDataType::UInt32 => Some(DataType::UInt32),
+        ArrowDataType::UInt64 => Some(DataType::UInt64),
+        ArrowDataType::Float32 => Some(DataType::Float32),
+        ArrowDataType::Float64 => Some(DataType::Float64),
+        ArrowDataType::Decimal128(_, _) => Some(DataType::Float64),
+        ArrowDataType::Utf8 | ArrowDataType::LargeUtf8 | ArrowDataType::Utf8View => {
+            Some(DataType::String)
+        }
+        ArrowDataType::Timestamp(_, _)
+        | ArrowDataType::Date32
+        | ArrowDataType::Date64 => Some(DataType::DateTime),
+        ArrowDataType::List(field) | ArrowDataType::LargeList(field) => {
+            from_arrow_type(field.data_type()).map(|t| DataType::List(Box::new(t)))
+        }
+        ArrowDataType::Null => Some(DataType::Unknown),
+        _ => None,
+    }
+}
+
+/// the arrow schema of a relation, all fields are nullable
+pub(crate) fn arrow_schema_of(schema: &RelationSchema) -> Schema {
+    Schema::new(
+        schema
+            .get_fields()
+            .iter()
+            .map(|field| Field::new(field.name(), to_arrow_type(field.data_type()), true))
+            .collect::<Vec<_>>(),
+    )
+}
+
+/// the arrow type of a data type, DateTime is mapped to timestamps of microseconds
+fn to_arrow_type(data_type: &DataType) -> ArrowDataType {
+    match data_type {
+        DataType::UInt8 => ArrowDataType::UInt8,
+        DataType::UInt16 => ArrowDataType::UInt16,
+        DataType::UInt32 => ArrowDataType::UInt32,
+        DataType::UInt64 => ArrowDataType::UInt64,
+        DataType::Int8 => ArrowDataType::Int8,
+        DataType::Int16 => ArrowDataType::Int16,
+        DataType::Int32 => ArrowDataType::Int32,
+        DataType::Int64 => ArrowDataType::Int64,
+        DataType::Float32 => ArrowDataType::Float32,
+        DataType::Float64 => ArrowDataType::Float64,
+        DataType::String => ArrowDataType::Utf8,
+        DataType::Boolean => ArrowDataType::Boolean,
+        DataType::DateTime => ArrowDataType::Timestamp(TimeUnit::Microsecond, None),
+        DataType::List(data_type) => {
+            ArrowDataType::new_list(to_arrow_type(data_type), true)
+        }
+        DataType::Unknown => ArrowDataType::Null,
+    }
+}
+
+/// a record batch of the rows, whose fields are of the data types of the schema
+pub(crate) fn record_batch_of(
+    schema: &RelationSchema,
+    rows: &[Row<'static>],
+) -> DBResult<RecordBatch> {
+    let columns = schema
+        .get_fields()
+        .iter()
+        .enumerate()
+        .map(|(idx, field)| {
+            let literals = rows
+                .iter()
+                .map(|row| row.get_field(idx))
+                .collect::<DBResult<Vec<_>>>()?;
+            array_of(&literals, field.data_type()).map_err(|e| match e {
+                DBError::StorageEngine(msg) => {
+                    DBError::StorageEngine(format!("column {}: {msg}", field.name()))
+                }
+                e => e,
+            })
+        })
+        .collect::<DBResult<Vec<_>>>()?;
+    let options = RecordBatchOptions::new().with_row_count(Some(rows.len()));
+    RecordBatch::try_new_with_options(
+        Arc::new(arrow_schema_of(schema)),
+        columns,
+        &options,
+    )
+    .map_err(|e| DBError::StorageEngine(format!("create arrow record batch failed: {e}")))
+}
+
+/// an arrow array of the literals, which are either of the data type or null
+pub(crate) fn array_of(literals: &[Literal], data_type: &DataType) -> DBResult<ArrayRef> {
+    let array: ArrayRef = match data_type {
+        DataType::UInt8 => {
+            primitive_array::<UInt8Type>(literals, data_type, |l| match l {
+                Literal::UInt8(v) => Some(*v),
+                _ => None,
+            })?
+        }
+        DataType::UInt16 => {
+            primitive_array::<UInt16Type>(literals, data_type, |l| match l {
+                Literal::UInt16(v) => Some(*v),
+                _ => None,
+            })?
+        }
+        DataType::UInt32 => {
+            primitive_array::<UInt32Type>(literals, data_type, |l| match l {
+                Literal::UInt32(v) => Some(*v),
+                _ => None,
+            })?
+        }
+        DataType::UInt64 => {
+            primitive_array::<UInt64Type>(literals, data_type, |l| match l {
+                Literal::UInt64(v) => Some(*v),
+                _ => None,
+            })?
+        }
+        DataType::Int8 => {
+            primitive_array::<Int8Type>(literals, data_type, |l| match l {
+                Literal::Int8(v) => Some(*v),
+                _ => None,
+            })?
+        }
+        DataType::Int16 => {
+            primitive_array::<Int16Type>(literals, data_type, |l| match l {
+                Literal::Int16(v) => Some(*v),
+                _ => None,
+            })?
+        }
+        DataType::Int32 => {
+            primitive_array::<Int32Type>(literals, data_type, |l| match l {
+                Literal::Int32(v) => Some(*v),
+                _ => None,
+            })?
+        }
+        DataType::Int64 => {
+            primitive_array::<Int64Type>(literals, data_type, |l| match l {
+                Literal::Int64(v) => Some(*v),
+                _ => None,
+            })?
+        }
+        DataType::Float32 => {
+            primitive_array::<Float32Type>(literals, data_type, |l| match l {
+                Literal::Float32(v) => Some(*v),
+                _ => None,
+            })?
+        }
+        DataType::Float64 => {
+            primitive_array::<Float64Type>(literals, data_type, |l| match l {
+                Literal::Float64(v) => Some(*v),
+                _ => None,
+            })?
+        }
+        DataType::Boolean => Arc::new(
+            literals
+                .iter()
+                .map(|l| match l {
+                    Literal::Bool(v) => Ok(Some(*v)),
+                    Literal::Null => Ok(None),
+                    l => Err(mismatch(l, data_type)),
+                })
+                .collect::<DBResult<BooleanArray>>()?,
+        ),
+        DataType::String => Arc::new(string_array(literals, data_type)?),
+        DataType::DateTime => {
+            // datetimes are parsed the same way as casting strings to timestamps
+            let strings = string_array(literals, data_type)?;
+            let options = CastOptions {
+                safe: false,
+                ..Default::default()
+            };
+            cast_with_options(&strings, &to_arrow_type(data_type), &options)
+                .map_err(|e| DBError::StorageEngine(format!("invalid datetime: {e}")))?
+        }
+        DataType::List(inner) => {
+            let mut values = vec![];
+            let mut lengths = vec![];
+            let mut valid = vec![];
+            for literal in literals {
+                match literal {
+                    Literal::List(list) => {
+                        values.extend(list.iter().cloned());
+                        lengths.push(list.len());
+                        valid.push(true);
+                    }
+                    Literal::Null => {
+                        lengths.push(0);
+                        valid.push(false);
+                    }
+                    l => return Err(mismatch(l, data_type)),
+                }
+            }
+            let field = Arc::new(Field::new_list_field(to_arrow_type(inner), true));
+            Arc::new(
+                ListArray::try_new(
+                    field,
+                    OffsetBuffer::from_lengths(lengths),
+                    array_of(&values, inner)?,
+                    Some(NullBuffer::from(valid)),
+                )
+                .map_err(|e| DBError::StorageEngine(format!("invalid list: {e}")))?,
+            )
+        }
+        DataType::Unknown => match literals.iter().find(|l| **l != Literal::Null) {
+            Some(l) => return Err(mismatch(l, data_type)),
+            None => Arc::new(NullArray::new(literals.len())),
+        },
+    };
+    Ok(array)
+}
+
+fn primitive_array<T: ArrowPrimitiveType>(
+    literals: &[Literal],
+    data_type: &DataType,
+    value_of: impl Fn(&Literal) -> Option<T::Native>,
+) -> DBResult<ArrayRef> {
+    let values = literals
+        .iter()
+        .map(|l| match l {
+            Literal::Null => Ok(None),
+            l => value_of(l).map(Some).ok_or_else(|| mismatch(l, data_type)),
+        })
+        .collect::<DBResult<Vec<_>>>()?;
+    Ok(Arc::new(PrimitiveArray::<T>::from_iter(values)))
+}
+
+fn string_array(literals: &[Literal], data_type: &DataType) -> DBResult<StringArray> {
+    literals
+        .iter()
+        .map(|l| match l {
+            Literal::String(v) | Literal::DateTime(v) => Ok(Some(v.as_str())),
+            Literal::Null => Ok(None),
+            l => Err(mismatch(l, data_type)),
+        })
+        .collect()
+}
+
+fn mismatch(literal: &Literal, data_type: &DataType) -> DBError {
+    DBError::StorageEngine(format!("value {literal} cannot be written as {data_type}"))
+}
+
+/// the value at the index of an array, of the data type mapped from the arrow type of
+/// the array
+pub(crate) fn literal_from_array(array: &dyn Array, idx: usize) -> DBResult<Literal> {
+    // null arrays have no null buffer
+    if array.is_null(idx) || array.data_type() == &ArrowDataType::Null {
+        return Ok(Literal::Null);
+    }
+    let literal = match array.data_type() {
+        ArrowDataType::Boolean => Literal::Bool(array.as_boolean().value(idx)),
+        ArrowDataType::Int8 => Literal::Int8(array.as_primitive::<Int8Type>().value(idx)),
+        ArrowDataType::Int16 => {
+            Literal::Int16(array.as_primitive::<Int16Type>().value(idx))
+        }
+        ArrowDataType::Int32 => {
+            Literal::Int32(array.as_primitive::<Int32Type>().value(idx))
+        }
+        ArrowDataType::Int64 => {
+            Literal::Int64(array.as_primitive::<Int64Type>().value(idx))
+        }
+        ArrowDataType::UInt8 => {
+            Literal::UInt8(array.as_primitive::<UInt8Type>().value(idx))
+        }
+        ArrowDataType::UInt16 => {
+            Literal::UInt16(array.as_primitive::<UInt16Type>().value(idx))
+        }
+        ArrowDataType::UInt32 => {
+            Literal::UInt32(array.as_primitive::<UInt32Type>().value(idx))
+        }
+        ArrowDataType::UInt64 => {
+            Literal::UInt64(array.as_primitive::<UInt64Type>().value(idx))
+        }
+        ArrowDataType::Float32 => {
+            Literal::Float32(array.as_primitive::<Float32Type>().value(idx))
+        }
+        ArrowDataType::Float64 => {
+            Literal::Float64(array.as_primitive::<Float64Type>().value(idx))
+        }
+        ArrowDataType::Decimal128(_, scale) => {
+            let value = array.as_primitive::<Decimal128Type>().value(idx);
+            Literal::Float64(value as f64 / 10f64.powi(*scale as i32))
+        }
+        ArrowDataType::Utf8 => {
+            Literal::String(array.as_string::<i32>().value(idx).to_owned())
+        }
+        ArrowDataType::LargeUtf8 => {
+            Literal::String(array.as_string::<i64>().value(idx).to_owned())
+        }
+        ArrowDataType::Utf8View => {
+            Literal::String(array.as_string_view().value(idx).to_owned())
+        }
+        ArrowDataType::Timestamp(unit, _) => {
+            let datetime = match unit {
+                TimeUnit::Second => array
+                    .as_primitive::<TimestampSecondType>()
+                    .value_as_datetime(idx),
+                TimeUnit::Millisecond => array
+                    .as_primitive::<TimestampMillisecondType>()
+                    .value_as_datetime(idx),
+                TimeUnit::Microsecond => array
+                    .as_primitive::<TimestampMicrosecondType>()
+                    .value_as_datetime(idx),
+                TimeUnit::Nanosecond => array
+                    .as_primitive::<TimestampNanosecondType>()
+                    .value_as_datetime(idx),
+            };
+            datetime_literal(datetime.map(|d| d.to_string()))?
+        }
+        ArrowDataType::Date32 => datetime_literal(
+            array
+                .as_primitive::<Date32Type>()
+                .value_as_datetime(idx)
+                .map(|d| d.to_string()),
+        )?,
+        ArrowDataType::Date64 => datetime_literal(
+            array
+                .as_primitive::<Date64Type>()
+                .value_as_datetime(idx)
+                .map(|d| d.to_string()),
+        )?,
+        ArrowDataType::List(_) => {
+            list_literal(array.as_list::<i32>().value(idx).as_ref())?
+        }
+        ArrowDataType::LargeList(_) => {
+            list_literal(array.as_list::<i64>().value(idx).as_ref())?
+        }
+        data_type => {
+            return Err(DBError::StorageEngine(format!(
+                "unsupported arrow type {data_type}"
+            )))
+        }
+    };
+    Ok(literal)
+}
+
+fn datetime_literal(datetime: Option<String>) -> DBResult<Literal> {
+    datetime.map(Literal::DateTime).ok_or_else(|| {
+        DBError::StorageEngine("datetime value is out of range".to_owned())
+    })
+}
+
+fn list_literal(values: &dyn Array) -> DBResult<Literal> {
+    (0..values.len())
+        .map(|idx| literal_from_array(values, idx))
+        .collect::<DBResult<Vec<_>>>()
+        .map(Literal::List)
+}
@@ -0,0 +1,99 @@
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader},
+};
+
+use bzip2::bufread::MultiBzDecoder;
+use flate2::bufread::MultiGzDecoder;
+use zstd::stream::read::Decoder as ZstdDecoder;
+
+/// the compression of a file, which text files like csv or json files may have
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Compression {
+    Gzip,
+    Zstd,
+    Bzip2,
+}
+
+impl Compression {
+    const ALL: [Compression; 3] =
+        [Compression::Gzip, Compression::Zstd, Compression::Bzip2];
+
+    fn extensions(&self) -> &'static [&'static str] {
+        match self {
+            Compression::Gzip => &[".gz", ".gzip"],
+            Compression::Zstd => &[".zst", ".zstd"],
+            Compression::Bzip2 => &[".bz2"],
+        }
+    }
+
+    /// whether the bytes start with the magic bytes of the compression. The magic of
+    /// bzip2 is `BZh` followed by the block size `1`..`9`, as text may start with `BZh`
+    fn is_magic_of(&self, bytes: &[u8]) -> bool {
+        match self {
+            Compression::Gzip => bytes.starts_with(b"\x1f\x8b"),
+            Compression::Zstd => bytes.starts_with(b"\x28\xb5\x2f\xfd"),
+            Compression::Bzip2 => {
+                matches!(bytes, [b'B', b'Z', b'h', b'1'..=b'9', ..])
+            }
+        }
+    }
+
+    /// the compression of the extension of the path, e.g. `orders.csv.gz`
+    fn from_path(path: &str) -> Option<Self> {
+        let path = path.to_lowercase();
+        Self::ALL.into_iter().find(|compression| {
+            compression
+                .extensions()
+                .iter()
+                .any(|ext| path.ends_with(ext))
+        })
+    }
+
+    /// the compression of the first bytes of a file
+    fn from_magic(bytes: &[u8]) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|compression| compression.is_magic_of(bytes))
+    }
+}
+
+/// the path without the extension of its compression, e.g. `orders.csv` of
+/// `orders.csv.gz`, which tells the format of the file
+pub(crate) fn strip_compression_extension(path: &str) -> &str {
+    match Compression::from_path(path) {
+        Some(compression) => {
+            let lower_case = path.to_lowercase();
+            let ext = compression
+                .extensions()
+                .iter()
+                .find(|ext| lower_case.ends_with(*ext))
+                .map_or(0, |ext| ext.len());
+            &path[..path.len() - ext]
+        }
+        None => path,
+    }
+}
+
+/// open the file for reading, files compressed by gzip, zstd or bzip2 are decompressed
+/// while they are read. The compression is told by the extension of the path, or the magic
+/// bytes at the start of the file otherwise.
+pub(crate) fn open_decompressed(path: &str) -> io::Result<Box<dyn BufRead>> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let compression = match Compression::from_path(path) {
+        Some(compression) => Some(compression),
+        None => Compression::from_magic(reader.fill_buf()?),
+    };
+    match compression {
+        None => Ok(Box::new(reader)),
+        Some(Compression::Gzip) => {
+            Ok(Box::new(BufReader::new(MultiGzDecoder::new(reader))))
+        }
+        Some(Compression::Zstd) => {
+            Ok(Box::new(BufReader::new(ZstdDecoder::with_buffer(reader)?)))
+        }
+        Some(Compression::Bzip2) => {
+            Ok(Box::new(BufReader::new(MultiBzDecoder::new(reader))))
+        }
+    }
+}
@@ -0,0 +1,274 @@
+use std::{collections::HashSet, ops::ControlFlow};
+
+use sqlparser::ast::{visit_expressions, visit_expressions_mut, Expr};
+
+use crate::{
+    expressions::{Expression, Literal},
+    interpreter::Interpreter,
+    optimizer::Optimizer,
+    parser::ast_expr_to_plan_expr,
+    row::Row,
+    DBError, DBResult,
+};
+
+use super::TableMeta;
+
+/// TableConstraint is a rule which every row of a table must satisfy, it is enforced
+/// when rows are inserted or updated.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TableConstraint {
+    /// the column is never null
+    NotNull { name: String, column: String },
+    /// the values of the columns are unique among rows, rows with null values are not
+    /// considered. PRIMARY KEY is a unique constraint of NOT NULL columns.
+    Unique {
+        name: String,
+        columns: Vec<String>,
+        is_primary: bool,
+    },
+    /// the expression is never false
+    Check { name: String, expr: Expr },
+}
+
+impl TableConstraint {
+    pub fn name(&self) -> &str {
+        match self {
+            TableConstraint::NotNull { name, .. }
+            | TableConstraint::Unique { name, .. }
+            | TableConstraint::Check { name, .. } => name,
+        }
+    }
+
+    /// names of the columns referenced by the constraint
+    pub fn columns(&self) -> Vec<String> {
+        match self {
+            TableConstraint::NotNull { column, .. } => vec![column.clone()],
+            TableConstraint::Unique { columns, .. } => columns.clone(),
+            TableConstraint::Check { expr, .. } => {
+                let mut columns = Vec::new();
+                let _ = visit_expressions(expr, |e| {
+                    if let Expr::Identifier(ident) = e {
+                        columns.push(ident.value.clone());
+                    }
+                    ControlFlow::<()>::Continue(())
+                });
+                columns
+            }
+        }
+    }
+
+    pub(crate) fn rename_column(&mut self, old_name: &str, new_name: &str) {
+        match self {
+            TableConstraint::NotNull { column, .. } => {
+                if column == old_name {
+                    *column = new_name.to_owned();
+                }
+            }
+            TableConstraint::Unique { columns, .. } => {
+                for column in columns.iter_mut().filter(|c| *c == old_name) {
+                    *column = new_name.to_owned();
+                }
+            }
+            TableConstraint::Check { expr, .. } => {
+                let _ = visit_expressions_mut(expr, |e| {
+                    if let Expr::Identifier(ident) = e {
+                        if ident.value == old_name {
+                            ident.value = new_name.to_owned();
+                        }
+                    }
+                    ControlFlow::<()>::Continue(())
+                });
+            }
+        }
+    }
+}
+
+/// RowChecker checks NOT NULL and CHECK constraints of a table against single rows.
+///
+/// CHECK expressions are resolved against the table schema when the checker is created.
+pub(crate) struct RowChecker {
+    not_nulls: Vec<(usize, String)>,
+    checks: Vec<(Expression, String)>,
+}
+
+impl RowChecker {
+    pub(crate) fn new(meta: &TableMeta) -> DBResult<Self> {
+        let schema = meta.get_schema();
+        let mut not_nulls = Vec::new();
+        let mut checks = Vec::new();
+        for constraint in meta.get_constraints() {
+            match constraint {
+                TableConstraint::NotNull { name, column } => {
+                    if let Some(idx) = schema.index_of(column) {
+                        not_nulls.push((idx, name.clone()));
+                    }
+                }
+                TableConstraint::Check { name, expr } => {
+                    let expr = Optimizer::optimize_expr(
+                        ast_expr_to_plan_expr(expr)?,
+                        schema.clone(),
+                    )?;
+                    checks.push((expr, name.clone()));
+                }
+                TableConstraint::Unique { .. } => {}
+            }
+        }
+        Ok(Self { not_nulls, checks })
+    }
+
+    pub(crate) fn check(&self, row: &Row) -> DBResult<()> {
+        for (idx, name) in self.not_nulls.iter() {
+            if row.get_field(*idx)? == Literal::Null {
+                return Err(DBError::NotNullViolation(name.clone()));
+            }
+        }
+        for (expr, name) in self.checks.iter() {
+            match Interpreter::eval(expr, row)? {
+                Literal::Bool(false) => {
+                    return Err(DBError::CheckViolation(name.clone()));
+                }
+                Literal::Bool(true) | Literal::Null => {}
+                value => {
+                    return Err(DBError::InterpretingError(format!(
+                        "CHECK constraint {name} must be boolean but got {value}"
+                    )))
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// keys of a unique index, which are values of the columns of the constraint
+type IndexKeys = HashSet<Vec<Literal>>;
+
+/// UniqueIndex is the hash set of keys of a UNIQUE or PRIMARY KEY constraint
+#[derive(Debug, Clone)]
+pub(crate) struct UniqueIndex {
+    name: String,
+    columns: Vec<usize>,
+    keys: IndexKeys,
+}
+
+impl UniqueIndex {
+    /// build indexes of all unique constraints of the table from its rows
+    pub(crate) fn build_all(
+        meta: &TableMeta,
+        rows: &[Row],
+    ) -> DBResult<Vec<UniqueIndex>> {
+        meta.get_constraints()
+            .iter()
+            .filter_map(|constraint| match constraint {
+                TableConstraint::Unique { name, columns, .. } => {
+                    let columns = columns
+                        .iter()
+                        .filter_map(|c| meta.get_schema().index_of(c))
+                        .collect();
+                    let index = UniqueIndex {
+                        name: name.clone(),
+                        columns,
+                        keys: HashSet::new(),
+                    };
+                    Some(index.rebuilt(rows.iter()))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// the key of the row, None if any of the values is null
+    fn key(&self, row: &Row) -> DBResult<Option<Vec<Literal>>> {
+        let key = self
+            .columns
+            .iter()
+            .map(|idx| row.get_field(*idx))
+            .collect::<DBResult<Vec<_>>>()?;
+        Ok(Some(key).filter(|k| !k.contains(&Literal::Null)))
+    }
+
+    /// keys of rows to be added to the index, fails if any of them is already in the
+    /// index or duplicated among the rows
+    pub(crate) fn new_keys<'a, 'b: 'a>(
+        &self,
+        rows: impl Iterator<Item = &'a Row<'b>>,
+    ) -> DBResult<IndexKeys> {
+        let mut new_keys = HashSet::new();
+        for row in rows {
+            if let Some(key) = self.key(row)? {
+                if self.keys.contains(&key) || !new_keys.insert(key) {
+                    return Err(DBError::UniqueViolation(self.name.clone()));
+                }
+            }
+        }
+        Ok(new_keys)
+    }
+
+    pub(crate) fn add_keys(&mut self, keys: IndexKeys) {
+        self.keys.extend(keys)
+    }
+
+    /// keys of rows to be replaced by updated rows, and keys of the updated rows. Fails
+    /// if any key of the updated rows is already in the index but not replaced, or
+    /// duplicated among the updated rows
+    pub(crate) fn replaced_keys<'a, 'b: 'a>(
+        &self,
+        updates: impl Iterator<Item = (&'a Row<'b>, &'a Row<'b>)>,
+    ) -> DBResult<(IndexKeys, IndexKeys)> {
+        let mut old_keys = HashSet::new();
+        let mut new_rows = Vec::new();
+        for (old_row, new_row) in updates {
+            old_keys.extend(self.key(old_row)?);
+            new_rows.push(new_row);
+        }
+        let mut new_keys = HashSet::new();
+        for row in new_rows {
+            if let Some(key) = self.key(row)? {
+                let is_taken = self.keys.contains(&key) && !old_keys.contains(&key);
+                if is_taken || !new_keys.insert(key) {
+                    return Err(DBError::UniqueViolation(self.name.clone()));
+                }
+            }
+        }
+        Ok((old_keys, new_keys))
+    }
+
+    pub(crate) fn replace_keys(&mut self, (old_keys, new_keys): (IndexKeys, IndexKeys)) {
+        self.keys.retain(|key| !old_keys.contains(key));
+        self.keys.extend(new_keys)
+    }
+
+    /// keys of rows to be removed from the index
+    pub(crate) fn removed_keys<'a, 'b: 'a>(
+        &self,
+        rows: impl Iterator<Item = &'a Row<'b>>,
+    ) -> DBResult<IndexKeys> {
+        let mut keys = HashSet::new();
+        for row in rows {
+            keys.extend(self.key(row)?);
+        }
+        Ok(keys)
+    }
+
+    pub(crate) fn remove_keys(&mut self, keys: IndexKeys) {
+        self.keys.retain(|key| !keys.contains(key));
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.keys.clear();
+    }
+
+    /// a new index with keys of the given rows only
+    pub(crate) fn rebuilt<'a, 'b: 'a>(
+        &self,
+        rows: impl Iterator<Item = &'a Row<'b>>,
+    ) -> DBResult<UniqueIndex> {
+        let mut index = UniqueIndex {
+            name: self.name.clone(),
+            columns: self.columns.clone(),
+            keys: HashSet::new(),
+        };
+        let keys = index.new_keys(rows)?;
+        index.add_keys(keys);
+        Ok(index)
+    }
+}
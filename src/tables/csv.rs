@@ -1,187 +1,654 @@
 use std::{
     fs::File,
-    io::{BufRead, BufReader},
+    io::{BufRead, BufWriter, Write},
 };
 
 use csv_core::{Reader, ReaderBuilder};
 
 use crate::{
     data_types::DataType,
+    expressions::Literal,
+    parser::{parse_column_types, FileOption},
     physical_plans::{CsvScan, PhysicalPlan},
     row::Row,
     DBError, DBResult,
 };
 
-use super::{FieldInfo, RelationSchema, Table, TableMeta};
+use super::{
+    compression::open_decompressed, create_file, FieldInfo, RelationSchema, RowWriter,
+    Table, TableMeta,
+};
 
 const NUM_RECORDS_TO_INFER_SCHEMA: usize = 10;
 
+/// What scans do with values which cannot be read as the types of their columns, e.g.
+/// values after the records the types are inferred from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvalidValuePolicy {
+    /// fail the scan with the position of the record
+    Error,
+    /// read the values as nulls
+    Null,
+}
+
+/// Options of reading csv files, i.e. the dialect of the files and how the types of
+/// columns are inferred.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsvOptions {
+    /// whether the first record is the names of the fields, fields are named `column0`,
+    /// `column1`... otherwise
+    pub header: bool,
+    pub delimiter: u8,
+    pub quote: u8,
+    /// the character escaping quotes in quoted fields, besides doubled quotes
+    pub escape: Option<u8>,
+    /// the texts of null values, empty fields and `NULL` by default
+    pub nulls: Vec<String>,
+    /// the number of lines to skip at the start of the file, before the header
+    pub skip: usize,
+    /// the number of records to infer the types from, all records are read if None
+    pub sample_size: Option<usize>,
+    /// the types of columns by name, instead of the inferred types
+    pub column_types: Vec<(String, DataType)>,
+    pub invalid_values: InvalidValuePolicy,
+    /// whether integers are inferred as Int32 if the sampled values fit, rather than
+    /// Int64. Note that values after the sample may not fit, and sums may overflow.
+    pub narrow_integers: bool,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            header: true,
+            delimiter: b',',
+            quote: b'"',
+            escape: None,
+            nulls: vec![String::new(), "NULL".to_owned()],
+            skip: 0,
+            sample_size: Some(NUM_RECORDS_TO_INFER_SCHEMA),
+            column_types: vec![],
+            invalid_values: InvalidValuePolicy::Error,
+            narrow_integers: false,
+        }
+    }
+}
+
+impl CsvOptions {
+    /// the options set by the file options, e.g. of `read_csv` or
+    /// `CREATE EXTERNAL TABLE`
+    pub(crate) fn try_from_options(options: &[FileOption]) -> DBResult<Self> {
+        let mut csv_options = Self::default();
+        for option in options {
+            if !csv_options.set_option(option)? {
+                return Err(DBError::ParserError(format!(
+                    "csv option {option} is not supported"
+                )));
+            }
+        }
+        Ok(csv_options)
+    }
+
+    /// set the option if it is a csv option, returns whether it is
+    pub(crate) fn set_option(&mut self, option: &FileOption) -> DBResult<bool> {
+        match option.name() {
+            "header" => self.header = option.bool_value()?,
+            "delimiter" | "delim" | "sep" => self.delimiter = option.char_value()?,
+            "quote" => self.quote = option.char_value()?,
+            "escape" => self.escape = Some(option.char_value()?),
+            "null" => self.nulls = vec![option.string_value()?.to_owned()],
+            "skip" => self.skip = option.usize_value()?,
+            "sample_size" => self.sample_size = option.sample_size_value()?,
+            "types" => self.column_types = parse_column_types(option.string_value()?)?,
+            "narrow_integers" => self.narrow_integers = option.bool_value()?,
+            "invalid_values" => {
+                self.invalid_values = match option.string_value()?.to_lowercase().as_str()
+                {
+                    "error" => InvalidValuePolicy::Error,
+                    "null" => InvalidValuePolicy::Null,
+                    _ => {
+                        return Err(DBError::ParserError(format!(
+                            "invalid option {option}: expected error or null"
+                        )))
+                    }
+                }
+            }
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    /// whether the value of a field is null
+    pub(crate) fn is_null(&self, value: &str) -> bool {
+        self.nulls.iter().any(|null| null == value)
+    }
+}
+
 /// Note, CsvTable is readonly to a csv file.
-/// Only a limit set of data types are supported: String, boolean, Int64, Float64, DateTime.
+/// Only a limit set of data types are inferred: String, Boolean, Int32, Int64, Float64
+/// and DateTime, while columns of other types may be declared by `column_types` or
+/// `CREATE EXTERNAL TABLE`.
 pub struct CsvTable {
     schema: RelationSchema,
     path: String,
+    options: CsvOptions,
 }
 
 impl CsvTable {
     pub fn new(path: String) -> DBResult<Self> {
-        let mut reader = CsvRecordReader::new(path.as_str())?;
-        // assume first line is headers
-        let headers: Vec<String> = reader.try_read_next()?.ok_or(
-            DBError::StorageEngine("Provided csv file is empty.".to_owned()),
-        )?;
+        Self::with_options(path, CsvOptions::default())
+    }
+
+    pub fn with_options(path: String, options: CsvOptions) -> DBResult<Self> {
+        let mut reader = CsvRecordReader::with_options(path.as_str(), &options)?;
+        let (headers, mut next_record) = Self::read_headers(&mut reader, &options)?;
 
-        // read ahead NUM_RECORDS_TO_INFER_SCHEMA lines to determine the field data types
+        // read ahead the sample of records to determine the field data types
         let mut data_types = vec![DataType::Unknown; headers.len()];
         let mut num_records_read = 0;
-        while let Some(result_record) = reader.try_read_next()? {
-            num_records_read += 1;
-            // TODO: better to provide the line # for debugability
-            if result_record.len() != headers.len() {
-                return Err(DBError::StorageEngine(
-                    "Csv records is not align with headers.".to_owned(),
-                ));
+        while let Some(record) = next_record {
+            if options
+                .sample_size
+                .is_some_and(|size| num_records_read >= size)
+            {
+                break;
             }
-            for (idx, field) in result_record.into_iter().enumerate() {
-                let data_type = Self::determine_data_type(field, &data_types[idx]);
-                data_types[idx] = data_type;
+            num_records_read += 1;
+            if record.len() != headers.len() {
+                return Err(reader.malformed(format!(
+                    "expected {} fields but got {}",
+                    headers.len(),
+                    record.len()
+                )));
             }
-            if num_records_read >= NUM_RECORDS_TO_INFER_SCHEMA {
-                break;
+            for (idx, field) in record.into_iter().enumerate() {
+                if options.is_null(&field) {
+                    continue;
+                }
+                data_types[idx] = Self::determine_data_type(
+                    &field,
+                    &data_types[idx],
+                    options.narrow_integers,
+                );
             }
+            next_record = reader.try_read_next()?;
         }
 
-        let fields = Iterator::zip(headers.into_iter(), data_types.into_iter())
-            .map(|(name, data_type)| FieldInfo::new(name, data_type))
-            .collect();
+        // fields of only nulls are read as strings
+        let mut fields = Iterator::zip(headers.into_iter(), data_types)
+            .map(|(name, data_type)| match data_type {
+                DataType::Unknown => FieldInfo::new(name, DataType::String),
+                data_type => FieldInfo::new(name, data_type),
+            })
+            .collect::<Vec<_>>();
+        for (name, data_type) in &options.column_types {
+            let field = fields
+                .iter_mut()
+                .find(|field| field.name() == name)
+                .ok_or_else(|| {
+                    DBError::ParserError(format!("column {name} not found in {path}"))
+                })?;
+            *field = FieldInfo::new(name.clone(), data_type.clone());
+        }
         let schema = RelationSchema::new(fields);
-        Ok(CsvTable { schema, path })
+        Ok(CsvTable {
+            schema,
+            path,
+            options,
+        })
+    }
+
+    /// the names of the fields of a csv file, by its header or `column0`, `column1`...
+    pub(crate) fn field_names(path: &str, options: &CsvOptions) -> DBResult<Vec<String>> {
+        let mut reader = CsvRecordReader::with_options(path, options)?;
+        Ok(Self::read_headers(&mut reader, options)?.0)
+    }
+
+    /// the names of the fields and the first record after the header
+    fn read_headers(
+        reader: &mut CsvRecordReader,
+        options: &CsvOptions,
+    ) -> DBResult<(Vec<String>, Option<Vec<String>>)> {
+        let first_record: Vec<String> = reader.try_read_next()?.ok_or(
+            DBError::StorageEngine("Provided csv file is empty.".to_owned()),
+        )?;
+        if options.header {
+            Ok((first_record, reader.try_read_next()?))
+        } else {
+            let headers = (0..first_record.len())
+                .map(|idx| format!("column{idx}"))
+                .collect();
+            Ok((headers, Some(first_record)))
+        }
     }
 
-    fn determine_data_type(field: String, type_hint: &DataType) -> DataType {
-        let lower_case = field.to_lowercase();
-        // TODO: support date time
-        let data_type = match lower_case.as_str() {
-            "true" => DataType::Boolean,
-            "false" => DataType::Boolean,
-            _ if lower_case.parse::<i64>().is_ok() => DataType::Int64,
-            _ if lower_case.parse::<f64>().is_ok() => DataType::Float64,
+    /// the table of the columns declared, rather than inferred from the file
+    pub fn with_schema(
+        path: String,
+        schema: RelationSchema,
+        options: CsvOptions,
+    ) -> DBResult<Self> {
+        // fail early if the file cannot be read
+        let _ = CsvRecordReader::with_options(path.as_str(), &options)?;
+        Ok(CsvTable {
+            schema,
+            path,
+            options,
+        })
+    }
+
+    /// the data type of both the field and the values before, whose data type is the
+    /// hint. Integers are Int32 if they fit and `narrow_integers` is set
+    pub(crate) fn determine_data_type(
+        field: &str,
+        type_hint: &DataType,
+        narrow_integers: bool,
+    ) -> DataType {
+        let trimmed = field.trim();
+        let data_type = match trimmed.to_lowercase().as_str() {
+            "true" | "false" => DataType::Boolean,
+            _ if narrow_integers && trimmed.parse::<i32>().is_ok() => DataType::Int32,
+            _ if trimmed.parse::<i64>().is_ok() => DataType::Int64,
+            _ if trimmed.parse::<f64>().is_ok() => DataType::Float64,
+            _ if parse_datetime(trimmed).is_some() => DataType::DateTime,
             _ => DataType::String,
         };
-        if &data_type == type_hint {
-            data_type
-        } else {
-            match Self::data_type_generality(&data_type)
-                .cmp(&Self::data_type_generality(type_hint))
-            {
-                std::cmp::Ordering::Less => type_hint.clone(),
-                std::cmp::Ordering::Equal => DataType::String,
-                std::cmp::Ordering::Greater => data_type,
-            }
+        match (type_hint, data_type) {
+            (DataType::Unknown, data_type) => data_type,
+            (hint, data_type) if *hint == data_type => data_type,
+            // numbers are read as the most general type of them
+            (hint, data_type) => match (
+                Self::numeric_generality(hint),
+                Self::numeric_generality(&data_type),
+            ) {
+                (Some(left), Some(right)) if left > right => hint.clone(),
+                (Some(_), Some(_)) => data_type,
+                // String has most generality, since everything can be treated as
+                // string from csv
+                _ => DataType::String,
+            },
         }
     }
 
-    /// a data type with higer generality order will win when determin/reconcil two data types
-    fn data_type_generality(data_type: &DataType) -> u8 {
+    /// a numeric data type with higher generality wins when reconciling two numeric
+    /// data types, None for other data types
+    fn numeric_generality(data_type: &DataType) -> Option<u8> {
         match data_type {
-            DataType::Int64 => 1,
+            DataType::Int32 => Some(1),
+            DataType::Int64 => Some(2),
             // Float has higher generality than Int
-            DataType::Float64 => 2,
-            // String has most generality, since everything can be treated as string from csv
-            DataType::String => u8::MAX,
-            DataType::Boolean => 1,
-            // everything else is not supported, thus least generality
-            _ => 0,
+            DataType::Float64 => Some(3),
+            _ => None,
+        }
+    }
+}
+
+/// the text of a date `YYYY-MM-DD`, or a timestamp of a date and a time
+/// `HH:MM[:SS[.fraction]]` separated by a space or `T`, optionally followed by `Z`. None
+/// if the value is not a valid date or timestamp. Timestamps are normalized into
+/// `YYYY-MM-DD HH:MM:SS[.fraction]`, since DateTime values are compared by their texts.
+pub(crate) fn parse_datetime(value: &str) -> Option<String> {
+    let number = |s: &str, len: usize, max: u32| {
+        Some(s)
+            .filter(|s| s.len() == len && s.bytes().all(|b| b.is_ascii_digit()))
+            .and_then(|s| s.parse::<u32>().ok())
+            .filter(|n| *n <= max)
+    };
+    let is_date = |date: &str| match date.split('-').collect::<Vec<_>>().as_slice() {
+        [year, month, day] => {
+            let year = number(year, 4, 9999);
+            let month = number(month, 2, 12).filter(|month| *month > 0);
+            match (year, month) {
+                (Some(year), Some(month)) => {
+                    number(day, 2, days_of_month(year, month)).is_some_and(|day| day > 0)
+                }
+                _ => false,
+            }
+        }
+        _ => false,
+    };
+    let normalize_time = |time: &str| {
+        let time = time.strip_suffix('Z').unwrap_or(time);
+        let (time, fraction) = match time.split_once('.') {
+            Some((time, fraction))
+                if !fraction.is_empty()
+                    && fraction.bytes().all(|b| b.is_ascii_digit()) =>
+            {
+                (time, fraction.trim_end_matches('0'))
+            }
+            Some(_) => return None,
+            None => (time, ""),
+        };
+        let (hour, minute, second) = match time.split(':').collect::<Vec<_>>().as_slice()
+        {
+            [hour, minute] if fraction.is_empty() => (*hour, *minute, "00"),
+            [hour, minute, second] => (*hour, *minute, *second),
+            _ => return None,
+        };
+        number(hour, 2, 23)?;
+        number(minute, 2, 59)?;
+        number(second, 2, 60)?;
+        match fraction {
+            "" => Some(format!("{hour}:{minute}:{second}")),
+            fraction => Some(format!("{hour}:{minute}:{second}.{fraction}")),
+        }
+    };
+    match value.split_once([' ', 'T']) {
+        Some((date, time)) if is_date(date) => {
+            normalize_time(time).map(|time| format!("{date} {time}"))
+        }
+        Some(_) => None,
+        None => is_date(value).then(|| value.to_owned()),
+    }
+}
+
+/// the number of days of the month of the year
+fn days_of_month(year: u32, month: u32) -> u32 {
+    match month {
+        2 if year.is_multiple_of(4)
+            && (!year.is_multiple_of(100) || year.is_multiple_of(400)) =>
+        {
+            29
         }
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
     }
 }
 
+/// the byte order mark of UTF-8, which is skipped at the start of files
+const UTF8_BOM: &[u8; 3] = b"\xEF\xBB\xBF";
+
+/// CsvRecordReader reads records of a csv file one by one. Bytes are fed to csv_core
+/// incrementally, so quoted fields may contain line breaks, and records or fields of
+/// any length and number are read. Lines end with LF or CRLF, and empty lines are
+/// skipped.
 pub struct CsvRecordReader {
-    buf_reader: BufReader<File>,
-    inputs_buf: String,
+    buf_reader: Box<dyn BufRead>,
+    csv_reader: Reader,
+    /// the unescaped bytes of the fields of the current record
     outputs_buf: Vec<u8>,
+    /// the end offsets of the fields in outputs_buf
     field_indices: Vec<usize>,
-    csv_reader: Reader,
+    /// the line number and byte offset of the next byte to read
+    next_line: usize,
+    next_offset: u64,
+    /// the line number and byte offset of the start of the last record
+    line: usize,
+    offset: u64,
 }
 
 impl CsvRecordReader {
     pub fn new(path: &str) -> DBResult<Self> {
-        let csv_reader = ReaderBuilder::new().build();
-        // TODO: handle error properly
-        let f = File::open(path)
-            .map_err(|e| DBError::StorageEngine(format!("read csv file failed: {e}")))?;
-        let buf_reader = BufReader::new(f);
+        Self::with_options(path, &CsvOptions::default())
+    }
 
-        let inputs_buf = String::with_capacity(4096);
-        let outputs_buf = [0; 4096].to_vec();
-        let field_indices = [0; 256].to_vec();
-        Ok(Self {
+    /// the reader of records in the dialect of the options, lines to skip are skipped
+    /// but the header is read as a record
+    pub fn with_options(path: &str, options: &CsvOptions) -> DBResult<Self> {
+        let csv_reader = ReaderBuilder::new()
+            .delimiter(options.delimiter)
+            .quote(options.quote)
+            .escape(options.escape)
+            .build();
+        let buf_reader = open_decompressed(path)
+            .map_err(|e| DBError::StorageEngine(format!("read csv file failed: {e}")))?;
+        let mut reader = Self {
             buf_reader,
-            inputs_buf,
-            outputs_buf,
-            field_indices,
             csv_reader,
-        })
+            outputs_buf: vec![0; 4096],
+            field_indices: vec![0; 64],
+            next_line: 1,
+            next_offset: 0,
+            line: 0,
+            offset: 0,
+        };
+        if reader.fill_buf()?.starts_with(UTF8_BOM) {
+            reader.consume(UTF8_BOM.len(), 0);
+        }
+        for _ in 0..options.skip {
+            let mut line = vec![];
+            let num_read = reader
+                .buf_reader
+                .read_until(b'\n', &mut line)
+                .map_err(read_error)?;
+            reader.next_line += 1;
+            reader.next_offset += num_read as u64;
+        }
+        Ok(reader)
+    }
+
+    /// the line number of the start of the last record, lines are numbered from 1
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// the byte offset of the start of the last record
+    pub fn byte_offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// the error of a malformed record, at the position of the last record
+    pub fn malformed(&self, msg: impl std::fmt::Display) -> DBError {
+        DBError::StorageEngine(format!(
+            "malformed csv record at line {} (byte {}): {msg}",
+            self.line, self.offset
+        ))
     }
 
     pub fn try_read_next(&mut self) -> DBResult<Option<Vec<String>>> {
-        // read_line will fail when encounter invalid UTF-8 bytes
-        self.inputs_buf.clear();
-        let _num_read = self
-            .buf_reader
-            .read_line(&mut self.inputs_buf)
-            .map_err(|e| DBError::StorageEngine(format!("read csv file error: {e}")))?;
-        let inputs = self.inputs_buf.as_bytes();
-        let (result, _num_read, _num_write, num_fields) = self.csv_reader.read_record(
-            inputs,
-            self.outputs_buf.as_mut_slice(),
-            self.field_indices.as_mut_slice(),
-        );
-        match result {
-            csv_core::ReadRecordResult::InputEmpty => {
-                // this could happen for the last line of the csv
-                unreachable!()
-            }
-            csv_core::ReadRecordResult::OutputFull => {
-                // TODO: consider increase outputs buffer
-                Err(DBError::StorageEngine(
-                    "Exceeded maximum buffer size: 4096".to_string(),
-                ))
+        // skip line breaks before the record, so that its position is the start of its
+        // first field
+        loop {
+            let input = self.fill_buf()?;
+            let breaks = input.iter().take_while(|b| matches!(b, b'\r' | b'\n'));
+            let (num_breaks, num_lines) =
+                breaks.fold((0, 0), |(n, l), &b| (n + 1, l + usize::from(b == b'\n')));
+            if num_breaks == 0 {
+                break;
             }
-            csv_core::ReadRecordResult::OutputEndsFull => Err(DBError::Unknown(
-                "Exceeding maximum supported num fields (256) when reading csv."
-                    .to_owned(),
-            )),
-            csv_core::ReadRecordResult::Record => {
-                let mut offset = 0;
-                let record = (0..num_fields)
-                    .map(|field_idx| {
-                        let end = self.field_indices[field_idx];
-                        // from_utf8_lossy will check the UTF-8 validity against the bytes
-                        let v = String::from_utf8_lossy(&self.outputs_buf[offset..end]);
-                        offset = end;
-                        v.to_string()
-                    })
-                    .collect();
-                Ok(Some(record))
+            self.consume(num_breaks, num_lines);
+        }
+        self.line = self.next_line;
+        self.offset = self.next_offset;
+
+        let (mut num_output, mut num_fields) = (0, 0);
+        loop {
+            let input = self.buf_reader.fill_buf().map_err(read_error)?;
+            let (result, num_read, num_write, num_ends) = self.csv_reader.read_record(
+                input,
+                &mut self.outputs_buf[num_output..],
+                &mut self.field_indices[num_fields..],
+            );
+            let num_lines = input[..num_read].iter().filter(|&&b| b == b'\n').count();
+            self.consume(num_read, num_lines);
+            num_output += num_write;
+            num_fields += num_ends;
+            match result {
+                // an empty input is the end of the file for csv_core
+                csv_core::ReadRecordResult::InputEmpty => {}
+                csv_core::ReadRecordResult::OutputFull => {
+                    let len = self.outputs_buf.len();
+                    self.outputs_buf.resize(len * 2, 0);
+                }
+                csv_core::ReadRecordResult::OutputEndsFull => {
+                    let len = self.field_indices.len();
+                    self.field_indices.resize(len * 2, 0);
+                }
+                csv_core::ReadRecordResult::Record => break,
+                csv_core::ReadRecordResult::End => return Ok(None),
             }
-            csv_core::ReadRecordResult::End => Ok(None),
         }
+
+        let mut start = 0;
+        let record = self.field_indices[..num_fields]
+            .iter()
+            .map(|&end| {
+                let field = std::str::from_utf8(&self.outputs_buf[start..end])
+                    .map(str::to_owned)
+                    .map_err(|e| self.malformed(format!("invalid UTF-8: {e}")));
+                start = end;
+                field
+            })
+            .collect::<DBResult<Vec<_>>>()?;
+        Ok(Some(record))
+    }
+
+    fn fill_buf(&mut self) -> DBResult<&[u8]> {
+        self.buf_reader.fill_buf().map_err(read_error)
+    }
+
+    /// consume the bytes read from the buffer, which contain the number of line breaks
+    fn consume(&mut self, num_read: usize, num_lines: usize) {
+        self.buf_reader.consume(num_read);
+        self.next_offset += num_read as u64;
+        self.next_line += num_lines;
     }
 }
 
+fn read_error(e: std::io::Error) -> DBError {
+    DBError::StorageEngine(format!("read csv file error: {e}"))
+}
+
 impl Table for CsvTable {
-    fn insert_data(&mut self, _data: Vec<Row<'static>>) {
-        unimplemented!()
+    fn insert_data(&mut self, _data: Vec<Row<'static>>) -> DBResult<()> {
+        Err(DBError::StorageEngine(
+            "insert is not supported by csv tables.".to_owned(),
+        ))
     }
 
     fn get_table_meta(&self) -> TableMeta {
-        TableMeta {
-            schema: self.schema.clone(),
-        }
+        TableMeta::new(self.schema.clone())
     }
 
     fn create_scan_op(&self) -> Box<dyn PhysicalPlan> {
-        Box::new(CsvScan::new(self.path.clone(), self.schema.clone()))
+        Box::new(CsvScan::new(
+            self.path.clone(),
+            self.schema.clone(),
+            self.options.clone(),
+        ))
+    }
+}
+
+/// the value of a csv field as the data type, None if it cannot be read as the data
+/// type
+pub(crate) fn parse_csv_field(value: &str, data_type: &DataType) -> Option<Literal> {
+    let trimmed = value.trim();
+    match data_type {
+        DataType::UInt8 => trimmed.parse().ok().map(Literal::UInt8),
+        DataType::UInt16 => trimmed.parse().ok().map(Literal::UInt16),
+        DataType::UInt32 => trimmed.parse().ok().map(Literal::UInt32),
+        DataType::UInt64 => trimmed.parse().ok().map(Literal::UInt64),
+        DataType::Int8 => trimmed.parse().ok().map(Literal::Int8),
+        DataType::Int16 => trimmed.parse().ok().map(Literal::Int16),
+        DataType::Int32 => trimmed.parse().ok().map(Literal::Int32),
+        DataType::Int64 => trimmed.parse().ok().map(Literal::Int64),
+        DataType::Float32 => trimmed.parse().ok().map(Literal::Float32),
+        DataType::Float64 => trimmed.parse().ok().map(Literal::Float64),
+        DataType::Boolean => match trimmed.to_lowercase().as_str() {
+            "true" | "t" | "yes" | "1" => Some(Literal::Bool(true)),
+            "false" | "f" | "no" | "0" => Some(Literal::Bool(false)),
+            _ => None,
+        },
+        DataType::String => Some(Literal::String(value.to_owned())),
+        DataType::DateTime => parse_datetime(trimmed).map(Literal::DateTime),
+        DataType::List(_) | DataType::Unknown => None,
+    }
+}
+
+/// Options of writing csv files.
+#[derive(Debug, Clone)]
+pub struct CsvWriteOptions {
+    /// whether the first line is the names of the fields, as csv tables expect
+    pub header: bool,
+    pub delimiter: u8,
+    pub quote: u8,
+    /// the text of null values
+    pub null: String,
+}
+
+impl Default for CsvWriteOptions {
+    fn default() -> Self {
+        Self {
+            header: true,
+            delimiter: b',',
+            quote: b'"',
+            null: String::new(),
+        }
+    }
+}
+
+/// CsvWriter writes rows as csv records, values are quoted if they contain the
+/// delimiter, quotes or line breaks, or are the same as the text of nulls.
+pub(crate) struct CsvWriter {
+    writer: BufWriter<File>,
+    options: CsvWriteOptions,
+}
+
+impl CsvWriter {
+    pub(crate) fn try_new(
+        path: &str,
+        schema: &RelationSchema,
+        options: CsvWriteOptions,
+    ) -> DBResult<Self> {
+        let mut writer = Self {
+            writer: BufWriter::new(create_file(path)?),
+            options,
+        };
+        if writer.options.header {
+            let names = schema.get_fields().iter().map(|f| Some(f.name()));
+            writer.write_record(names)?;
+        }
+        Ok(writer)
+    }
+
+    /// write a line of the values, None is null
+    fn write_record<'a>(
+        &mut self,
+        values: impl Iterator<Item = Option<&'a str>>,
+    ) -> DBResult<()> {
+        let mut line = String::new();
+        let delimiter = self.options.delimiter as char;
+        let quote = self.options.quote as char;
+        for (idx, value) in values.enumerate() {
+            if idx > 0 {
+                line.push(delimiter);
+            }
+            match value {
+                None => line.push_str(&self.options.null),
+                Some(v)
+                    if v.contains([delimiter, quote, '\n', '\r'])
+                        || v == self.options.null =>
+                {
+                    line.push(quote);
+                    line.push_str(&v.replace(quote, &format!("{quote}{quote}")));
+                    line.push(quote);
+                }
+                Some(v) => line.push_str(v),
+            }
+        }
+        line.push('\n');
+        self.writer
+            .write_all(line.as_bytes())
+            .map_err(|e| DBError::StorageEngine(format!("write csv file error: {e}")))
+    }
+}
+
+impl RowWriter for CsvWriter {
+    fn write_row(&mut self, row: &Row<'static>) -> DBResult<()> {
+        let values = (0..row.num_fields())
+            .map(|idx| {
+                row.get_field(idx).map(|value| match value {
+                    Literal::Null => None,
+                    value => Some(value.to_string()),
+                })
+            })
+            .collect::<DBResult<Vec<_>>>()?;
+        self.write_record(values.iter().map(|v| v.as_deref()))
+    }
+
+    fn finish(mut self: Box<Self>) -> DBResult<()> {
+        self.writer
+            .flush()
+            .map_err(|e| DBError::StorageEngine(format!("write csv file error: {e}")))
     }
 }
@@ -0,0 +1,108 @@
+use crate::{
+    catalog::{Catalog, INFORMATION_SCHEMA},
+    data_types::DataType,
+    expressions::Literal,
+    physical_plans::{InMemTableScan, PhysicalPlan},
+    row::Row,
+    DBError, DBResult,
+};
+
+use super::{constraints::TableConstraint, FieldInfo, RelationSchema, Table, TableMeta};
+
+/// InformationSchemaTable is a read-only snapshot of the catalog, taken when the table
+/// is looked up, so every query sees the current tables and views.
+pub struct InformationSchemaTable {
+    meta: TableMeta,
+    data: Vec<Row<'static>>,
+}
+
+/// the information_schema table of the given qualified name, if any
+pub(crate) fn create_table(
+    catalog: &Catalog,
+    table_name: &str,
+) -> DBResult<Option<Box<dyn Table>>> {
+    let table = match table_name.split_once('.') {
+        Some((INFORMATION_SCHEMA, "tables")) => tables(catalog)?,
+        Some((INFORMATION_SCHEMA, "columns")) => columns(catalog)?,
+        _ => return Ok(None),
+    };
+    Ok(Some(Box::new(table)))
+}
+
+fn tables(catalog: &Catalog) -> DBResult<InformationSchemaTable> {
+    let schema = RelationSchema::new(vec![
+        FieldInfo::new("table_schema".to_owned(), DataType::String),
+        FieldInfo::new("table_name".to_owned(), DataType::String),
+        FieldInfo::new("table_type".to_owned(), DataType::String),
+    ]);
+    let data = catalog
+        .list_tables()?
+        .into_iter()
+        .map(|(schema, name, table_type)| {
+            Row::new(vec![
+                Literal::String(schema),
+                Literal::String(name),
+                Literal::String(table_type.to_string()),
+            ])
+        })
+        .collect();
+    Ok(InformationSchemaTable::new(schema, data))
+}
+
+fn columns(catalog: &Catalog) -> DBResult<InformationSchemaTable> {
+    let schema = RelationSchema::new(vec![
+        FieldInfo::new("table_schema".to_owned(), DataType::String),
+        FieldInfo::new("table_name".to_owned(), DataType::String),
+        FieldInfo::new("column_name".to_owned(), DataType::String),
+        FieldInfo::new("ordinal_position".to_owned(), DataType::Int64),
+        FieldInfo::new("data_type".to_owned(), DataType::String),
+        FieldInfo::new("is_nullable".to_owned(), DataType::String),
+    ]);
+    let mut data = Vec::new();
+    for (table_schema, table_name, _) in catalog.list_tables()? {
+        let meta = catalog.get_relation_meta(&format!("{table_schema}.{table_name}"))?;
+        for (idx, field) in meta.get_schema().get_fields().iter().enumerate() {
+            let not_null = meta.get_constraints().iter().any(|c| {
+                matches!(c, TableConstraint::NotNull { column, .. } if column == field.name())
+            });
+            let is_nullable = if not_null { "NO" } else { "YES" };
+            data.push(Row::new(vec![
+                Literal::String(table_schema.clone()),
+                Literal::String(table_name.clone()),
+                Literal::String(field.name().to_owned()),
+                Literal::Int64(idx as i64 + 1),
+                Literal::String(field.data_type().to_string()),
+                Literal::String(is_nullable.to_owned()),
+            ]));
+        }
+    }
+    Ok(InformationSchemaTable::new(schema, data))
+}
+
+impl InformationSchemaTable {
+    fn new(schema: RelationSchema, data: Vec<Row<'static>>) -> Self {
+        Self {
+            meta: TableMeta::new(schema),
+            data,
+        }
+    }
+}
+
+impl Table for InformationSchemaTable {
+    fn insert_data(&mut self, _data: Vec<Row<'static>>) -> DBResult<()> {
+        Err(DBError::StorageEngine(
+            "insert is not supported by information_schema tables.".to_owned(),
+        ))
+    }
+
+    fn get_table_meta(&self) -> TableMeta {
+        self.meta.clone()
+    }
+
+    fn create_scan_op(&self) -> Box<dyn PhysicalPlan> {
+        Box::new(InMemTableScan::new(
+            self.data.clone(),
+            self.meta.get_schema().clone(),
+        ))
+    }
+}
@@ -1,27 +1,83 @@
 use crate::{
+    expressions::{Expression, Literal},
+    interpreter::Interpreter,
     physical_plans::{InMemTableScan, PhysicalPlan},
     row::Row,
+    DBResult,
 };
 
-use super::{Table, TableMeta};
+use super::{
+    constraints::{RowChecker, UniqueIndex},
+    FieldInfo, Table, TableMeta,
+};
 
 pub struct InMemTable {
     meta: TableMeta,
     data: Vec<Row<'static>>,
+    /// indexes of UNIQUE and PRIMARY KEY constraints
+    unique_indexes: Vec<UniqueIndex>,
 }
 
 impl InMemTable {
     pub fn new(meta: TableMeta) -> Self {
+        // constraints of a valid meta only reference existing columns, and there are
+        // no rows to violate them
+        let unique_indexes = UniqueIndex::build_all(&meta, &[]).unwrap_or_default();
         InMemTable {
             meta,
             data: Vec::new(),
+            unique_indexes,
+        }
+    }
+
+    /// replace the meta of the table, and rebuild unique indexes for it
+    fn set_meta(&mut self, meta: TableMeta) -> DBResult<()> {
+        self.unique_indexes = UniqueIndex::build_all(&meta, &self.data)?;
+        self.meta = meta;
+        Ok(())
+    }
+
+    /// rebuild every row from its fields
+    fn map_rows(&mut self, f: impl Fn(&mut Vec<Literal>)) -> DBResult<()> {
+        for row in self.data.iter_mut() {
+            let mut fields = (0..row.num_fields())
+                .map(|idx| row.get_field(idx))
+                .collect::<DBResult<Vec<_>>>()?;
+            f(&mut fields);
+            *row = Row::new(fields);
+        }
+        Ok(())
+    }
+
+    /// whether the row satisfies the predicate, null is treated as false
+    fn matches(predicate: Option<&Expression>, row: &Row) -> DBResult<bool> {
+        match predicate {
+            Some(predicate) => match Interpreter::eval(predicate, row)? {
+                Literal::Null => Ok(false),
+                value => value.as_bool(),
+            },
+            None => Ok(true),
         }
     }
 }
 
 impl Table for InMemTable {
-    fn insert_data(&mut self, data: Vec<Row<'static>>) {
-        self.data.extend(data)
+    fn insert_data(&mut self, data: Vec<Row<'static>>) -> DBResult<()> {
+        let checker = RowChecker::new(&self.meta)?;
+        for row in data.iter() {
+            checker.check(row)?;
+        }
+        let new_keys = self
+            .unique_indexes
+            .iter()
+            .map(|index| index.new_keys(data.iter()))
+            .collect::<DBResult<Vec<_>>>()?;
+
+        for (index, keys) in self.unique_indexes.iter_mut().zip(new_keys) {
+            index.add_keys(keys);
+        }
+        self.data.extend(data);
+        Ok(())
     }
 
     fn get_table_meta(&self) -> TableMeta {
@@ -34,4 +90,113 @@ impl Table for InMemTable {
             self.meta.schema.clone(),
         ))
     }
+
+    fn add_column(&mut self, field: FieldInfo, default: Option<Literal>) -> DBResult<()> {
+        let meta = self.meta.with_new_column(field, default.clone())?;
+        let value = default.unwrap_or(Literal::Null);
+        self.map_rows(|fields| fields.push(value.clone()))?;
+        self.set_meta(meta)
+    }
+
+    fn drop_column(&mut self, idx: usize) -> DBResult<()> {
+        let meta = self.meta.without_column(idx)?;
+        self.map_rows(|fields| {
+            fields.remove(idx);
+        })?;
+        self.set_meta(meta)
+    }
+
+    fn rename_column(&mut self, idx: usize, name: String) -> DBResult<()> {
+        let meta = self.meta.with_renamed_column(idx, name)?;
+        self.set_meta(meta)
+    }
+
+    fn update(
+        &mut self,
+        predicate: Option<&Expression>,
+        assignments: &[(usize, Expression)],
+    ) -> DBResult<usize> {
+        // evaluate all rows before updating any of them, so that a failed update leaves
+        // the table untouched
+        // and constraints are checked against the updated table
+        let checker = RowChecker::new(&self.meta)?;
+        let mut updates = Vec::new();
+        for (row_idx, row) in self.data.iter().enumerate() {
+            if Self::matches(predicate, row)? {
+                let mut updated = row.clone();
+                for (field_idx, expr) in assignments {
+                    let value = Interpreter::eval(expr, row)?;
+                    // values of narrower numeric types are widened to the field type
+                    let value = self
+                        .meta
+                        .get_schema()
+                        .get_field_at(*field_idx)
+                        .and_then(|field| value.widen(field.data_type()))
+                        .unwrap_or(value);
+                    updated.update_field(*field_idx, value)?;
+                }
+                checker.check(&updated)?;
+                updates.push((row_idx, updated));
+            }
+        }
+        let num_updated = updates.len();
+        if num_updated == 0 {
+            return Ok(0);
+        }
+
+        // keys of unique indexes are validated for all updated rows before any index or
+        // row is changed
+        let replaced_keys = self
+            .unique_indexes
+            .iter()
+            .map(|index| {
+                index.replaced_keys(
+                    updates
+                        .iter()
+                        .map(|(row_idx, updated)| (&self.data[*row_idx], updated)),
+                )
+            })
+            .collect::<DBResult<Vec<_>>>()?;
+        for (index, keys) in self.unique_indexes.iter_mut().zip(replaced_keys) {
+            index.replace_keys(keys);
+        }
+        for (row_idx, updated) in updates {
+            self.data[row_idx] = updated;
+        }
+        Ok(num_updated)
+    }
+
+    fn delete(&mut self, predicate: Option<&Expression>) -> DBResult<usize> {
+        let num_rows = self.data.len();
+        if predicate.is_none() {
+            self.data.clear();
+            for index in self.unique_indexes.iter_mut() {
+                index.clear();
+            }
+            return Ok(num_rows);
+        }
+
+        // evaluate all rows before deleting any of them, so that a failed delete leaves
+        // the table untouched
+        let to_delete = self
+            .data
+            .iter()
+            .map(|row| Self::matches(predicate, row))
+            .collect::<DBResult<Vec<_>>>()?;
+        let removed_keys = self
+            .unique_indexes
+            .iter()
+            .map(|index| {
+                let deleted_rows = Iterator::zip(self.data.iter(), to_delete.iter())
+                    .filter_map(|(row, is_deleted)| is_deleted.then_some(row));
+                index.removed_keys(deleted_rows)
+            })
+            .collect::<DBResult<Vec<_>>>()?;
+        for (index, keys) in self.unique_indexes.iter_mut().zip(removed_keys) {
+            index.remove_keys(keys);
+        }
+        let mut to_delete = to_delete.into_iter();
+        self.data.retain(|_| !to_delete.next().unwrap_or(false));
+        Ok(num_rows - self.data.len())
+    }
 }
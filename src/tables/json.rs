@@ -0,0 +1,395 @@
+use std::{
+    fmt,
+    fs::File,
+    io::{BufRead, BufWriter, Write},
+};
+
+use serde::{
+    de::{MapAccess, SeqAccess, Visitor},
+    Deserialize, Deserializer,
+};
+use serde_json::Value;
+
+use crate::{
+    data_types::DataType,
+    parser::FileOption,
+    physical_plans::{JsonScan, PhysicalPlan},
+    row::Row,
+    DBError, DBResult, RowWithSchema,
+};
+
+use super::{
+    compression::open_decompressed, create_file, FieldInfo, RelationSchema, RowWriter,
+    Table, TableMeta,
+};
+
+const NUM_RECORDS_TO_INFER_SCHEMA: usize = 100;
+
+/// Options of reading json files.
+#[derive(Debug, Clone)]
+pub struct JsonOptions {
+    /// the number of records to infer the schema from, all records are read if None
+    pub sample_size: Option<usize>,
+}
+
+impl Default for JsonOptions {
+    fn default() -> Self {
+        Self {
+            sample_size: Some(NUM_RECORDS_TO_INFER_SCHEMA),
+        }
+    }
+}
+
+impl JsonOptions {
+    /// the options set by the file options, e.g. of `read_json` or
+    /// `CREATE EXTERNAL TABLE`
+    pub(crate) fn try_from_options(options: &[FileOption]) -> DBResult<Self> {
+        let mut json_options = Self::default();
+        for option in options {
+            match option.name() {
+                "sample_size" => json_options.sample_size = option.sample_size_value()?,
+                _ => {
+                    return Err(DBError::ParserError(format!(
+                        "json option {option} is not supported"
+                    )))
+                }
+            }
+        }
+        Ok(json_options)
+    }
+}
+
+/// Note, JsonTable is readonly to a json file, which is either an array of objects or
+/// objects separated by whitespaces, e.g. newline delimited json.
+///
+/// Every object is a record, fields of nested objects are flattened into dotted names,
+/// e.g. `address.city`. Numbers are read as Int64 or Float64, arrays as lists, fields
+/// with conflicting types are read as json text, and missing fields are null.
+pub struct JsonTable {
+    schema: RelationSchema,
+    path: String,
+}
+
+impl JsonTable {
+    pub fn new(path: String) -> DBResult<Self> {
+        Self::with_options(path, JsonOptions::default())
+    }
+
+    pub fn with_options(path: String, options: JsonOptions) -> DBResult<Self> {
+        let mut reader = JsonRecordReader::new(path.as_str())?;
+        let mut fields: Vec<(String, DataType)> = vec![];
+        let mut num_records_read = 0;
+        while options
+            .sample_size
+            .is_none_or(|size| num_records_read < size)
+        {
+            let Some(record) = reader.try_read_next()? else {
+                break;
+            };
+            num_records_read += 1;
+            for (name, value) in record {
+                let data_type = data_type_of(&value);
+                match fields.iter_mut().find(|(n, _)| *n == name) {
+                    Some((_, current)) => {
+                        *current = reconcile(current.clone(), data_type)
+                    }
+                    None => fields.push((name, data_type)),
+                }
+            }
+        }
+        if num_records_read == 0 {
+            return Err(DBError::StorageEngine(
+                "Provided json file is empty.".to_owned(),
+            ));
+        }
+
+        let fields = fields
+            .into_iter()
+            .map(|(name, data_type)| FieldInfo::new(name, finalize(data_type)))
+            .collect();
+        Ok(JsonTable {
+            schema: RelationSchema::new(fields),
+            path,
+        })
+    }
+}
+
+/// the data type of a json value, Unknown for null
+fn data_type_of(value: &Value) -> DataType {
+    match value {
+        Value::Null => DataType::Unknown,
+        Value::Bool(_) => DataType::Boolean,
+        Value::Number(n) if n.is_i64() => DataType::Int64,
+        Value::Number(_) => DataType::Float64,
+        Value::String(_) => DataType::String,
+        Value::Array(values) => DataType::List(Box::new(
+            values
+                .iter()
+                .map(data_type_of)
+                .fold(DataType::Unknown, reconcile),
+        )),
+        // objects in arrays are kept as json text
+        Value::Object(_) => DataType::String,
+    }
+}
+
+/// the data type of values of both data types, json text is the fallback
+fn reconcile(left: DataType, right: DataType) -> DataType {
+    match (left, right) {
+        (left, right) if left == right => left,
+        (DataType::Unknown, data_type) | (data_type, DataType::Unknown) => data_type,
+        (DataType::Int64, DataType::Float64) | (DataType::Float64, DataType::Int64) => {
+            DataType::Float64
+        }
+        (DataType::List(left), DataType::List(right)) => {
+            DataType::List(Box::new(reconcile(*left, *right)))
+        }
+        _ => DataType::String,
+    }
+}
+
+/// fields of only null values are read as strings
+fn finalize(data_type: DataType) -> DataType {
+    match data_type {
+        DataType::Unknown => DataType::String,
+        DataType::List(inner) => DataType::List(Box::new(finalize(*inner))),
+        data_type => data_type,
+    }
+}
+
+/// JsonValue is a json value which keeps the order of fields of objects.
+enum JsonValue {
+    Object(Vec<(String, JsonValue)>),
+    Value(Value),
+}
+
+impl JsonValue {
+    /// flatten fields of nested objects into dotted names
+    fn flatten(
+        prefix: Option<&str>,
+        fields: Vec<(String, JsonValue)>,
+    ) -> Vec<(String, Value)> {
+        let mut flattened = vec![];
+        for (key, value) in fields {
+            let name = match prefix {
+                Some(prefix) => format!("{prefix}.{key}"),
+                None => key,
+            };
+            match value {
+                JsonValue::Object(fields) => {
+                    flattened.extend(Self::flatten(Some(&name), fields))
+                }
+                JsonValue::Value(value) => flattened.push((name, value)),
+            }
+        }
+        flattened
+    }
+}
+
+impl<'de> Deserialize<'de> for JsonValue {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(JsonValueVisitor)
+    }
+}
+
+struct JsonValueVisitor;
+
+impl<'de> Visitor<'de> for JsonValueVisitor {
+    type Value = JsonValue;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("any valid json value")
+    }
+
+    fn visit_bool<E>(self, v: bool) -> Result<JsonValue, E> {
+        Ok(JsonValue::Value(Value::Bool(v)))
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<JsonValue, E> {
+        Ok(JsonValue::Value(Value::from(v)))
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<JsonValue, E> {
+        Ok(JsonValue::Value(Value::from(v)))
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<JsonValue, E> {
+        Ok(JsonValue::Value(Value::from(v)))
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<JsonValue, E> {
+        Ok(JsonValue::Value(Value::String(v.to_owned())))
+    }
+
+    fn visit_string<E>(self, v: String) -> Result<JsonValue, E> {
+        Ok(JsonValue::Value(Value::String(v)))
+    }
+
+    fn visit_unit<E>(self) -> Result<JsonValue, E> {
+        Ok(JsonValue::Value(Value::Null))
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<JsonValue, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut values = vec![];
+        while let Some(value) = seq.next_element::<Value>()? {
+            values.push(value);
+        }
+        Ok(JsonValue::Value(Value::Array(values)))
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<JsonValue, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut fields = vec![];
+        while let Some((key, value)) = map.next_entry::<String, JsonValue>()? {
+            fields.push((key, value));
+        }
+        Ok(JsonValue::Object(fields))
+    }
+}
+
+/// JsonRecordReader reads records of a json file one by one, without loading the whole
+/// file.
+pub struct JsonRecordReader {
+    buf_reader: Box<dyn BufRead>,
+    /// whether records are elements of a top level array
+    in_array: bool,
+    num_records: usize,
+}
+
+impl JsonRecordReader {
+    pub fn new(path: &str) -> DBResult<Self> {
+        let buf_reader = open_decompressed(path)
+            .map_err(|e| DBError::StorageEngine(format!("read json file failed: {e}")))?;
+        let mut reader = Self {
+            buf_reader,
+            in_array: false,
+            num_records: 0,
+        };
+        if reader.peek_byte()? == Some(b'[') {
+            reader.buf_reader.consume(1);
+            reader.in_array = true;
+        }
+        Ok(reader)
+    }
+
+    /// the number of records read so far
+    pub fn num_records(&self) -> usize {
+        self.num_records
+    }
+
+    /// read the next record, fields of nested objects are flattened into dotted names
+    pub fn try_read_next(&mut self) -> DBResult<Option<Vec<(String, Value)>>> {
+        if self.in_array {
+            match self.peek_byte()? {
+                Some(b']') => {
+                    self.buf_reader.consume(1);
+                    return match self.peek_byte()? {
+                        None => Ok(None),
+                        Some(_) => Err(self.error("unexpected content after json array")),
+                    };
+                }
+                Some(b',') if self.num_records > 0 => self.buf_reader.consume(1),
+                Some(_) if self.num_records == 0 => {}
+                Some(_) => return Err(self.error("expected , or ] after record")),
+                None => return Err(self.error("unexpected end of json array")),
+            }
+        }
+        if self.peek_byte()?.is_none() {
+            return match self.in_array {
+                true => Err(self.error("unexpected end of json array")),
+                false => Ok(None),
+            };
+        }
+
+        self.num_records += 1;
+        let mut deserializer =
+            serde_json::Deserializer::from_reader(&mut self.buf_reader);
+        match JsonValue::deserialize(&mut deserializer) {
+            Ok(JsonValue::Object(fields)) => Ok(Some(JsonValue::flatten(None, fields))),
+            Ok(JsonValue::Value(_)) => Err(self.error("json record is not an object")),
+            Err(e) => Err(self.error(&format!("invalid json: {e}"))),
+        }
+    }
+
+    /// skip whitespaces and return the next byte without consuming it
+    fn peek_byte(&mut self) -> DBResult<Option<u8>> {
+        loop {
+            let buf = self.buf_reader.fill_buf().map_err(|e| {
+                DBError::StorageEngine(format!("read json file error: {e}"))
+            })?;
+            if buf.is_empty() {
+                return Ok(None);
+            }
+            match buf.iter().position(|b| !b.is_ascii_whitespace()) {
+                Some(pos) => {
+                    let byte = buf[pos];
+                    self.buf_reader.consume(pos);
+                    return Ok(Some(byte));
+                }
+                None => {
+                    let len = buf.len();
+                    self.buf_reader.consume(len);
+                }
+            }
+        }
+    }
+
+    fn error(&self, msg: &str) -> DBError {
+        DBError::StorageEngine(format!("json record {}: {msg}", self.num_records))
+    }
+}
+
+impl Table for JsonTable {
+    fn insert_data(&mut self, _data: Vec<Row<'static>>) -> DBResult<()> {
+        Err(DBError::StorageEngine(
+            "insert is not supported by json tables.".to_owned(),
+        ))
+    }
+
+    fn get_table_meta(&self) -> TableMeta {
+        TableMeta::new(self.schema.clone())
+    }
+
+    fn create_scan_op(&self) -> Box<dyn PhysicalPlan> {
+        Box::new(JsonScan::new(self.path.clone(), self.schema.clone()))
+    }
+}
+
+/// NdjsonWriter writes rows as json objects line by line, i.e. newline delimited json.
+pub(crate) struct NdjsonWriter {
+    writer: BufWriter<File>,
+    schema: RelationSchema,
+}
+
+impl NdjsonWriter {
+    pub(crate) fn try_new(path: &str, schema: RelationSchema) -> DBResult<Self> {
+        Ok(Self {
+            writer: BufWriter::new(create_file(path)?),
+            schema,
+        })
+    }
+}
+
+impl RowWriter for NdjsonWriter {
+    fn write_row(&mut self, row: &Row<'static>) -> DBResult<()> {
+        serde_json::to_writer(&mut self.writer, &RowWithSchema::new(&self.schema, row))
+            .map_err(|e| DBError::StorageEngine(format!("write json file error: {e}")))?;
+        self.writer
+            .write_all(b"\n")
+            .map_err(|e| DBError::StorageEngine(format!("write json file error: {e}")))
+    }
+
+    fn finish(mut self: Box<Self>) -> DBResult<()> {
+        self.writer
+            .flush()
+            .map_err(|e| DBError::StorageEngine(format!("write json file error: {e}")))
+    }
+}
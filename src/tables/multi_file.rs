@@ -0,0 +1,366 @@
+use std::{fs, path::Path};
+
+use crate::{
+    data_types::DataType,
+    expressions::{BinaryOp, Expression, Literal},
+    interpreter::Interpreter,
+    physical_plans::{MultiFileScan, PhysicalPlan},
+    row::Row,
+    DBError, DBResult,
+};
+
+use super::{
+    csv::{parse_csv_field, CsvTable},
+    FieldInfo, RelationSchema, ScanOptions, Table, TableMeta,
+};
+
+/// the directory name Hive uses for null partition values
+const HIVE_NULL_PARTITION: &str = "__HIVE_DEFAULT_PARTITION__";
+
+/// Note, MultiFileTable is readonly to the files matching a glob pattern, e.g.
+/// `logs/2026-*/part-*.csv`, whose rows are the union of the rows of the files.
+///
+/// Directories of Hive-style `key=value` names in the paths of the files are partitions,
+/// which are virtual columns following the columns of the files, e.g. `date` and
+/// `region` of `events/date=2026-10-01/region=eu/part-0.csv`. Files are skipped by
+/// scans if filters on partition columns show that none of their rows is needed.
+pub struct MultiFileTable {
+    pattern: String,
+    schema: RelationSchema,
+    /// the number of fields of the files, which are followed by the partition fields
+    num_file_fields: usize,
+    files: Vec<PartitionedFile>,
+}
+
+/// a file of a multi file table, and the values of the partitions of its path
+struct PartitionedFile {
+    table: Box<dyn Table>,
+    partition_values: Vec<Literal>,
+}
+
+impl MultiFileTable {
+    /// the table of the files matching the pattern. Tables of the files are created by
+    /// the given function, with the schema of the first file for the other files, which
+    /// must be the schema of their tables.
+    pub fn try_new(
+        pattern: &str,
+        create_table: impl Fn(&str, Option<&RelationSchema>) -> DBResult<Box<dyn Table>>,
+    ) -> DBResult<Self> {
+        let paths = expand_glob(pattern)?;
+        let Some(first_path) = paths.first() else {
+            return Err(DBError::StorageEngine(format!(
+                "no files match the pattern {pattern}"
+            )));
+        };
+
+        let partition_names = partitions_of(first_path)
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect::<Vec<_>>();
+        let mut partition_values = Vec::with_capacity(paths.len());
+        for path in paths.iter() {
+            let partitions = partitions_of(path);
+            if !Iterator::eq(
+                partitions.iter().map(|(name, _)| name),
+                partition_names.iter(),
+            ) {
+                return Err(DBError::StorageEngine(format!(
+                    "partitions of {path} are not the partitions of {first_path}"
+                )));
+            }
+            partition_values.push(partitions.into_iter().map(|(_, value)| value));
+        }
+        let partition_values = partition_values
+            .into_iter()
+            .map(|values| values.collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let partition_types = (0..partition_names.len())
+            .map(|idx| {
+                let data_type = partition_values
+                    .iter()
+                    .filter_map(|values| values[idx].as_deref())
+                    .fold(DataType::Unknown, |hint, value| {
+                        CsvTable::determine_data_type(value, &hint, false)
+                    });
+                match data_type {
+                    DataType::Unknown => DataType::String,
+                    data_type => data_type,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let first_table = create_table(first_path, None)?;
+        let file_schema = first_table.get_table_meta().get_schema().clone();
+        let mut tables = vec![first_table];
+        for path in paths.iter().skip(1) {
+            let table = create_table(path, Some(&file_schema))?;
+            if table.get_table_meta().get_schema() != &file_schema {
+                return Err(DBError::StorageEngine(format!(
+                    "the schema of {path} is not the schema of {first_path}"
+                )));
+            }
+            tables.push(table);
+        }
+
+        let mut fields = file_schema.get_fields().clone();
+        for (name, data_type) in
+            Iterator::zip(partition_names.iter(), partition_types.iter())
+        {
+            if fields.iter().any(|field| field.name() == name) {
+                return Err(DBError::StorageEngine(format!(
+                    "partition {name} is also a column of {first_path}"
+                )));
+            }
+            fields.push(FieldInfo::new(name.clone(), data_type.clone()));
+        }
+        let files = Iterator::zip(tables.into_iter(), partition_values)
+            .map(|(table, values)| {
+                let partition_values =
+                    Iterator::zip(values.iter(), partition_types.iter())
+                        .map(|(value, data_type)| match value {
+                            Some(value) => parse_csv_field(value, data_type)
+                                .ok_or_else(DBError::should_never_happen),
+                            None => Ok(Literal::Null),
+                        })
+                        .collect::<DBResult<Vec<_>>>()?;
+                Ok(PartitionedFile {
+                    table,
+                    partition_values,
+                })
+            })
+            .collect::<DBResult<Vec<_>>>()?;
+        Ok(MultiFileTable {
+            pattern: pattern.to_owned(),
+            schema: RelationSchema::new(fields),
+            num_file_fields: file_schema.num_fields(),
+            files,
+        })
+    }
+
+    /// whether the partition values of a file satisfy all filters on partitions
+    fn may_match(&self, file: &PartitionedFile, filters: &[&Expression]) -> bool {
+        let mut cells = vec![Literal::Null; self.num_file_fields];
+        cells.extend(file.partition_values.iter().cloned());
+        let row = Row::new(cells);
+        filters
+            .iter()
+            .all(|filter| match Interpreter::eval(filter, &row) {
+                Ok(Literal::Bool(false)) | Ok(Literal::Null) => false,
+                // comparisons of null values fail rather than being null, so no row of
+                // files of null partitions read by the filter matches it. Files are not
+                // skipped if the filter cannot be evaluated otherwise
+                Err(_) => !self.reads_null_partition(file, filter),
+                Ok(_) => true,
+            })
+    }
+
+    /// whether the filter reads a partition whose value of the file is null
+    fn reads_null_partition(&self, file: &PartitionedFile, filter: &Expression) -> bool {
+        filter.referenced_fields().is_some_and(|fields| {
+            fields.iter().any(|idx| {
+                idx.checked_sub(self.num_file_fields)
+                    .and_then(|idx| file.partition_values.get(idx))
+                    == Some(&Literal::Null)
+            })
+        })
+    }
+}
+
+impl Table for MultiFileTable {
+    fn insert_data(&mut self, _data: Vec<Row<'static>>) -> DBResult<()> {
+        Err(DBError::StorageEngine(
+            "insert is not supported by tables of multiple files.".to_owned(),
+        ))
+    }
+
+    fn get_table_meta(&self) -> TableMeta {
+        TableMeta::new(self.schema.clone())
+    }
+
+    fn create_scan_op(&self) -> Box<dyn PhysicalPlan> {
+        self.create_scan_op_with(&ScanOptions::default())
+    }
+
+    fn create_scan_op_with(&self, options: &ScanOptions) -> Box<dyn PhysicalPlan> {
+        let is_file_field = |idx: &usize| *idx < self.num_file_fields;
+        // filters only on partitions skip files, and filters only on the columns of
+        // files are passed to the scans of files
+        let mut partition_filters = vec![];
+        let mut file_filters = vec![];
+        for filter in options.filters.iter().flat_map(conjuncts) {
+            match filter.referenced_fields() {
+                Some(fields) if fields.iter().all(is_file_field) => {
+                    file_filters.push(filter.clone())
+                }
+                Some(fields) if !fields.iter().any(is_file_field) => {
+                    partition_filters.push(filter)
+                }
+                _ => {}
+            }
+        }
+        let file_options = ScanOptions {
+            projection: options
+                .projection
+                .as_ref()
+                .map(|fields| fields.iter().copied().filter(is_file_field).collect()),
+            filters: file_filters,
+        };
+        let scans = self
+            .files
+            .iter()
+            .filter(|file| self.may_match(file, &partition_filters))
+            .map(|file| {
+                (
+                    file.table.create_scan_op_with(&file_options),
+                    file.partition_values.clone(),
+                )
+            })
+            .collect();
+        Box::new(MultiFileScan::new(
+            self.pattern.clone(),
+            self.schema.clone(),
+            scans,
+            self.files.len(),
+        ))
+    }
+}
+
+/// the expressions of a filter which are all true if the filter is true, e.g. `a = 1`
+/// and `b > 2` of `a = 1 and b > 2`
+fn conjuncts(filter: &Expression) -> Vec<&Expression> {
+    match filter {
+        Expression::BinaryOp {
+            op: BinaryOp::And,
+            left,
+            right,
+        } => {
+            let mut expressions = conjuncts(left);
+            expressions.extend(conjuncts(right));
+            expressions
+        }
+        _ => vec![filter],
+    }
+}
+
+/// whether the path is a glob pattern of files, e.g. `logs/*.csv`
+pub(crate) fn is_glob(path: &str) -> bool {
+    path.contains(['*', '?'])
+}
+
+/// the paths of files matching the glob pattern in order. `*` matches any characters
+/// but `/` and `?` matches one of them, while a `**` directory matches any number of
+/// directories.
+fn expand_glob(pattern: &str) -> DBResult<Vec<String>> {
+    let segments = pattern.split('/').collect::<Vec<_>>();
+    let num_literals = segments
+        .iter()
+        .take(segments.len() - 1)
+        .take_while(|segment| !is_glob(segment))
+        .count();
+    let base = segments[..num_literals].join("/");
+    let mut paths = vec![];
+    let dir = match base.as_str() {
+        "" if pattern.starts_with('/') => "/",
+        "" => ".",
+        base => base,
+    };
+    expand_segments(Path::new(dir), &base, &segments[num_literals..], &mut paths)?;
+    paths.sort();
+    paths.dedup();
+    Ok(paths)
+}
+
+/// add the paths under the directory matching the segments of a pattern, the paths
+/// start with the prefix
+fn expand_segments(
+    dir: &Path,
+    prefix: &str,
+    segments: &[&str],
+    paths: &mut Vec<String>,
+) -> DBResult<()> {
+    let Some((segment, rest)) = segments.split_first() else {
+        return Ok(());
+    };
+    if *segment == "**" {
+        // `**` matches no directory, or any directory followed by `**` again
+        expand_segments(dir, prefix, rest, paths)?;
+    }
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return Ok(()),
+    };
+    for entry in entries {
+        let entry = entry.map_err(|e| {
+            DBError::StorageEngine(format!(
+                "read directory {} failed: {e}",
+                dir.display()
+            ))
+        })?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        let path = match prefix {
+            "" => name.clone(),
+            "/" => format!("/{name}"),
+            prefix => format!("{prefix}/{name}"),
+        };
+        let is_dir = entry.path().is_dir();
+        if *segment == "**" {
+            if is_dir && !name.starts_with('.') {
+                expand_segments(&entry.path(), &path, segments, paths)?;
+            }
+        } else if matches_wildcard(segment, &name)
+            && (name.starts_with('.') == segment.starts_with('.'))
+        {
+            match (rest.is_empty(), is_dir) {
+                (true, false) => paths.push(path),
+                (false, true) => expand_segments(&entry.path(), &path, rest, paths)?,
+                _ => {}
+            }
+        }
+    }
+    Ok(())
+}
+
+/// whether the name matches the pattern of `*` and `?` wildcards
+fn matches_wildcard(pattern: &str, name: &str) -> bool {
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let name = name.chars().collect::<Vec<_>>();
+    let (mut p, mut n) = (0, 0);
+    // the position of the last `*` and the position in the name it matches up to
+    let mut backtrack = None;
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, n));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    p = star + 1;
+                    n = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// the `key=value` directories of a path in order, values of null partitions are None
+fn partitions_of(path: &str) -> Vec<(String, Option<String>)> {
+    let mut directories = path.split('/').collect::<Vec<_>>();
+    directories.pop();
+    directories
+        .into_iter()
+        .filter_map(|directory| directory.split_once('='))
+        .filter(|(key, _)| !key.is_empty())
+        .map(|(key, value)| {
+            let value = (value != HIVE_NULL_PARTITION).then(|| value.to_owned());
+            (key.to_owned(), value)
+        })
+        .collect()
+}
@@ -0,0 +1,342 @@
+use std::{cmp::Ordering, fs::File, sync::Arc};
+
+use arrow::datatypes::{DataType as ArrowDataType, Schema};
+use parquet::{
+    arrow::{arrow_reader::ParquetRecordBatchReaderBuilder, ArrowWriter},
+    file::{metadata::RowGroupMetaData, statistics::Statistics},
+    schema::types::SchemaDescriptor,
+};
+
+use crate::{
+    expressions::{BinaryOp, Expression, Literal},
+    physical_plans::{ParquetScan, PhysicalPlan},
+    row::Row,
+    DBError, DBResult,
+};
+
+use super::{
+    arrow_types::{arrow_schema_of, data_type_from_arrow, record_batch_of},
+    create_file, FieldInfo, RelationSchema, RowWriter, ScanOptions, Table, TableMeta,
+};
+
+/// the number of rows converted to a record batch at a time when writing
+const NUM_ROWS_PER_BATCH: usize = 1024;
+
+/// Note, ParquetTable is readonly to a parquet file.
+///
+/// Columns are read by their arrow types, timestamps and dates are read as DateTime,
+/// and decimals as Float64. Scans only read the columns a query needs, and skip row
+/// groups whose statistics show that no row can satisfy the filters of the query.
+pub struct ParquetTable {
+    schema: RelationSchema,
+    path: String,
+}
+
+impl ParquetTable {
+    pub fn new(path: String) -> DBResult<Self> {
+        let builder = open_parquet(&path)?;
+        let fields = builder
+            .schema()
+            .fields()
+            .iter()
+            .map(|field| {
+                data_type_from_arrow(field)
+                    .map(|data_type| FieldInfo::new(field.name().clone(), data_type))
+            })
+            .collect::<DBResult<Vec<_>>>()?;
+        Ok(ParquetTable {
+            schema: RelationSchema::new(fields),
+            path,
+        })
+    }
+}
+
+/// open a parquet file to read its metadata, and then its record batches
+pub(crate) fn open_parquet(
+    path: &str,
+) -> DBResult<ParquetRecordBatchReaderBuilder<File>> {
+    let f = File::open(path)
+        .map_err(|e| DBError::StorageEngine(format!("read parquet file failed: {e}")))?;
+    ParquetRecordBatchReaderBuilder::try_new(f)
+        .map_err(|e| DBError::StorageEngine(format!("read parquet file failed: {e}")))
+}
+
+/// the indexes of row groups which may have rows satisfying all the filters
+pub(crate) fn prune_row_groups(
+    row_groups: &[RowGroupMetaData],
+    parquet_schema: &SchemaDescriptor,
+    arrow_schema: &Schema,
+    filters: &[Expression],
+) -> Vec<usize> {
+    // statistics are only used for columns of primitive values, whose physical values
+    // compare like the values read
+    let leaves = arrow_schema
+        .fields()
+        .iter()
+        .enumerate()
+        .map(|(root, field)| {
+            let comparable = matches!(
+                field.data_type(),
+                ArrowDataType::Int8
+                    | ArrowDataType::Int16
+                    | ArrowDataType::Int32
+                    | ArrowDataType::Int64
+                    | ArrowDataType::UInt8
+                    | ArrowDataType::UInt16
+                    | ArrowDataType::UInt32
+                    | ArrowDataType::UInt64
+                    | ArrowDataType::Float32
+                    | ArrowDataType::Float64
+                    | ArrowDataType::Utf8
+                    | ArrowDataType::LargeUtf8
+            );
+            let leaf = (0..parquet_schema.num_columns())
+                .find(|&leaf| parquet_schema.get_column_root_idx(leaf) == root)
+                .filter(|_| {
+                    parquet_schema.root_schema().get_fields()[root].is_primitive()
+                });
+            leaf.filter(|_| comparable)
+                .map(|leaf| (leaf, field.data_type().clone()))
+        })
+        .collect::<Vec<_>>();
+
+    (0..row_groups.len())
+        .filter(|&idx| {
+            let row_group = &row_groups[idx];
+            let range_of = |field_idx: usize| {
+                let (leaf, data_type) = leaves.get(field_idx)?.as_ref()?;
+                let statistics = row_group.column(*leaf).statistics()?;
+                ValueRange::new(statistics, data_type, row_group.num_rows())
+            };
+            filters.iter().all(|filter| may_match(filter, &range_of))
+        })
+        .collect()
+}
+
+/// whether any row whose fields are in the ranges may satisfy the predicate
+fn may_match(
+    predicate: &Expression,
+    range_of: &impl Fn(usize) -> Option<ValueRange>,
+) -> bool {
+    let Expression::BinaryOp { op, left, right } = predicate else {
+        return true;
+    };
+    match op {
+        BinaryOp::And => may_match(left, range_of) && may_match(right, range_of),
+        BinaryOp::Or => may_match(left, range_of) || may_match(right, range_of),
+        BinaryOp::Eq | BinaryOp::Gt | BinaryOp::Gte | BinaryOp::Lt | BinaryOp::Lte => {
+            let (index, op, literal) = match (left.as_ref(), right.as_ref()) {
+                (Expression::FieldRef { index, .. }, Expression::Literal(literal)) => {
+                    (*index, op.clone(), literal)
+                }
+                (Expression::Literal(literal), Expression::FieldRef { index, .. }) => {
+                    (*index, flip(op), literal)
+                }
+                _ => return true,
+            };
+            let (Some(range), Some(value)) = (range_of(index), StatValue::from(literal))
+            else {
+                return true;
+            };
+            range.may_satisfy(&op, &value)
+        }
+        _ => true,
+    }
+}
+
+/// the operator with its operands swapped, e.g. `1 < a` is `a > 1`
+fn flip(op: &BinaryOp) -> BinaryOp {
+    match op {
+        BinaryOp::Gt => BinaryOp::Lt,
+        BinaryOp::Gte => BinaryOp::Lte,
+        BinaryOp::Lt => BinaryOp::Gt,
+        BinaryOp::Lte => BinaryOp::Gte,
+        op => op.clone(),
+    }
+}
+
+/// StatValue is a min or max value of statistics, or a literal compared with them.
+#[derive(Debug, PartialEq)]
+enum StatValue {
+    Int(i128),
+    Float(f64),
+    String(Vec<u8>),
+}
+
+impl StatValue {
+    fn from(literal: &Literal) -> Option<Self> {
+        match literal {
+            Literal::UInt8(v) => Some(StatValue::Int(*v as i128)),
+            Literal::UInt16(v) => Some(StatValue::Int(*v as i128)),
+            Literal::UInt32(v) => Some(StatValue::Int(*v as i128)),
+            Literal::UInt64(v) => Some(StatValue::Int(*v as i128)),
+            Literal::Int8(v) => Some(StatValue::Int(*v as i128)),
+            Literal::Int16(v) => Some(StatValue::Int(*v as i128)),
+            Literal::Int32(v) => Some(StatValue::Int(*v as i128)),
+            Literal::Int64(v) => Some(StatValue::Int(*v as i128)),
+            Literal::Float32(v) => Some(StatValue::Float(*v as f64)),
+            Literal::Float64(v) => Some(StatValue::Float(*v)),
+            Literal::String(v) => Some(StatValue::String(v.as_bytes().to_vec())),
+            _ => None,
+        }
+    }
+}
+
+impl PartialOrd for StatValue {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (StatValue::Int(l), StatValue::Int(r)) => l.partial_cmp(r),
+            (StatValue::Int(l), StatValue::Float(r)) => (*l as f64).partial_cmp(r),
+            (StatValue::Float(l), StatValue::Int(r)) => l.partial_cmp(&(*r as f64)),
+            (StatValue::Float(l), StatValue::Float(r)) => l.partial_cmp(r),
+            (StatValue::String(l), StatValue::String(r)) => l.partial_cmp(r),
+            _ => None,
+        }
+    }
+}
+
+/// ValueRange is the range of the values of a column in a row group.
+struct ValueRange {
+    /// None if the row group has no values but nulls
+    min_max: Option<(StatValue, StatValue)>,
+}
+
+impl ValueRange {
+    /// the range by the statistics, None if the statistics do not tell the range
+    fn new(
+        statistics: &Statistics,
+        data_type: &ArrowDataType,
+        num_rows: i64,
+    ) -> Option<Self> {
+        if statistics.null_count_opt() == Some(num_rows as u64) {
+            return Some(ValueRange { min_max: None });
+        }
+        let min_max = match statistics {
+            Statistics::Int32(s) => {
+                let to_value = |v: &i32| match data_type {
+                    ArrowDataType::UInt32 => StatValue::Int(*v as u32 as i128),
+                    _ => StatValue::Int(*v as i128),
+                };
+                (to_value(s.min_opt()?), to_value(s.max_opt()?))
+            }
+            Statistics::Int64(s) => {
+                let to_value = |v: &i64| match data_type {
+                    ArrowDataType::UInt64 => StatValue::Int(*v as u64 as i128),
+                    _ => StatValue::Int(*v as i128),
+                };
+                (to_value(s.min_opt()?), to_value(s.max_opt()?))
+            }
+            Statistics::Float(s) => (
+                StatValue::Float(*s.min_opt()? as f64),
+                StatValue::Float(*s.max_opt()? as f64),
+            ),
+            Statistics::Double(s) => (
+                StatValue::Float(*s.min_opt()?),
+                StatValue::Float(*s.max_opt()?),
+            ),
+            Statistics::ByteArray(s) => (
+                StatValue::String(s.min_opt()?.data().to_vec()),
+                StatValue::String(s.max_opt()?.data().to_vec()),
+            ),
+            _ => return None,
+        };
+        Some(ValueRange {
+            min_max: Some(min_max),
+        })
+    }
+
+    /// whether any value in the range may satisfy `value op literal`
+    fn may_satisfy(&self, op: &BinaryOp, literal: &StatValue) -> bool {
+        // comparisons with nulls are never true
+        let Some((min, max)) = &self.min_max else {
+            return false;
+        };
+        // values which are not comparable, e.g. NaN, cannot rule out any row
+        let (Some(min_cmp), Some(max_cmp)) =
+            (min.partial_cmp(literal), max.partial_cmp(literal))
+        else {
+            return true;
+        };
+        match op {
+            BinaryOp::Eq => min_cmp.is_le() && max_cmp.is_ge(),
+            BinaryOp::Gt => max_cmp.is_gt(),
+            BinaryOp::Gte => max_cmp.is_ge(),
+            BinaryOp::Lt => min_cmp.is_lt(),
+            BinaryOp::Lte => min_cmp.is_le(),
+            _ => true,
+        }
+    }
+}
+
+impl Table for ParquetTable {
+    fn insert_data(&mut self, _data: Vec<Row<'static>>) -> DBResult<()> {
+        Err(DBError::StorageEngine(
+            "insert is not supported by parquet tables.".to_owned(),
+        ))
+    }
+
+    fn get_table_meta(&self) -> TableMeta {
+        TableMeta::new(self.schema.clone())
+    }
+
+    fn create_scan_op(&self) -> Box<dyn PhysicalPlan> {
+        self.create_scan_op_with(&ScanOptions::default())
+    }
+
+    fn create_scan_op_with(&self, options: &ScanOptions) -> Box<dyn PhysicalPlan> {
+        Box::new(ParquetScan::new(
+            self.path.clone(),
+            self.schema.clone(),
+            options.clone(),
+        ))
+    }
+}
+
+/// ParquetWriter writes rows to a parquet file batch by batch, DateTime values are
+/// written as timestamps of microseconds.
+pub(crate) struct ParquetWriter {
+    writer: ArrowWriter<File>,
+    schema: RelationSchema,
+    /// rows not written yet
+    rows: Vec<Row<'static>>,
+}
+
+impl ParquetWriter {
+    pub(crate) fn try_new(path: &str, schema: RelationSchema) -> DBResult<Self> {
+        let arrow_schema = Arc::new(arrow_schema_of(&schema));
+        let writer = ArrowWriter::try_new(create_file(path)?, arrow_schema, None)
+            .map_err(write_error)?;
+        Ok(Self {
+            writer,
+            schema,
+            rows: vec![],
+        })
+    }
+
+    fn write_batch(&mut self) -> DBResult<()> {
+        let batch = record_batch_of(&self.schema, &self.rows)?;
+        self.rows.clear();
+        self.writer.write(&batch).map_err(write_error)
+    }
+}
+
+fn write_error(e: parquet::errors::ParquetError) -> DBError {
+    DBError::StorageEngine(format!("write parquet file error: {e}"))
+}
+
+impl RowWriter for ParquetWriter {
+    fn write_row(&mut self, row: &Row<'static>) -> DBResult<()> {
+        self.rows.push(row.clone());
+        if self.rows.len() >= NUM_ROWS_PER_BATCH {
+            self.write_batch()?;
+        }
+        Ok(())
+    }
+
+    fn finish(mut self: Box<Self>) -> DBResult<()> {
+        if !self.rows.is_empty() {
+            self.write_batch()?;
+        }
+        self.writer.close().map(|_| ()).map_err(write_error)
+    }
+}
@@ -3,7 +3,7 @@ use crackdb::{
     expressions::Literal,
     row::Row,
     tables::{FieldInfo, RelationSchema},
-    CrackDB, ResultSet,
+    CrackDB, DBError, ResultSet,
 };
 
 #[test]
@@ -14,7 +14,7 @@ pub fn support_aggregations() {
         Ok(ResultSet::empty()));
     assert_eq!(
         db.execute("insert into orders values (1, 30.0, '101', '2023-01-22 15:04:00'), (2, 26.0, '101', '2023-02-01 20:55:00'), (3, 42.0, '102', '2023-02-01 20:55:00')"),
-        Ok(ResultSet::empty())
+        Ok(ResultSet::affected_rows(3))
     );
     let schema = RelationSchema::new(vec![
         FieldInfo::new("sum(amount)".to_owned(), DataType::Float64),
@@ -49,7 +49,7 @@ pub fn support_aggregations_within_complex_expressions() {
         Ok(ResultSet::empty()));
     assert_eq!(
         db.execute("insert into orders values (1, 30.0, '101', '2023-01-22 15:04:00'), (2, 26.0, '101', '2023-02-01 20:55:00'), (3, 42.0, '102', '2023-02-01 20:55:00')"),
-        Ok(ResultSet::empty())
+        Ok(ResultSet::affected_rows(3))
     );
     let schema = RelationSchema::new(vec![
         FieldInfo::new("amount".to_owned(), DataType::Float64),
@@ -84,7 +84,7 @@ pub fn support_more_aggregators() {
         Ok(ResultSet::empty()));
     assert_eq!(
         db.execute("insert into orders values (1, 30.0, '101', '2023-01-22 15:04:00'), (2, 26.0, '101', '2023-02-01 20:55:00'), (3, 42.0, '102', '2023-02-01 20:55:00')"),
-        Ok(ResultSet::empty())
+        Ok(ResultSet::affected_rows(3))
     );
     let schema = RelationSchema::new(vec![
         FieldInfo::new("avg".to_owned(), DataType::Float64),
@@ -126,7 +126,7 @@ pub fn support_having() {
         Ok(ResultSet::empty()));
     assert_eq!(
         db.execute("insert into orders values (1, 30.0, '101', '2023-01-22 15:04:00'), (2, 26.0, '101', '2023-02-01 20:55:00'), (3, 42.0, '102', '2023-02-01 20:55:00')"),
-        Ok(ResultSet::empty())
+        Ok(ResultSet::affected_rows(3))
     );
     let schema = RelationSchema::new(vec![
         FieldInfo::new("amount".to_owned(), DataType::Float64),
@@ -146,3 +146,279 @@ pub fn support_having() {
         Ok(expected_results)
     );
 }
+
+#[test]
+pub fn support_collection_aggregators() {
+    let db = CrackDB::new();
+    assert_eq!(
+        db.execute("create table orders (id int, amount double, userId String, dateTime DateTime)"),
+        Ok(ResultSet::empty()));
+    assert_eq!(
+        db.execute("insert into orders values (1, 30.0, '101', '2023-01-22 15:04:00'), (2, 26.0, '101', '2023-02-01 20:55:00'), (3, 42.0, '102', '2023-02-01 20:55:00'), (4, 12.0, '101', '2023-01-02 08:00:00')"),
+        Ok(ResultSet::affected_rows(4))
+    );
+    let schema = RelationSchema::new(vec![
+        FieldInfo::new("ids".to_owned(), DataType::String),
+        FieldInfo::new(
+            "amounts".to_owned(),
+            DataType::List(Box::new(DataType::Float64)),
+        ),
+        FieldInfo::new("userId".to_owned(), DataType::String),
+    ]);
+    let expected_results = ResultSet::new(
+        schema,
+        vec![
+            Row::new(vec![
+                Literal::String("4|2|1".to_owned()),
+                Literal::List(vec![
+                    Literal::Float64(12.0),
+                    Literal::Float64(30.0),
+                    Literal::Float64(26.0),
+                ]),
+                Literal::String("101".to_owned()),
+            ]),
+            Row::new(vec![
+                Literal::String("3".to_owned()),
+                Literal::List(vec![Literal::Float64(42.0)]),
+                Literal::String("102".to_owned()),
+            ]),
+        ],
+    );
+    assert_eq!(
+        db.execute("select string_agg(id, '|' order by id desc) as ids, array_agg(amount order by dateTime) as amounts, userId from orders group by userId order by userId"),
+        Ok(expected_results)
+    );
+    assert_eq!(
+        db.execute("select array_agg(amount) within group (order by dateTime) as amounts, userId from orders group by userId order by userId").unwrap(),
+        db.execute("select array_agg(amount order by dateTime) as amounts, userId from orders group by userId order by userId").unwrap()
+    );
+    assert_ne!(
+        db.execute("select array_agg(amount) within group (order by dateTime) as amounts, userId from orders group by userId order by userId"),
+        db.execute("select array_agg(amount order by dateTime desc) as amounts, userId from orders group by userId order by userId")
+    );
+    assert!(matches!(
+        db.execute("select array_agg(amount order by id) within group (order by dateTime) from orders"),
+        Err(DBError::ParserError(msg)) if msg.contains("both ORDER BY and WITHIN GROUP")
+    ));
+}
+
+#[test]
+pub fn support_boolean_and_positional_aggregators() {
+    let db = CrackDB::new();
+    assert_eq!(
+        db.execute(
+            "create table orders (id int, amount double, userId String, paid boolean)"
+        ),
+        Ok(ResultSet::empty())
+    );
+    assert_eq!(
+        db.execute("insert into orders values (1, 30.0, '101', true), (2, 26.0, '101', false), (3, 42.0, '102', true), (4, 12.0, '101', null)"),
+        Ok(ResultSet::affected_rows(4))
+    );
+    let schema = RelationSchema::new(vec![
+        FieldInfo::new("all_paid".to_owned(), DataType::Boolean),
+        FieldInfo::new("any_paid".to_owned(), DataType::Boolean),
+        FieldInfo::new("every_paid".to_owned(), DataType::Boolean),
+        FieldInfo::new("some_amount".to_owned(), DataType::Float64),
+        FieldInfo::new("first_amount".to_owned(), DataType::Float64),
+        FieldInfo::new("last_amount".to_owned(), DataType::Float64),
+        FieldInfo::new("largest".to_owned(), DataType::Int32),
+        FieldInfo::new("userId".to_owned(), DataType::String),
+    ]);
+    let expected_results = ResultSet::new(
+        schema,
+        vec![
+            Row::new(vec![
+                Literal::Bool(false),
+                Literal::Bool(true),
+                Literal::Bool(false),
+                Literal::Float64(30.0),
+                Literal::Float64(30.0),
+                Literal::Float64(12.0),
+                Literal::Int32(1),
+                Literal::String("101".to_owned()),
+            ]),
+            Row::new(vec![
+                Literal::Bool(true),
+                Literal::Bool(true),
+                Literal::Bool(true),
+                Literal::Float64(42.0),
+                Literal::Float64(42.0),
+                Literal::Float64(42.0),
+                Literal::Int32(3),
+                Literal::String("102".to_owned()),
+            ]),
+        ],
+    );
+    assert_eq!(
+        db.execute("select bool_and(paid) as all_paid, bool_or(paid) as any_paid, every(paid) as every_paid, any_value(amount) as some_amount, first(amount) as first_amount, last(amount) as last_amount, first(id order by amount desc) as largest, userId from orders group by userId order by userId"),
+        Ok(expected_results)
+    );
+
+    // aggregates whose results do not depend on the order of inputs reject ORDER BY
+    assert_eq!(
+        db.execute("select sum(amount order by id) from orders"),
+        Err(DBError::ParserError(
+            "ORDER BY is not supported by the aggregate function sum".to_owned()
+        ))
+    );
+}
+
+#[test]
+pub fn support_count_and_aggregate_filter() {
+    let db = CrackDB::new();
+    assert_eq!(
+        db.execute(
+            "create table orders (id int, amount double, userId String, coupon String)"
+        ),
+        Ok(ResultSet::empty())
+    );
+    assert_eq!(
+        db.execute("insert into orders values (1, 30.0, '101', 'NEW'), (2, 26.0, '101', null), (3, 42.0, '102', null), (4, 12.0, '101', 'VIP')"),
+        Ok(ResultSet::affected_rows(4))
+    );
+    let schema = RelationSchema::new(vec![
+        FieldInfo::new("orders".to_owned(), DataType::UInt64),
+        FieldInfo::new("with_coupon".to_owned(), DataType::UInt64),
+        FieldInfo::new("large_orders".to_owned(), DataType::UInt64),
+        FieldInfo::new("large_amount".to_owned(), DataType::Float64),
+        FieldInfo::new("userId".to_owned(), DataType::String),
+    ]);
+    let expected_results = ResultSet::new(
+        schema,
+        vec![
+            Row::new(vec![
+                Literal::UInt64(3),
+                Literal::UInt64(2),
+                Literal::UInt64(2),
+                Literal::Float64(56.0),
+                Literal::String("101".to_owned()),
+            ]),
+            Row::new(vec![
+                Literal::UInt64(1),
+                Literal::UInt64(0),
+                Literal::UInt64(1),
+                Literal::Float64(42.0),
+                Literal::String("102".to_owned()),
+            ]),
+        ],
+    );
+    assert_eq!(
+        db.execute("select count(*) as orders, count(coupon) as with_coupon, count(*) filter (where amount > 20.0) as large_orders, sum(amount) filter (where amount > 20.0) as large_amount, userId from orders group by userId order by userId"),
+        Ok(expected_results)
+    );
+}
+
+#[test]
+pub fn support_aggregations_without_group_by() {
+    let db = CrackDB::new();
+    assert_eq!(
+        db.execute("create table orders (id int, amount double, userId String, dateTime DateTime)"),
+        Ok(ResultSet::empty()));
+
+    // aggregating an empty table still results in a single row
+    let schema = RelationSchema::new(vec![
+        FieldInfo::new("count(*)".to_owned(), DataType::UInt64),
+        FieldInfo::new("sum(amount)".to_owned(), DataType::Float64),
+        FieldInfo::new("max(id)".to_owned(), DataType::Int32),
+    ]);
+    assert_eq!(
+        db.execute("select count(*), sum(amount), max(id) from orders"),
+        Ok(ResultSet::new(
+            schema.clone(),
+            vec![Row::new(vec![
+                Literal::UInt64(0),
+                Literal::Null,
+                Literal::Null
+            ])]
+        ))
+    );
+
+    assert_eq!(
+        db.execute("insert into orders values (1, 30.0, '101', '2023-01-22 15:04:00'), (2, 26.0, '101', '2023-02-01 20:55:00'), (3, 42.0, '102', '2023-02-01 20:55:00')"),
+        Ok(ResultSet::affected_rows(3))
+    );
+    assert_eq!(
+        db.execute("select count(*), sum(amount), max(id) from orders"),
+        Ok(ResultSet::new(
+            schema,
+            vec![Row::new(vec![
+                Literal::UInt64(3),
+                Literal::Float64(98.0),
+                Literal::Int32(3)
+            ])]
+        ))
+    );
+
+    let schema = RelationSchema::new(vec![FieldInfo::new(
+        "avg(amount)".to_owned(),
+        DataType::Float64,
+    )]);
+    assert_eq!(
+        db.execute("select avg(amount) from orders where userId = '103'"),
+        Ok(ResultSet::new(schema, vec![Row::new(vec![Literal::Null])]))
+    );
+}
+
+#[test]
+pub fn support_having_on_non_projected_aggregators() {
+    let db = CrackDB::new();
+    assert_eq!(
+        db.execute("create table orders (id int, amount double, userId String, dateTime DateTime)"),
+        Ok(ResultSet::empty()));
+    assert_eq!(
+        db.execute("insert into orders values (1, 30.0, '101', '2023-01-22 15:04:00'), (2, 26.0, '101', '2023-02-01 20:55:00'), (3, 42.0, '102', '2023-02-01 20:55:00')"),
+        Ok(ResultSet::affected_rows(3))
+    );
+
+    let schema =
+        RelationSchema::new(vec![FieldInfo::new("userId".to_owned(), DataType::String)]);
+    assert_eq!(
+        db.execute("select userId from orders group by userId having count(*) > 1"),
+        Ok(ResultSet::new(
+            schema.clone(),
+            vec![Row::new(vec![Literal::String("101".to_owned())])]
+        ))
+    );
+    assert_eq!(
+        db.execute("select userId from orders group by userId having userId = '102'"),
+        Ok(ResultSet::new(
+            schema,
+            vec![Row::new(vec![Literal::String("102".to_owned())])]
+        ))
+    );
+
+    let schema = RelationSchema::new(vec![FieldInfo::new(
+        "sum(amount)".to_owned(),
+        DataType::Float64,
+    )]);
+    assert_eq!(
+        db.execute("select sum(amount) from orders having max(amount) > 50.0"),
+        Ok(ResultSet::new(schema, vec![]))
+    );
+}
+
+#[test]
+pub fn reject_non_grouped_columns() {
+    let db = CrackDB::new();
+    assert_eq!(
+        db.execute("create table orders (id int, amount double, userId String, dateTime DateTime)"),
+        Ok(ResultSet::empty()));
+
+    let error = DBError::ParserError(
+        "column id must appear in the GROUP BY clause or be used in an aggregate function"
+            .to_owned(),
+    );
+    assert_eq!(
+        db.execute("select id, sum(amount) from orders group by userId"),
+        Err(error)
+    );
+    let error = DBError::ParserError(
+        "column userId must appear in the GROUP BY clause or be used in an aggregate function"
+            .to_owned(),
+    );
+    assert_eq!(
+        db.execute("select userId, count(*) from orders"),
+        Err(error)
+    );
+}
@@ -0,0 +1,59 @@
+use std::fs::File;
+
+use crackdb::{expressions::Literal, row::Row, CrackDB};
+
+mod common;
+use common::temp_path;
+
+/// write the whole table to an arrow file, and read it back
+fn assert_round_trip(source: &str, target: &str) {
+    let db = CrackDB::new();
+    let expected = db.execute(&format!("select * from '{source}'")).unwrap();
+    let path = temp_path(target);
+    expected
+        .write_arrow_ipc(File::create(&path).unwrap())
+        .unwrap();
+
+    let actual = db
+        .execute(&format!("select * from '{}'", path.display()))
+        .unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn test_round_trip_csv() {
+    assert_round_trip("tests/assets/orders.csv", "orders.arrow");
+}
+
+#[test]
+fn test_round_trip_parquet() {
+    assert_round_trip("tests/assets/products.parquet", "products.feather");
+}
+
+#[test]
+fn test_round_trip_json() {
+    assert_round_trip("tests/assets/users.ndjson", "users.arrow");
+}
+
+#[test]
+fn test_query_arrow_file() {
+    let db = CrackDB::new();
+    let path = temp_path("query.arrow");
+    db.execute("select id, name, price from 'tests/assets/products.parquet'")
+        .unwrap()
+        .write_arrow_ipc(File::create(&path).unwrap())
+        .unwrap();
+
+    let rs = db
+        .execute(&format!(
+            "select count(*), max(price) from '{}' where id > 2",
+            path.display()
+        ))
+        .unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(
+        rs.rows,
+        vec![Row::new(vec![Literal::UInt64(4), Literal::Float64(19.99)])]
+    );
+}
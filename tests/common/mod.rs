@@ -0,0 +1,16 @@
+#![allow(dead_code)]
+
+use std::path::PathBuf;
+
+/// a path in the temp dir unique to the process running the tests, so tests running
+/// in the same process must use different names
+pub fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("crackdb_{}_{name}", std::process::id()))
+}
+
+/// write the content to a file of `temp_path`
+pub fn temp_file(name: &str, content: &str) -> PathBuf {
+    let path = temp_path(name);
+    std::fs::write(&path, content).unwrap();
+    path
+}
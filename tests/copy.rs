@@ -0,0 +1,249 @@
+use std::path::PathBuf;
+
+use crackdb::{expressions::Literal, row::Row, CrackDB, DBError, ResultSet};
+
+mod common;
+use common::{temp_file, temp_path};
+
+/// read the file and remove it
+fn take_file(path: &PathBuf) -> String {
+    let content = std::fs::read_to_string(path).unwrap();
+    std::fs::remove_file(path).unwrap();
+    content
+}
+
+#[test]
+fn test_copy_query_to_csv() {
+    let db = CrackDB::new();
+    let path = temp_path("products.csv");
+    let rs = db
+        .execute(&format!(
+            "COPY (select id, name, rating from 'tests/assets/products.parquet' \
+             where id <= 3 order by id) TO '{}' WITH (format csv, header true, \
+             delimiter '|')",
+            path.display()
+        ))
+        .unwrap();
+    assert_eq!(rs, ResultSet::affected_rows(3));
+    assert_eq!(
+        take_file(&path),
+        "id|name|rating\n1|apple|4.5\n2|banana|\n3|cherry|3\n"
+    );
+}
+
+#[test]
+fn test_copy_table_to_csv() {
+    let db = CrackDB::new();
+    db.execute_batch(
+        "create table notes (id int, body String); \
+         insert into notes values (1, 'hello, world'), (2, 'say \"hi\"'), (3, null);",
+    )
+    .unwrap();
+    let path = temp_path("notes.csv");
+    db.execute(&format!("COPY notes (body) TO '{}'", path.display()))
+        .unwrap();
+    assert_eq!(
+        take_file(&path),
+        "body\n\"hello, world\"\n\"say \"\"hi\"\"\"\n\n"
+    );
+}
+
+#[test]
+fn test_copy_query_to_ndjson() {
+    let db = CrackDB::new();
+    let path = temp_path("products.ndjson");
+    db.execute(&format!(
+        "COPY (select * from 'tests/assets/products.parquet') TO '{}'",
+        path.display()
+    ))
+    .unwrap();
+    let content = take_file(&path);
+    assert_eq!(
+        content.lines().next().unwrap(),
+        r#"{"id":1,"name":"apple","price":1.25,"created_at":"2024-01-01 00:00:00","rating":4.5}"#
+    );
+    assert_eq!(content.lines().count(), 6);
+}
+
+#[test]
+fn test_copy_query_to_parquet() {
+    let db = CrackDB::new();
+    let query = "select * from 'tests/assets/products.parquet'";
+    let path = temp_path("products.parquet");
+    db.execute(&format!(
+        "COPY ({query}) TO '{}' WITH (format parquet)",
+        path.display()
+    ))
+    .unwrap();
+    let actual = db
+        .execute(&format!("select * from '{}'", path.display()))
+        .unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(actual, db.execute(query).unwrap());
+}
+
+#[test]
+fn test_copy_options_error() {
+    let db = CrackDB::new();
+    let path = temp_path("error.parquet");
+    let err = db
+        .execute(&format!(
+            "COPY (select * from 'tests/assets/orders.csv') TO '{}' WITH (header true)",
+            path.display()
+        ))
+        .unwrap_err();
+    assert!(
+        matches!(&err, DBError::ParserError(msg) if msg.contains("only supported by csv")),
+        "{err}"
+    );
+    assert!(!path.exists());
+}
+
+#[test]
+fn test_copy_from_csv() {
+    let db = CrackDB::new();
+    db.execute(
+        "create table orders (id int, amount double default 0, note String, paid Boolean)",
+    )
+    .unwrap();
+    let path = temp_file(
+        "orders_from.csv",
+        "id;note;paid\n1;first;true\n2;;false\n3;\"semi;colon\";1\n",
+    );
+    let rs = db
+        .execute(&format!(
+            "COPY orders (id, note, paid) FROM '{}' WITH (header, delimiter ';')",
+            path.display()
+        ))
+        .unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(rs.rows[0].get_field(0), Ok(Literal::UInt64(3)));
+    assert_eq!(
+        db.execute("select id, amount, note, paid from orders order by id")
+            .unwrap()
+            .rows,
+        vec![
+            Row::new(vec![
+                Literal::Int32(1),
+                Literal::Float64(0.0),
+                Literal::String("first".to_owned()),
+                Literal::Bool(true),
+            ]),
+            Row::new(vec![
+                Literal::Int32(2),
+                Literal::Float64(0.0),
+                Literal::Null,
+                Literal::Bool(false),
+            ]),
+            Row::new(vec![
+                Literal::Int32(3),
+                Literal::Float64(0.0),
+                Literal::String("semi;colon".to_owned()),
+                Literal::Bool(true),
+            ]),
+        ]
+    );
+}
+
+#[test]
+fn test_copy_from_bad_row() {
+    let db = CrackDB::new();
+    db.execute("create table orders (id int, amount double)")
+        .unwrap();
+    let path = temp_file("orders_bad.csv", "id,amount\n1,2.5\n2,abc\n3,1\n");
+    let err = db
+        .execute(&format!("COPY orders FROM '{}'", path.display()))
+        .unwrap_err();
+    assert_eq!(
+        err,
+        DBError::StorageEngine(
+            "line 3: column amount: cannot read 'abc' as Float64".to_owned()
+        )
+    );
+    // nothing is loaded if the COPY fails
+    assert!(db.execute("select id from orders").unwrap().rows.is_empty());
+
+    let rs = db
+        .execute(&format!(
+            "COPY orders FROM '{}' WITH (on_error skip)",
+            path.display()
+        ))
+        .unwrap();
+    assert_eq!(rs.rows[0].get_field(0), Ok(Literal::UInt64(2)));
+    assert_eq!(
+        rs.rows[0].get_field(1),
+        Ok(Literal::List(vec![Literal::UInt64(3)]))
+    );
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_copy_from_reject_limit() {
+    let db = CrackDB::new();
+    db.execute("create table orders (id int, amount double)")
+        .unwrap();
+    let path = temp_file("orders_limit.csv", "1,2.5\nx,1\n3\n4,1\n");
+    let copy = |limit: usize| {
+        db.execute(&format!(
+            "COPY orders FROM '{}' WITH (header false, reject_limit {limit})",
+            path.display()
+        ))
+    };
+    assert_eq!(
+        copy(1).unwrap_err(),
+        DBError::StorageEngine("line 3: expected 2 fields but got 1".to_owned())
+    );
+    let rs = copy(2).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(
+        rs.rows[0].get_field(1),
+        Ok(Literal::List(vec![Literal::UInt64(2), Literal::UInt64(3)]))
+    );
+}
+
+#[test]
+fn test_copy_from_constraints() {
+    let db = CrackDB::new();
+    db.execute("create table orders (id int primary key, amount double not null check (amount > 0))")
+        .unwrap();
+    db.execute("insert into orders values (1, 1.0)").unwrap();
+    let path = temp_file(
+        "orders_constraints.csv",
+        "1,2.5\n2,2.5\n2,3.5\n3,\n4,-1\n5,1\n",
+    );
+    let copy = |options: &str| {
+        db.execute(&format!(
+            "COPY orders FROM '{}' WITH (header false{options})",
+            path.display()
+        ))
+    };
+    assert_eq!(
+        copy("").unwrap_err(),
+        DBError::StorageEngine("line 1: UniqueViolation: orders_pkey".to_owned())
+    );
+    assert_eq!(
+        copy(", reject_limit 3").unwrap_err(),
+        DBError::StorageEngine("line 5: CheckViolation: orders_amount_check".to_owned())
+    );
+    let rs = copy(", on_error skip").unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(rs.rows[0].get_field(0), Ok(Literal::UInt64(2)));
+    assert_eq!(
+        rs.rows[0].get_field(1),
+        Ok(Literal::List(vec![
+            Literal::UInt64(1),
+            Literal::UInt64(3),
+            Literal::UInt64(4),
+            Literal::UInt64(5),
+        ]))
+    );
+    let rs = db.execute("select id from orders order by id").unwrap();
+    assert_eq!(
+        rs.rows,
+        vec![
+            Row::new(vec![Literal::Int32(1)]),
+            Row::new(vec![Literal::Int32(2)]),
+            Row::new(vec![Literal::Int32(5)]),
+        ]
+    );
+}
@@ -5,7 +5,7 @@ use crackdb::{
     expressions::Literal,
     row::Row,
     tables::{FieldInfo, RelationSchema},
-    CrackDB, ResultSet,
+    CrackDB, DBError, ResultSet,
 };
 
 #[test]
@@ -17,7 +17,7 @@ fn create_insert_and_query() {
     );
     assert_eq!(
         db.execute("insert into orders values (1, 30, 101)"),
-        Ok(ResultSet::empty())
+        Ok(ResultSet::affected_rows(1))
     );
     let schema = RelationSchema::new(vec![
         FieldInfo::new("id".to_owned(), DataType::Int32),
@@ -47,7 +47,7 @@ fn create_insert_and_query_with_more_data_types_and_expressions() {
     );
     assert_eq!(
         db.execute("insert into orders values (1, 30.0, '101', '2023-01-22 15:04:00')"),
-        Ok(ResultSet::empty())
+        Ok(ResultSet::affected_rows(1))
     );
     let schema = RelationSchema::new(vec![
         FieldInfo::new("id".to_owned(), DataType::Int32),
@@ -78,11 +78,11 @@ fn create_insert_and_query_with_more_expressions() {
         Ok(ResultSet::empty()));
     assert_eq!(
         db.execute("insert into orders values (1, 30.0, '101', '2023-01-22 15:04:00')"),
-        Ok(ResultSet::empty())
+        Ok(ResultSet::affected_rows(1))
     );
     assert_eq!(
         db.execute("insert into orders values (2, 60.0, '102', '2023-01-24 21:07:00')"),
-        Ok(ResultSet::empty())
+        Ok(ResultSet::affected_rows(1))
     );
     let schema = RelationSchema::new(vec![
         FieldInfo::new("id".to_owned(), DataType::Int32),
@@ -114,7 +114,7 @@ fn supports_projection_query() {
         Ok(ResultSet::empty()));
     assert_eq!(
         db.execute("insert into orders values (1, 30.0, '101', '2023-01-22 15:04:00')"),
-        Ok(ResultSet::empty())
+        Ok(ResultSet::affected_rows(1))
     );
     let schema = RelationSchema::new(vec![
         FieldInfo::new("id".to_owned(), DataType::Int32),
@@ -145,7 +145,7 @@ fn support_order_by() {
         Ok(ResultSet::empty()));
     assert_eq!(
         db.execute("insert into orders values (1, 30.0, '101', '2023-01-22 15:04:00'), (2, 60.0, '103', '2023-01-24 21:07:00'), (3, 60.0, '102', '2023-01-24 21:07:00')"),
-        Ok(ResultSet::empty())
+        Ok(ResultSet::affected_rows(3))
     );
     let schema = RelationSchema::new(vec![
         FieldInfo::new("id".to_owned(), DataType::Int32),
@@ -187,7 +187,7 @@ fn support_limit_and_offset() {
         Ok(ResultSet::empty()));
     assert_eq!(
         db.execute("insert into orders values (1, 30.0, '101', '2023-01-22 15:04:00'), (2, 60.0, '103', '2023-01-24 21:07:00'), (3, 60.0, '102', '2023-01-24 21:07:00')"),
-        Ok(ResultSet::empty())
+        Ok(ResultSet::affected_rows(3))
     );
     let schema = RelationSchema::new(vec![
         FieldInfo::new("id".to_owned(), DataType::Int32),
@@ -210,3 +210,355 @@ fn support_limit_and_offset() {
         Ok(expected_results)
     );
 }
+
+#[test]
+fn update_rows() {
+    let db = CrackDB::new();
+    assert_eq!(
+        db.execute("create table orders (id int, amount double, userId String)"),
+        Ok(ResultSet::empty())
+    );
+    assert_eq!(
+        db.execute("insert into orders values (1, 30.0, '101'), (2, 26.0, '101'), (3, 42.0, '102')"),
+        Ok(ResultSet::affected_rows(3))
+    );
+    assert_eq!(
+        db.execute(
+            "update orders set amount = amount * 2, userId = '103' where userId = '101'"
+        ),
+        Ok(ResultSet::affected_rows(2))
+    );
+    assert_eq!(
+        db.execute("update orders set amount = 0.0 where id > 10"),
+        Ok(ResultSet::affected_rows(0))
+    );
+    let schema = RelationSchema::new(vec![
+        FieldInfo::new("id".to_owned(), DataType::Int32),
+        FieldInfo::new("amount".to_owned(), DataType::Float64),
+        FieldInfo::new("userId".to_owned(), DataType::String),
+    ]);
+    let expected_results = ResultSet::new(
+        schema,
+        vec![
+            Row::new(vec![
+                Literal::Int32(1),
+                Literal::Float64(60.0),
+                Literal::String("103".to_owned()),
+            ]),
+            Row::new(vec![
+                Literal::Int32(2),
+                Literal::Float64(52.0),
+                Literal::String("103".to_owned()),
+            ]),
+            Row::new(vec![
+                Literal::Int32(3),
+                Literal::Float64(42.0),
+                Literal::String("102".to_owned()),
+            ]),
+        ],
+    );
+    assert_eq!(
+        db.execute("select * from orders order by id"),
+        Ok(expected_results)
+    );
+
+    // update without WHERE clause updates every row
+    assert_eq!(
+        db.execute("update orders set id = id + 10"),
+        Ok(ResultSet::affected_rows(3))
+    );
+    assert_eq!(
+        db.execute("update orders set unknown = 1"),
+        Err(DBError::ParserError("column unknown not found".to_owned()))
+    );
+    assert_eq!(
+        db.execute("update orders set id = 'abc'"),
+        Err(DBError::ParserError(
+            "cannot assign abc to column id of Int32".to_owned()
+        ))
+    );
+    // narrower numeric values are widened to the type of the column
+    assert_eq!(
+        db.execute("update orders set amount = id where id = 11"),
+        Ok(ResultSet::affected_rows(1))
+    );
+    assert_eq!(
+        db.execute("update orders set id = amount"),
+        Err(DBError::ParserError(
+            "cannot assign amount to column id of Int32".to_owned()
+        ))
+    );
+    assert_eq!(
+        db.execute("select amount from orders where id = 11"),
+        Ok(ResultSet::new(
+            RelationSchema::new(vec![FieldInfo::new(
+                "amount".to_owned(),
+                DataType::Float64
+            )]),
+            vec![Row::new(vec![Literal::Float64(11.0)])]
+        ))
+    );
+}
+
+#[test]
+fn delete_and_truncate_rows() {
+    let db = CrackDB::new();
+    assert_eq!(
+        db.execute("create table orders (id int, amount double, userId String)"),
+        Ok(ResultSet::empty())
+    );
+    assert_eq!(
+        db.execute("insert into orders values (1, 30.0, '101'), (2, 26.0, '101'), (3, 42.0, '102')"),
+        Ok(ResultSet::affected_rows(3))
+    );
+    assert_eq!(
+        db.execute("delete from orders where userId = '101' and amount < 28.0"),
+        Ok(ResultSet::affected_rows(1))
+    );
+    assert_eq!(
+        db.execute("delete from orders where id > 10"),
+        Ok(ResultSet::affected_rows(0))
+    );
+    let schema =
+        RelationSchema::new(vec![FieldInfo::new("id".to_owned(), DataType::Int32)]);
+    let expected_results = ResultSet::new(
+        schema.clone(),
+        vec![
+            Row::new(vec![Literal::Int32(1)]),
+            Row::new(vec![Literal::Int32(3)]),
+        ],
+    );
+    assert_eq!(
+        db.execute("select id from orders order by id"),
+        Ok(expected_results)
+    );
+
+    assert_eq!(
+        db.execute("truncate orders"),
+        Ok(ResultSet::affected_rows(2))
+    );
+    assert_eq!(
+        db.execute("select id from orders"),
+        Ok(ResultSet::new(schema, vec![]))
+    );
+
+    assert_eq!(
+        db.execute("insert into orders values (4, 12.0, '103')"),
+        Ok(ResultSet::affected_rows(1))
+    );
+    assert_eq!(
+        db.execute("delete from orders"),
+        Ok(ResultSet::affected_rows(1))
+    );
+}
+
+#[test]
+fn insert_with_columns_and_defaults() {
+    let db = CrackDB::new();
+    assert_eq!(
+        db.execute("create table orders (id int, amount double default 10.0, userId String default upper('unknown'))"),
+        Ok(ResultSet::empty())
+    );
+    assert_eq!(
+        db.execute(
+            "insert into orders (userId, id) values ('101', 1), (upper('abc'), 1 + 1)"
+        ),
+        Ok(ResultSet::affected_rows(2))
+    );
+    assert_eq!(
+        db.execute("insert into orders values (3, DEFAULT, lower('ABC')), (-4, 2.5 * 2.0, DEFAULT)"),
+        Ok(ResultSet::affected_rows(2))
+    );
+    let schema = RelationSchema::new(vec![
+        FieldInfo::new("id".to_owned(), DataType::Int32),
+        FieldInfo::new("amount".to_owned(), DataType::Float64),
+        FieldInfo::new("userId".to_owned(), DataType::String),
+    ]);
+    let expected_results = ResultSet::new(
+        schema,
+        vec![
+            Row::new(vec![
+                Literal::Int32(-4),
+                Literal::Float64(5.0),
+                Literal::String("UNKNOWN".to_owned()),
+            ]),
+            Row::new(vec![
+                Literal::Int32(1),
+                Literal::Float64(10.0),
+                Literal::String("101".to_owned()),
+            ]),
+            Row::new(vec![
+                Literal::Int32(2),
+                Literal::Float64(10.0),
+                Literal::String("ABC".to_owned()),
+            ]),
+            Row::new(vec![
+                Literal::Int32(3),
+                Literal::Float64(10.0),
+                Literal::String("abc".to_owned()),
+            ]),
+        ],
+    );
+    assert_eq!(
+        db.execute("select * from orders order by id"),
+        Ok(expected_results)
+    );
+
+    assert_eq!(
+        db.execute("insert into orders (id) values (5, 1.0)"),
+        Err(DBError::ParserError(
+            "INSERT has more expressions than target columns: 2".to_owned()
+        ))
+    );
+    assert_eq!(
+        db.execute("insert into orders (id, amount) values (5, 1.0), (6)"),
+        Err(DBError::ParserError(
+            "INSERT has fewer expressions than target columns: 1".to_owned()
+        ))
+    );
+    assert_eq!(
+        db.execute("insert into orders (id, unknown) values (5, 1.0)"),
+        Err(DBError::ParserError("column unknown not found".to_owned()))
+    );
+    assert_eq!(
+        db.execute("insert into orders (id) values ('abc')"),
+        Err(DBError::ParserError(
+            "cannot store abc into column id of Int32".to_owned()
+        ))
+    );
+}
+
+#[test]
+fn insert_from_select() {
+    let db = CrackDB::new();
+    assert_eq!(
+        db.execute("create table orders (id int, amount double, userId String)"),
+        Ok(ResultSet::empty())
+    );
+    assert_eq!(
+        db.execute("insert into orders values (1, 30.0, '101'), (2, 26.0, '101'), (3, 42.0, '102')"),
+        Ok(ResultSet::affected_rows(3))
+    );
+    assert_eq!(
+        db.execute("create table totals (userId String, total double, note String)"),
+        Ok(ResultSet::empty())
+    );
+    assert_eq!(
+        db.execute("insert into totals (userId, total) select userId, sum(amount) from orders group by userId"),
+        Ok(ResultSet::affected_rows(2))
+    );
+    assert_eq!(
+        db.execute("insert into totals select upper(userId), amount, 'copied' from orders where id = 3"),
+        Ok(ResultSet::affected_rows(1))
+    );
+    let schema = RelationSchema::new(vec![
+        FieldInfo::new("userId".to_owned(), DataType::String),
+        FieldInfo::new("total".to_owned(), DataType::Float64),
+        FieldInfo::new("note".to_owned(), DataType::String),
+    ]);
+    let expected_results = ResultSet::new(
+        schema,
+        vec![
+            Row::new(vec![
+                Literal::String("101".to_owned()),
+                Literal::Float64(56.0),
+                Literal::Null,
+            ]),
+            Row::new(vec![
+                Literal::String("102".to_owned()),
+                Literal::Float64(42.0),
+                Literal::Null,
+            ]),
+            Row::new(vec![
+                Literal::String("102".to_owned()),
+                Literal::Float64(42.0),
+                Literal::String("copied".to_owned()),
+            ]),
+        ],
+    );
+    assert_eq!(
+        db.execute("select * from totals order by userId, total"),
+        Ok(expected_results)
+    );
+    assert_eq!(
+        db.execute("insert into totals (userId) select userId, amount from orders"),
+        Err(DBError::ParserError(
+            "INSERT has 1 target columns but the query returns 2 columns".to_owned()
+        ))
+    );
+}
+
+#[test]
+fn execute_batch() {
+    let db = CrackDB::new();
+    let results = db.execute_batch(
+        "create table t (id int, name String);
+        insert into t values (1, 'a'), (2, 'b');;
+        update t set name = 'c' where id = 2;
+        select name from t order by name;",
+    );
+    let schema =
+        RelationSchema::new(vec![FieldInfo::new("name".to_owned(), DataType::String)]);
+    let names = ResultSet::new(
+        schema,
+        vec![
+            Row::new(vec![Literal::String("a".to_owned())]),
+            Row::new(vec![Literal::String("c".to_owned())]),
+        ],
+    );
+    assert_eq!(
+        results,
+        Ok(vec![
+            ResultSet::empty(),
+            ResultSet::affected_rows(2),
+            ResultSet::affected_rows(1),
+            names
+        ])
+    );
+
+    // statements after the failed one are not executed
+    assert_eq!(
+        db.execute_batch(
+            "delete from t where id = 1; select * from missing; delete from t"
+        ),
+        Err(DBError::StatementFailed(
+            1,
+            Box::new(DBError::TableNotFound("missing".to_owned()))
+        ))
+    );
+    assert_eq!(
+        db.execute("select count(*) from t"),
+        Ok(ResultSet::new(
+            RelationSchema::new(vec![FieldInfo::new(
+                "count(*)".to_owned(),
+                DataType::UInt64
+            )]),
+            vec![Row::new(vec![Literal::UInt64(1)])]
+        ))
+    );
+
+    // no statement is executed if a statement fails to parse
+    assert!(matches!(
+        db.execute_batch("delete from t; select * frm t"),
+        Err(DBError::StatementFailed(1, e)) if matches!(*e, DBError::ParserError(_))
+    ));
+    assert!(matches!(
+        db.execute_batch("delete from t;\n select 1 select 2; delete from t"),
+        Err(DBError::StatementFailed(1, e)) if matches!(*e, DBError::ParserError(_))
+    ));
+    assert!(matches!(
+        db.execute_batch("delete from t; ;\n select 1; select 'a"),
+        Err(DBError::StatementFailed(2, e)) if matches!(*e, DBError::ParserError(_))
+    ));
+    assert_eq!(
+        db.execute("select count(*) from t"),
+        Ok(ResultSet::new(
+            RelationSchema::new(vec![FieldInfo::new(
+                "count(*)".to_owned(),
+                DataType::UInt64
+            )]),
+            vec![Row::new(vec![Literal::UInt64(1)])]
+        ))
+    );
+    assert_eq!(db.execute_batch(""), Ok(vec![]));
+}
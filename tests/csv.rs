@@ -1,13 +1,18 @@
-use std::env;
+use std::{env, io::Write};
 
+use bzip2::write::BzEncoder;
 use crackdb::{
     data_types::DataType,
     expressions::Literal,
     row::Row,
     tables::{FieldInfo, RelationSchema},
-    CrackDB, ResultSet,
+    CrackDB, DBError, ResultSet,
 };
 use csv_core::ReaderBuilder;
+use flate2::write::GzEncoder;
+
+mod common;
+use common::{temp_file, temp_path};
 
 #[test]
 fn query_from_csv() {
@@ -40,7 +45,7 @@ fn query_all_from_csv() {
         FieldInfo::new("userId".to_owned(), DataType::String),
         FieldInfo::new("id".to_owned(), DataType::Int64),
         FieldInfo::new("amount".to_owned(), DataType::Float64),
-        FieldInfo::new("dateTime".to_owned(), DataType::String),
+        FieldInfo::new("dateTime".to_owned(), DataType::DateTime),
     ]);
     let expected_results = ResultSet::new(
         schema,
@@ -49,19 +54,19 @@ fn query_all_from_csv() {
                 Literal::String("101".to_owned()),
                 Literal::Int64(1),
                 Literal::Float64(30.0),
-                Literal::String("2023-02-14 12:35:00".to_owned()),
+                Literal::DateTime("2023-02-14 12:35:00".to_owned()),
             ]),
             Row::new(vec![
                 Literal::String("101".to_owned()),
                 Literal::Int64(2),
                 Literal::Float64(26.0),
-                Literal::String("2023-02-14 12:36:00".to_owned()),
+                Literal::DateTime("2023-02-14 12:36:00".to_owned()),
             ]),
             Row::new(vec![
                 Literal::String("102a".to_owned()),
                 Literal::Int64(3),
                 Literal::Float64(64.0),
-                Literal::String("2023-02-14 12:37:00".to_owned()),
+                Literal::DateTime("2023-02-14 12:37:00".to_owned()),
             ]),
         ],
     );
@@ -103,3 +108,487 @@ fn test_csv() {
         }
     }
 }
+
+#[test]
+fn test_read_csv_with_options() {
+    let db = CrackDB::new();
+    let path = temp_file(
+        "measurements.tsv",
+        "# exported measurements\n# sensor a\n1\t20.5\tNA\n2\tNA\tok\n",
+    );
+    let rs = db
+        .execute(&format!(
+            r"select * from read_csv('{}', delim => '\t', header => false, null => 'NA', skip => 2)",
+            path.display()
+        ))
+        .unwrap();
+    std::fs::remove_file(&path).unwrap();
+    let schema = RelationSchema::new(vec![
+        FieldInfo::new("column0".to_owned(), DataType::Int64),
+        FieldInfo::new("column1".to_owned(), DataType::Float64),
+        FieldInfo::new("column2".to_owned(), DataType::String),
+    ]);
+    let expected_results = ResultSet::new(
+        schema,
+        vec![
+            Row::new(vec![
+                Literal::Int64(1),
+                Literal::Float64(20.5),
+                Literal::Null,
+            ]),
+            Row::new(vec![
+                Literal::Int64(2),
+                Literal::Null,
+                Literal::String("ok".to_owned()),
+            ]),
+        ],
+    );
+    assert_eq!(rs, expected_results);
+}
+
+#[test]
+fn test_create_external_table() {
+    let db = CrackDB::new();
+    let path = temp_file("notes.csv", "1|'it\\'s'\n2|'a|b'\n");
+    db.execute(&format!(
+        "CREATE EXTERNAL TABLE notes (id int, body String) STORED AS csv LOCATION '{}' \
+         OPTIONS ('header' 'false', 'delimiter' '|', quote '''', escape '\\')",
+        path.display()
+    ))
+    .unwrap();
+    let rs = db
+        .execute("select id, body from notes where id > 0")
+        .unwrap();
+    let schema = RelationSchema::new(vec![
+        FieldInfo::new("id".to_owned(), DataType::Int32),
+        FieldInfo::new("body".to_owned(), DataType::String),
+    ]);
+    let expected_results = ResultSet::new(
+        schema,
+        vec![
+            Row::new(vec![Literal::Int32(1), Literal::String("it's".to_owned())]),
+            Row::new(vec![Literal::Int32(2), Literal::String("a|b".to_owned())]),
+        ],
+    );
+    assert_eq!(rs, expected_results);
+
+    // columns are read from the file if not declared
+    db.execute(&format!(
+        "CREATE EXTERNAL TABLE raw_notes STORED AS csv LOCATION '{}' \
+         OPTIONS (header false, delimiter '|', quote '''', escape '\\')",
+        path.display()
+    ))
+    .unwrap();
+    let rs = db.execute("select column0 from raw_notes").unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(rs.schema.get_fields()[0].data_type(), &DataType::Int64);
+    assert_eq!(rs.rows.len(), 2);
+}
+
+#[test]
+fn test_csv_multi_line_records() {
+    let db = CrackDB::new();
+    let path = temp_file(
+        "multi_line.csv",
+        "\u{feff}id,note\r\n1,\"first\r\nsecond\"\r\n\r\n2,\"say \"\"hi\"\"\"\r\n",
+    );
+    let rs = db
+        .execute(&format!("select id, note from '{}'", path.display()))
+        .unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(
+        rs.rows,
+        vec![
+            Row::new(vec![
+                Literal::Int64(1),
+                Literal::String("first\r\nsecond".to_owned()),
+            ]),
+            Row::new(vec![
+                Literal::Int64(2),
+                Literal::String("say \"hi\"".to_owned()),
+            ]),
+        ]
+    );
+}
+
+#[test]
+fn test_csv_large_and_wide_records() {
+    let db = CrackDB::new();
+    let names = (0..300).map(|i| format!("c{i}")).collect::<Vec<_>>();
+    let long_value = "x".repeat(100_000);
+    let values = (0..300)
+        .map(|i| match i {
+            299 => long_value.clone(),
+            i => i.to_string(),
+        })
+        .collect::<Vec<_>>();
+    let path = temp_file(
+        "wide.csv",
+        &format!("{}\n{}\n", names.join(","), values.join(",")),
+    );
+    let rs = db
+        .execute(&format!("select c0, c298, c299 from '{}'", path.display()))
+        .unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(
+        rs.rows,
+        vec![Row::new(vec![
+            Literal::Int64(0),
+            Literal::Int64(298),
+            Literal::String(long_value),
+        ])]
+    );
+}
+
+#[test]
+fn test_read_csv_is_read_by_each_query() {
+    let db = CrackDB::new();
+    let path = temp_file("changing.csv", "id\n1\n");
+    let query = || {
+        db.execute(&format!("select * from read_csv('{}')", path.display()))
+            .unwrap()
+    };
+    assert_eq!(query().rows, vec![Row::new(vec![Literal::Int64(1)])]);
+
+    // the table of a table function is not kept in the catalog
+    std::fs::write(&path, "id,name\n2,b\n").unwrap();
+    let rs = query();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(rs.schema.num_fields(), 2);
+    assert_eq!(
+        rs.rows,
+        vec![Row::new(vec![
+            Literal::Int64(2),
+            Literal::String("b".to_owned())
+        ])]
+    );
+    let rs = db
+        .execute("select table_name from information_schema.tables")
+        .unwrap();
+    assert!(rs.rows.is_empty());
+}
+
+#[test]
+fn test_csv_malformed_record_position() {
+    let db = CrackDB::new();
+    let path = temp_file("malformed.csv", "id,amount\n1,\"2\n.5\"\n2\n");
+    let err = db
+        .execute(&format!(
+            "select * from read_csv('{}', header => false)",
+            path.display()
+        ))
+        .unwrap_err();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(
+        err,
+        DBError::StorageEngine(
+            "malformed csv record at line 4 (byte 19): expected 2 fields but got 1"
+                .to_owned()
+        )
+    );
+}
+
+#[test]
+fn test_csv_inference_options() {
+    let db = CrackDB::new();
+    let mut content = "id,score,seen,label\n".to_owned();
+    for id in 1..=10 {
+        content.push_str(&format!("{id},{id}0,2024-01-{id:02} 10:00:00,NULL\n"));
+    }
+    content.push_str("11,n/a,,eleven\n");
+    let path = temp_file("scores.csv", &content);
+    let query = |options: &str| {
+        db.execute(&format!(
+            "select id, score, seen, label from read_csv('{}'{options}) where id > 9",
+            path.display()
+        ))
+    };
+
+    // the types are inferred from the first 10 records
+    assert_eq!(
+        query("").unwrap_err(),
+        DBError::StorageEngine(
+            "malformed csv record at line 12 (byte 322): column score: cannot read 'n/a' \
+             as Int64"
+                .to_owned()
+        )
+    );
+    let rs = query(", invalid_values => 'null'").unwrap();
+    assert_eq!(
+        rs.schema,
+        RelationSchema::new(vec![
+            FieldInfo::new("id".to_owned(), DataType::Int64),
+            FieldInfo::new("score".to_owned(), DataType::Int64),
+            FieldInfo::new("seen".to_owned(), DataType::DateTime),
+            FieldInfo::new("label".to_owned(), DataType::String),
+        ])
+    );
+    assert_eq!(
+        rs.rows,
+        vec![
+            Row::new(vec![
+                Literal::Int64(10),
+                Literal::Int64(100),
+                Literal::DateTime("2024-01-10 10:00:00".to_owned()),
+                Literal::Null,
+            ]),
+            Row::new(vec![
+                Literal::Int64(11),
+                Literal::Null,
+                Literal::Null,
+                Literal::String("eleven".to_owned()),
+            ]),
+        ]
+    );
+
+    let rs = query(", sample_size => -1").unwrap();
+    assert_eq!(rs.schema.get_fields()[1].data_type(), &DataType::String);
+    let rs =
+        query(", types => 'score double, id bigint', invalid_values => 'null'").unwrap();
+    assert_eq!(rs.schema.get_fields()[0].data_type(), &DataType::Int64);
+    assert_eq!(rs.rows[0].get_field(1), Ok(Literal::Float64(100.0)));
+    let rs = query(", narrow_integers => true, invalid_values => 'null'").unwrap();
+    assert_eq!(rs.schema.get_fields()[1].data_type(), &DataType::Int32);
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_csv_datetimes() {
+    let db = CrackDB::new();
+    let path = temp_file(
+        "datetimes.csv",
+        "seen\n2026-01-01T10:00Z\n2026-01-01 09:00\n2024-02-29 00:00:00.50\n",
+    );
+    let query = |sql: &str| {
+        db.execute(&sql.replace("{path}", &path.display().to_string()))
+            .unwrap()
+    };
+    // timestamps are normalized, so that they are compared by their texts
+    let rs = query("select seen from '{path}' order by seen");
+    assert_eq!(rs.schema.get_fields()[0].data_type(), &DataType::DateTime);
+    assert_eq!(
+        rs.rows,
+        vec![
+            Row::new(vec![Literal::DateTime("2024-02-29 00:00:00.5".to_owned())]),
+            Row::new(vec![Literal::DateTime("2026-01-01 09:00:00".to_owned())]),
+            Row::new(vec![Literal::DateTime("2026-01-01 10:00:00".to_owned())]),
+        ]
+    );
+    let rs = query("select seen from '{path}' where seen > '2026-01-01T09:30'");
+    assert_eq!(rs.rows.len(), 1);
+
+    std::fs::remove_file(&path).unwrap();
+
+    // days out of their months are not dates
+    let path = temp_file("days.csv", "seen\n2026-01-31\n2026-02-29\n");
+    let rs = db
+        .execute(&format!("select seen from '{}'", path.display()))
+        .unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(rs.schema.get_fields()[0].data_type(), &DataType::String);
+}
+
+#[test]
+fn test_csv_sum_of_large_integers() {
+    let db = CrackDB::new();
+    let path = temp_file("large.csv", "amount\n2000000000\n2000000000\n");
+    let query = |options: &str| {
+        db.execute(&format!(
+            "select sum(amount) from read_csv('{}'{options})",
+            path.display()
+        ))
+    };
+    let rs = query("").unwrap();
+    assert_eq!(rs.rows, vec![Row::new(vec![Literal::Int64(4000000000)])]);
+    // sums of narrowed integers fail rather than overflow
+    let err = query(", narrow_integers => true").unwrap_err();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(
+        err,
+        DBError::InterpretingError("Plus operator overflowed".to_owned())
+    );
+}
+
+#[test]
+fn test_csv_glob_with_partitions() {
+    let db = CrackDB::new();
+    let dir = temp_path("events");
+    for (date, region, content) in [
+        ("2026-10-01", "eu", "id,amount\n1,10\n2,20\n"),
+        ("2026-10-01", "us", "id,amount\n3,30\n"),
+        ("2026-10-02", "eu", "id,amount\n4,40\n"),
+    ] {
+        let partition = dir.join(format!("date={date}/region={region}"));
+        std::fs::create_dir_all(&partition).unwrap();
+        std::fs::write(partition.join("part-0.csv"), content).unwrap();
+    }
+    let pattern = format!("{}/*/*/part-*.csv", dir.display());
+
+    let rs = db
+        .execute(&format!("select * from '{pattern}' where amount > 10"))
+        .unwrap();
+    assert_eq!(
+        rs.schema,
+        RelationSchema::new(vec![
+            FieldInfo::new("id".to_owned(), DataType::Int64),
+            FieldInfo::new("amount".to_owned(), DataType::Int64),
+            FieldInfo::new("date".to_owned(), DataType::DateTime),
+            FieldInfo::new("region".to_owned(), DataType::String),
+        ])
+    );
+    assert_eq!(
+        rs.rows,
+        vec![
+            Row::new(vec![
+                Literal::Int64(2),
+                Literal::Int64(20),
+                Literal::DateTime("2026-10-01".to_owned()),
+                Literal::String("eu".to_owned()),
+            ]),
+            Row::new(vec![
+                Literal::Int64(3),
+                Literal::Int64(30),
+                Literal::DateTime("2026-10-01".to_owned()),
+                Literal::String("us".to_owned()),
+            ]),
+            Row::new(vec![
+                Literal::Int64(4),
+                Literal::Int64(40),
+                Literal::DateTime("2026-10-02".to_owned()),
+                Literal::String("eu".to_owned()),
+            ]),
+        ]
+    );
+
+    // files of other partitions are not read
+    let query = format!(
+        "select id from read_csv('{pattern}') where region = 'eu' and date = '2026-10-01'"
+    );
+    let rs = db.execute(&query).unwrap();
+    assert_eq!(
+        rs.rows,
+        vec![
+            Row::new(vec![Literal::Int64(1)]),
+            Row::new(vec![Literal::Int64(2)])
+        ]
+    );
+    let plan = db.execute(&format!("explain analyze {query}")).unwrap();
+    let scan = plan
+        .rows
+        .iter()
+        .filter_map(|row| match row.get_field(1) {
+            Ok(Literal::String(line)) if line.contains("MultiFileScan") => Some(line),
+            _ => None,
+        })
+        .next()
+        .unwrap();
+    assert!(scan.contains("files: 1 of 3"), "{scan}");
+
+    let err = db
+        .execute(&format!("select * from '{}/*/*.csv'", dir.display()))
+        .unwrap_err();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert!(
+        matches!(&err, DBError::StorageEngine(msg) if msg.starts_with("no files match")),
+        "{err:?}"
+    );
+}
+
+#[test]
+fn test_csv_glob_with_null_partitions() {
+    let dir = temp_path("null_partitions");
+    for (partition, content) in [
+        ("k=abc2", "a\n1\n"),
+        ("k=__HIVE_DEFAULT_PARTITION__", "a\n2\n"),
+    ] {
+        std::fs::create_dir_all(dir.join(partition)).unwrap();
+        std::fs::write(dir.join(partition).join("p.csv"), content).unwrap();
+    }
+    let rs = CrackDB::new()
+        .execute(&format!(
+            "select a, k from '{}/k=*/p.csv' where k = 'abc2'",
+            dir.display()
+        ))
+        .unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(
+        rs.rows,
+        vec![Row::new(vec![
+            Literal::Int64(1),
+            Literal::String("abc2".to_owned())
+        ])]
+    );
+}
+
+#[test]
+fn test_csv_glob_of_other_columns() {
+    let dir = temp_path("columns");
+    std::fs::create_dir_all(&dir).unwrap();
+    let query = |second: &str| {
+        std::fs::write(dir.join("part-0.csv"), "id,amount\n1,10\n").unwrap();
+        std::fs::write(dir.join("part-1.csv"), second).unwrap();
+        CrackDB::new().execute(&format!("select * from '{}/part-*.csv'", dir.display()))
+    };
+    let expected_err = DBError::StorageEngine(format!(
+        "the schema of {0}/part-1.csv is not the schema of {0}/part-0.csv",
+        dir.display()
+    ));
+    // files are matched by the names and the number of their columns
+    assert_eq!(query("id,price\n2,20\n").unwrap_err(), expected_err);
+    assert_eq!(query("id,amount,note\n2,20,x\n").unwrap_err(), expected_err);
+    assert_eq!(query("id\n2\n").unwrap_err(), expected_err);
+    let rs = query("id,amount\n2,20\n").unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(rs.rows.len(), 2);
+}
+
+#[test]
+fn test_csv_compressed_files() {
+    let db = CrackDB::new();
+    let content = std::fs::read("tests/assets/orders.csv").unwrap();
+    let expected = db
+        .execute("select id, dateTime from 'tests/assets/orders.csv' where amount > 26")
+        .unwrap();
+
+    let mut encoder = GzEncoder::new(vec![], flate2::Compression::default());
+    encoder.write_all(&content).unwrap();
+    let gzip_path = temp_file("orders.csv.gz", "");
+    std::fs::write(&gzip_path, encoder.finish().unwrap()).unwrap();
+    // zstd files are told by their magic bytes without the extension
+    let zstd_path = temp_file("orders_zstd.csv", "");
+    std::fs::write(&zstd_path, zstd::encode_all(content.as_slice(), 0).unwrap()).unwrap();
+    let mut encoder = BzEncoder::new(vec![], bzip2::Compression::default());
+    encoder.write_all(&content).unwrap();
+    let bzip2_path = temp_file("orders.csv.bz2", "");
+    std::fs::write(&bzip2_path, encoder.finish().unwrap()).unwrap();
+    for path in [&gzip_path, &zstd_path, &bzip2_path] {
+        let rs = db
+            .execute(&format!(
+                "select id, dateTime from '{}' where amount > 26",
+                path.display()
+            ))
+            .unwrap();
+        std::fs::remove_file(path).unwrap();
+        assert_eq!(rs, expected);
+    }
+
+    // text starting with `BZh` is not bzip2 without the block size
+    let text_path = temp_file("bzh.csv", "BZhx,y\n1,2\n");
+    let rs = db
+        .execute(&format!("select BZhx from '{}'", text_path.display()))
+        .unwrap();
+    std::fs::remove_file(&text_path).unwrap();
+    assert_eq!(rs.rows, vec![Row::new(vec![Literal::Int64(1)])]);
+
+    let bzip2_path = temp_file("corrupt.csv.bz2", "BZh91AY&SY");
+    let err = db
+        .execute(&format!("select * from '{}'", bzip2_path.display()))
+        .unwrap_err();
+    std::fs::remove_file(&bzip2_path).unwrap();
+    assert_eq!(
+        err,
+        DBError::StorageEngine(
+            "read csv file error: decompression not finished but EOF reached".to_owned()
+        )
+    );
+}
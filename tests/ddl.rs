@@ -0,0 +1,440 @@
+use std::{sync::Arc, thread};
+
+use crackdb::{
+    catalog::Catalog,
+    data_types::DataType,
+    expressions::Literal,
+    row::Row,
+    tables::{inmem::InMemTable, FieldInfo, RelationSchema, TableMeta},
+    CrackDB, DBError, ResultSet,
+};
+
+fn create_orders(db: &CrackDB) {
+    assert_eq!(
+        db.execute(
+            "create table orders (id int, amount double default 1.0, userId String)"
+        ),
+        Ok(ResultSet::empty())
+    );
+    assert_eq!(
+        db.execute("insert into orders values (1, 30.0, '101'), (2, 26.0, '101'), (3, 42.0, '102')"),
+        Ok(ResultSet::affected_rows(3))
+    );
+}
+
+#[test]
+fn create_table_as_select() {
+    let db = CrackDB::new();
+    create_orders(&db);
+    assert_eq!(
+        db.execute("create table top_users as select userId, sum(amount) as total, 'vip' as tier from orders group by userId"),
+        Ok(ResultSet::empty())
+    );
+    let schema = RelationSchema::new(vec![
+        FieldInfo::new("userId".to_owned(), DataType::String),
+        FieldInfo::new("total".to_owned(), DataType::Float64),
+        FieldInfo::new("tier".to_owned(), DataType::String),
+    ]);
+    let expected_results = ResultSet::new(
+        schema,
+        vec![
+            Row::new(vec![
+                Literal::String("101".to_owned()),
+                Literal::Float64(56.0),
+                Literal::String("vip".to_owned()),
+            ]),
+            Row::new(vec![
+                Literal::String("102".to_owned()),
+                Literal::Float64(42.0),
+                Literal::String("vip".to_owned()),
+            ]),
+        ],
+    );
+    assert_eq!(
+        db.execute("select * from top_users order by userId"),
+        Ok(expected_results)
+    );
+}
+
+#[test]
+fn create_table_like() {
+    let db = CrackDB::new();
+    create_orders(&db);
+    assert_eq!(
+        db.execute("create table orders_copy (like orders)"),
+        Ok(ResultSet::empty())
+    );
+    assert_eq!(
+        db.execute("create table orders_copy2 like orders"),
+        Ok(ResultSet::empty())
+    );
+    assert_eq!(
+        db.execute("insert into orders_copy (id, userId) values (4, '103')"),
+        Ok(ResultSet::affected_rows(1))
+    );
+    let schema = RelationSchema::new(vec![
+        FieldInfo::new("id".to_owned(), DataType::Int32),
+        FieldInfo::new("amount".to_owned(), DataType::Float64),
+        FieldInfo::new("userId".to_owned(), DataType::String),
+    ]);
+    assert_eq!(
+        db.execute("select * from orders_copy"),
+        Ok(ResultSet::new(
+            schema.clone(),
+            vec![Row::new(vec![
+                Literal::Int32(4),
+                Literal::Float64(1.0),
+                Literal::String("103".to_owned()),
+            ])]
+        ))
+    );
+    assert_eq!(
+        db.execute("select * from orders_copy2"),
+        Ok(ResultSet::new(schema, vec![]))
+    );
+}
+
+#[test]
+fn create_table_if_not_exists_concurrently() {
+    let catalog = Arc::new(Catalog::new());
+    let handles = (0..8)
+        .map(|_| {
+            let catalog = Arc::clone(&catalog);
+            thread::spawn(move || {
+                let schema = RelationSchema::new(vec![FieldInfo::new(
+                    "id".to_owned(),
+                    DataType::Int32,
+                )]);
+                let table = InMemTable::new(TableMeta::new(schema));
+                catalog.add_new_table("orders".to_owned(), Box::new(table), false, true)
+            })
+        })
+        .collect::<Vec<_>>();
+    for handle in handles {
+        assert_eq!(handle.join().unwrap(), Ok(()));
+    }
+    assert_eq!(catalog.table_exists("orders"), Ok(true));
+}
+
+#[test]
+fn create_table_if_not_exists_or_replace() {
+    let db = CrackDB::new();
+    create_orders(&db);
+    assert_eq!(
+        db.execute("create table orders (id int)"),
+        Err(DBError::TableAlreadyExists("orders".to_owned()))
+    );
+
+    // existing table is kept
+    assert_eq!(
+        db.execute("create table if not exists orders (id int)"),
+        Ok(ResultSet::empty())
+    );
+    let schema = RelationSchema::new(vec![FieldInfo::new(
+        "count(*)".to_owned(),
+        DataType::UInt64,
+    )]);
+    assert_eq!(
+        db.execute("select count(*) from orders"),
+        Ok(ResultSet::new(
+            schema.clone(),
+            vec![Row::new(vec![Literal::UInt64(3)])]
+        ))
+    );
+
+    // existing table is replaced
+    assert_eq!(
+        db.execute(
+            "create or replace table orders as select id from orders where id > 1"
+        ),
+        Ok(ResultSet::empty())
+    );
+    assert_eq!(
+        db.execute("select count(*) from orders"),
+        Ok(ResultSet::new(
+            schema,
+            vec![Row::new(vec![Literal::UInt64(2)])]
+        ))
+    );
+}
+
+#[test]
+fn drop_tables() {
+    let db = CrackDB::new();
+    create_orders(&db);
+    assert_eq!(db.execute("drop table orders"), Ok(ResultSet::empty()));
+    assert_eq!(
+        db.execute("select * from orders"),
+        Err(DBError::TableNotFound("orders".to_owned()))
+    );
+    assert_eq!(
+        db.execute("drop table orders"),
+        Err(DBError::TableNotFound("orders".to_owned()))
+    );
+    assert_eq!(
+        db.execute("drop table if exists orders"),
+        Ok(ResultSet::empty())
+    );
+}
+
+#[test]
+fn alter_table_columns() {
+    let db = CrackDB::new();
+    create_orders(&db);
+    assert_eq!(
+        db.execute("alter table orders add column coupon String default 'NONE'"),
+        Ok(ResultSet::empty())
+    );
+    assert_eq!(
+        db.execute("alter table orders add column note String"),
+        Ok(ResultSet::empty())
+    );
+    assert_eq!(
+        db.execute("alter table orders drop column amount"),
+        Ok(ResultSet::empty())
+    );
+    assert_eq!(
+        db.execute("alter table orders rename column userId to user_id"),
+        Ok(ResultSet::empty())
+    );
+    assert_eq!(
+        db.execute("insert into orders (id, user_id) values (4, '103')"),
+        Ok(ResultSet::affected_rows(1))
+    );
+    let schema = RelationSchema::new(vec![
+        FieldInfo::new("id".to_owned(), DataType::Int32),
+        FieldInfo::new("user_id".to_owned(), DataType::String),
+        FieldInfo::new("coupon".to_owned(), DataType::String),
+        FieldInfo::new("note".to_owned(), DataType::String),
+    ]);
+    let row = |id, user_id: &str| {
+        Row::new(vec![
+            Literal::Int32(id),
+            Literal::String(user_id.to_owned()),
+            Literal::String("NONE".to_owned()),
+            Literal::Null,
+        ])
+    };
+    assert_eq!(
+        db.execute("select * from orders order by id"),
+        Ok(ResultSet::new(
+            schema,
+            vec![row(1, "101"), row(2, "101"), row(3, "102"), row(4, "103")]
+        ))
+    );
+
+    assert_eq!(
+        db.execute("alter table orders add column id int"),
+        Err(DBError::ParserError(
+            "column id is defined more than once".to_owned()
+        ))
+    );
+    assert_eq!(
+        db.execute("alter table orders rename column note to coupon"),
+        Err(DBError::ParserError(
+            "column coupon is defined more than once".to_owned()
+        ))
+    );
+    assert_eq!(
+        db.execute("alter table orders drop column amount"),
+        Err(DBError::ParserError("column amount not found".to_owned()))
+    );
+    assert_eq!(
+        db.execute("create table single (id int)"),
+        Ok(ResultSet::empty())
+    );
+    assert_eq!(
+        db.execute("alter table single drop column id"),
+        Err(DBError::ParserError(
+            "table must have at least one column".to_owned()
+        ))
+    );
+}
+
+#[test]
+fn rename_tables() {
+    let db = CrackDB::new();
+    create_orders(&db);
+    assert_eq!(
+        db.execute("create table users (id int)"),
+        Ok(ResultSet::empty())
+    );
+    assert_eq!(
+        db.execute("alter table orders rename to users"),
+        Err(DBError::TableAlreadyExists("users".to_owned()))
+    );
+    assert_eq!(
+        db.execute("alter table orders rename to orders_2023"),
+        Ok(ResultSet::empty())
+    );
+    assert_eq!(
+        db.execute("select id from orders"),
+        Err(DBError::TableNotFound("orders".to_owned()))
+    );
+    let schema = RelationSchema::new(vec![FieldInfo::new(
+        "count(*)".to_owned(),
+        DataType::UInt64,
+    )]);
+    assert_eq!(
+        db.execute("select count(*) from orders_2023"),
+        Ok(ResultSet::new(
+            schema,
+            vec![Row::new(vec![Literal::UInt64(3)])]
+        ))
+    );
+}
+
+#[test]
+fn column_constraints() {
+    let db = CrackDB::new();
+    assert_eq!(
+        db.execute("create table users (id int primary key, email String unique, age int not null check (age >= 0), tier String default 'basic')"),
+        Ok(ResultSet::empty())
+    );
+    assert_eq!(
+        db.execute("insert into users (id, email, age) values (1, 'a@x.com', 30), (2, null, 20), (3, null, 25)"),
+        Ok(ResultSet::affected_rows(3))
+    );
+
+    assert_eq!(
+        db.execute("insert into users values (null, 'b@x.com', 40, 'vip')"),
+        Err(DBError::NotNullViolation("users_id_not_null".to_owned()))
+    );
+    assert_eq!(
+        db.execute("insert into users (id, email) values (4, 'b@x.com')"),
+        Err(DBError::NotNullViolation("users_age_not_null".to_owned()))
+    );
+    assert_eq!(
+        db.execute("insert into users values (1, 'b@x.com', 40, 'vip')"),
+        Err(DBError::UniqueViolation("users_pkey".to_owned()))
+    );
+    // duplicates within the inserted rows are violations too, and nothing is inserted
+    assert_eq!(
+        db.execute("insert into users values (4, 'c@x.com', 40, 'vip'), (5, 'c@x.com', 41, 'vip')"),
+        Err(DBError::UniqueViolation("users_email_key".to_owned()))
+    );
+    assert_eq!(
+        db.execute("insert into users values (4, 'b@x.com', -1, 'vip')"),
+        Err(DBError::CheckViolation("users_age_check".to_owned()))
+    );
+
+    assert_eq!(
+        db.execute("update users set email = 'a@x.com' where id = 2"),
+        Err(DBError::UniqueViolation("users_email_key".to_owned()))
+    );
+    assert_eq!(
+        db.execute("update users set age = age - 25 where id > 1"),
+        Err(DBError::CheckViolation("users_age_check".to_owned()))
+    );
+    // keys are checked against the updated rows only
+    assert_eq!(
+        db.execute("update users set id = id + 1"),
+        Ok(ResultSet::affected_rows(3))
+    );
+    assert_eq!(
+        db.execute("delete from users where id = 4"),
+        Ok(ResultSet::affected_rows(1))
+    );
+    assert_eq!(
+        db.execute("insert into users (id, age) values (4, 50)"),
+        Ok(ResultSet::affected_rows(1))
+    );
+    // a failed update keeps the keys of the rows, while updated keys are freed
+    assert_eq!(
+        db.execute("update users set email = 'b@x.com', age = age - 100 where id = 2"),
+        Err(DBError::CheckViolation("users_age_check".to_owned()))
+    );
+    assert_eq!(
+        db.execute("insert into users (id, email, age) values (10, 'a@x.com', 1)"),
+        Err(DBError::UniqueViolation("users_email_key".to_owned()))
+    );
+    assert_eq!(
+        db.execute("update users set email = 'b@x.com' where id = 2"),
+        Ok(ResultSet::affected_rows(1))
+    );
+    assert_eq!(
+        db.execute("insert into users (id, email, age) values (10, 'a@x.com', 1)"),
+        Ok(ResultSet::affected_rows(1))
+    );
+    assert_eq!(
+        db.execute("delete from users where id = 10"),
+        Ok(ResultSet::affected_rows(1))
+    );
+
+    let schema = RelationSchema::new(vec![
+        FieldInfo::new("id".to_owned(), DataType::Int32),
+        FieldInfo::new("age".to_owned(), DataType::Int32),
+        FieldInfo::new("tier".to_owned(), DataType::String),
+    ]);
+    let expected_results = ResultSet::new(
+        schema,
+        vec![
+            Row::new(vec![
+                Literal::Int32(2),
+                Literal::Int32(30),
+                Literal::String("basic".to_owned()),
+            ]),
+            Row::new(vec![
+                Literal::Int32(3),
+                Literal::Int32(20),
+                Literal::String("basic".to_owned()),
+            ]),
+            Row::new(vec![
+                Literal::Int32(4),
+                Literal::Int32(50),
+                Literal::String("basic".to_owned()),
+            ]),
+        ],
+    );
+    assert_eq!(
+        db.execute("select id, age, tier from users order by id"),
+        Ok(expected_results)
+    );
+}
+
+#[test]
+fn table_constraints() {
+    let db = CrackDB::new();
+    assert_eq!(
+        db.execute("create table items (orderId int, line int, qty int, constraint items_pk primary key (orderId, line), check (qty > 0))"),
+        Ok(ResultSet::empty())
+    );
+    assert_eq!(
+        db.execute("insert into items values (1, 1, 2), (1, 2, 1), (2, 1, 5)"),
+        Ok(ResultSet::affected_rows(3))
+    );
+    assert_eq!(
+        db.execute("insert into items values (1, 2, 3)"),
+        Err(DBError::UniqueViolation("items_pk".to_owned()))
+    );
+    assert_eq!(
+        db.execute("insert into items values (1, null, 3)"),
+        Err(DBError::NotNullViolation("items_line_not_null".to_owned()))
+    );
+    assert_eq!(
+        db.execute("insert into items values (3, 1, 0)"),
+        Err(DBError::CheckViolation("items_check".to_owned()))
+    );
+
+    assert!(matches!(
+        db.execute("alter table items drop column qty"),
+        Err(DBError::ParserError(_))
+    ));
+    assert_eq!(
+        db.execute("alter table items rename column qty to quantity"),
+        Ok(ResultSet::empty())
+    );
+    assert_eq!(
+        db.execute("insert into items values (3, 1, -1)"),
+        Err(DBError::CheckViolation("items_check".to_owned()))
+    );
+
+    assert!(matches!(
+        db.execute("create table t (a int primary key, b int primary key)"),
+        Err(DBError::ParserError(_))
+    ));
+    assert!(matches!(
+        db.execute("create table t (a int, unique (b))"),
+        Err(DBError::ParserError(_))
+    ));
+}
@@ -0,0 +1,91 @@
+use crackdb::{expressions::Literal, CrackDB, DBError, ResultSet};
+
+fn create_orders(db: &CrackDB) {
+    assert_eq!(
+        db.execute("create table orders (id int, amount double, userId String)"),
+        Ok(ResultSet::empty())
+    );
+    assert_eq!(
+        db.execute("insert into orders values (1, 30.0, '101'), (2, 26.0, '101'), (3, 42.0, '102')"),
+        Ok(ResultSet::affected_rows(3))
+    );
+}
+
+/// (plan_type, plan) of every row
+fn plan_lines(rs: ResultSet) -> Vec<(String, String)> {
+    rs.rows
+        .iter()
+        .map(|row| match (row.get_field(0), row.get_field(1)) {
+            (Ok(Literal::String(plan_type)), Ok(Literal::String(plan))) => {
+                (plan_type, plan)
+            }
+            fields => panic!("unexpected fields: {fields:?}"),
+        })
+        .collect()
+}
+
+#[test]
+fn explain() {
+    let db = CrackDB::new();
+    create_orders(&db);
+    let rs = db
+        .execute(
+            "explain select userId, sum(amount) from orders where id > 1 group by userId",
+        )
+        .unwrap();
+    let lines = plan_lines(rs);
+    let expected = vec![
+        ("logical_plan", "Projection: userId, sum(amount)"),
+        (
+            "logical_plan",
+            "  Aggregator: groupings=[userId], aggregators=[sum(amount)]",
+        ),
+        ("logical_plan", "    Filter: id_>_1"),
+        ("logical_plan", "      Scan: main.orders"),
+        ("physical_plan", "Projection: userId, sum(amount)"),
+        (
+            "physical_plan",
+            "  HashAggregator: groupings=[userId], aggregators=[sum(amount)]",
+        ),
+        ("physical_plan", "    Filter: id_>_1"),
+        ("physical_plan", "      InMemTableScan: 3 rows"),
+    ];
+    let expected = expected
+        .into_iter()
+        .map(|(plan_type, plan)| (plan_type.to_owned(), plan.to_owned()))
+        .collect::<Vec<_>>();
+    assert_eq!(lines, expected);
+
+    assert!(matches!(
+        db.execute("explain delete from orders"),
+        Err(DBError::ParserError(_))
+    ));
+}
+
+#[test]
+fn explain_analyze() {
+    let db = CrackDB::new();
+    create_orders(&db);
+    let rs = db
+        .execute("explain analyze select id from orders where amount > 28.0 order by id limit 1")
+        .unwrap();
+    let physical_lines = plan_lines(rs)
+        .into_iter()
+        .filter(|(plan_type, _)| plan_type == "physical_plan")
+        .map(|(_, plan)| plan)
+        .collect::<Vec<_>>();
+    let expected_prefixes = [
+        "Limit: offset=0, limit=1 (rows=1, elapsed=",
+        "  Sort: id ASC (rows=2, elapsed=",
+        "    Projection: id (rows=2, elapsed=",
+        "      Filter: amount_>_28 (rows=2, elapsed=",
+        "        InMemTableScan: 3 rows (rows=3, elapsed=",
+    ];
+    assert_eq!(physical_lines.len(), expected_prefixes.len());
+    for (line, prefix) in physical_lines.iter().zip(expected_prefixes) {
+        assert!(
+            line.starts_with(prefix),
+            "{line} should start with {prefix}"
+        );
+    }
+}
@@ -0,0 +1,125 @@
+use crackdb::{
+    data_types::DataType,
+    expressions::Literal,
+    row::Row,
+    tables::{FieldInfo, RelationSchema},
+    CrackDB, DBError, ResultSet,
+};
+
+fn string_rows(names: Vec<&str>, rows: Vec<Vec<&str>>) -> ResultSet {
+    let schema = RelationSchema::new(
+        names
+            .into_iter()
+            .map(|name| FieldInfo::new(name.to_owned(), DataType::String))
+            .collect(),
+    );
+    let rows = rows
+        .into_iter()
+        .map(|row| {
+            Row::new(
+                row.into_iter()
+                    .map(|v| Literal::String(v.to_owned()))
+                    .collect(),
+            )
+        })
+        .collect();
+    ResultSet::new(schema, rows)
+}
+
+fn create_tables(db: &CrackDB) {
+    assert_eq!(
+        db.execute(
+            "create table orders (id int primary key, amount double, userId String)"
+        ),
+        Ok(ResultSet::empty())
+    );
+    assert_eq!(
+        db.execute(
+            "create view big_orders as select id, amount from orders where amount > 30.0"
+        ),
+        Ok(ResultSet::empty())
+    );
+    assert_eq!(
+        db.execute(
+            "create materialized view order_count as select count(*) as cnt from orders"
+        ),
+        Ok(ResultSet::empty())
+    );
+}
+
+#[test]
+fn show_and_describe() {
+    let db = CrackDB::new();
+    assert_eq!(
+        db.execute("show tables"),
+        Ok(string_rows(vec!["name"], vec![]))
+    );
+    create_tables(&db);
+    assert_eq!(
+        db.execute("show tables"),
+        Ok(string_rows(
+            vec!["name"],
+            vec![vec!["big_orders"], vec!["order_count"], vec!["orders"]]
+        ))
+    );
+
+    let orders_columns = || {
+        string_rows(
+            vec!["name", "data_type"],
+            vec![
+                vec!["id", "Int32"],
+                vec!["amount", "Float64"],
+                vec!["userId", "String"],
+            ],
+        )
+    };
+    assert_eq!(db.execute("describe orders"), Ok(orders_columns()));
+    assert_eq!(db.execute("show columns from orders"), Ok(orders_columns()));
+    assert_eq!(
+        db.execute("describe big_orders"),
+        Ok(string_rows(
+            vec!["name", "data_type"],
+            vec![vec!["id", "Int32"], vec!["amount", "Float64"]]
+        ))
+    );
+    assert_eq!(
+        db.execute("describe missing"),
+        Err(DBError::TableNotFound("missing".to_owned()))
+    );
+}
+
+#[test]
+fn information_schema() {
+    let db = CrackDB::new();
+    create_tables(&db);
+    assert_eq!(
+        db.execute("select table_name, table_type from information_schema.tables where table_name < 'orders'"),
+        Ok(string_rows(
+            vec!["table_name", "table_type"],
+            vec![
+                vec!["big_orders", "VIEW"],
+                vec!["order_count", "MATERIALIZED VIEW"]
+            ]
+        ))
+    );
+    assert_eq!(
+        db.execute("select column_name, data_type, is_nullable from information_schema.columns where table_name = 'orders' and ordinal_position < 3"),
+        Ok(string_rows(
+            vec!["column_name", "data_type", "is_nullable"],
+            vec![vec!["id", "Int32", "NO"], vec!["amount", "Float64", "YES"]]
+        ))
+    );
+
+    // the catalog is read when information_schema is queried
+    assert_eq!(db.execute("drop view big_orders"), Ok(ResultSet::empty()));
+    assert_eq!(
+        db.execute("select count(*) as cnt from information_schema.columns"),
+        Ok(ResultSet::new(
+            RelationSchema::new(vec![FieldInfo::new("cnt".to_owned(), DataType::UInt64)]),
+            vec![Row::new(vec![Literal::UInt64(4)])]
+        ))
+    );
+    assert!(db
+        .execute("insert into information_schema.tables values ('t', 'VIEW')")
+        .is_err());
+}
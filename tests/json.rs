@@ -0,0 +1,181 @@
+use std::io::Write;
+
+use crackdb::{
+    data_types::DataType,
+    expressions::Literal,
+    row::Row,
+    tables::{
+        json::{JsonOptions, JsonTable},
+        FieldInfo, RelationSchema,
+    },
+    CrackDB, DBError, ResultSet,
+};
+use flate2::write::GzEncoder;
+
+mod common;
+use common::temp_path;
+
+#[test]
+fn query_all_from_ndjson() {
+    let db = CrackDB::new();
+    let schema = RelationSchema::new(vec![
+        FieldInfo::new("id".to_owned(), DataType::Int64),
+        FieldInfo::new("name".to_owned(), DataType::String),
+        FieldInfo::new("score".to_owned(), DataType::Float64),
+        FieldInfo::new("address.city".to_owned(), DataType::String),
+        FieldInfo::new("address.zip".to_owned(), DataType::String),
+        FieldInfo::new(
+            "tags".to_owned(),
+            DataType::List(Box::new(DataType::String)),
+        ),
+        FieldInfo::new("active".to_owned(), DataType::Boolean),
+    ]);
+    let string = |v: &str| Literal::String(v.to_owned());
+    let expected_results = ResultSet::new(
+        schema,
+        vec![
+            Row::new(vec![
+                Literal::Int64(1),
+                string("alice"),
+                Literal::Float64(3.0),
+                string("Paris"),
+                string("75001"),
+                Literal::List(vec![string("a"), string("b")]),
+                Literal::Null,
+            ]),
+            Row::new(vec![
+                Literal::Int64(2),
+                string("bob"),
+                Literal::Float64(4.5),
+                string("Berlin"),
+                Literal::Null,
+                Literal::Null,
+                Literal::Bool(true),
+            ]),
+            Row::new(vec![
+                Literal::Int64(3),
+                string("carol"),
+                Literal::Null,
+                string("Paris"),
+                // values of conflicting types are read as json text
+                string("10115"),
+                Literal::List(vec![]),
+                Literal::Null,
+            ]),
+        ],
+    );
+    assert_eq!(
+        db.execute("select * from 'tests/assets/users.ndjson'"),
+        Ok(expected_results)
+    );
+}
+
+#[test]
+fn query_nested_fields_from_json() {
+    let db = CrackDB::new();
+    let schema = RelationSchema::new(vec![
+        FieldInfo::new("name".to_owned(), DataType::String),
+        FieldInfo::new("address.city".to_owned(), DataType::String),
+    ]);
+    let expected_results = ResultSet::new(
+        schema,
+        vec![
+            Row::new(vec![
+                Literal::String("alice".to_owned()),
+                Literal::String("Paris".to_owned()),
+            ]),
+            Row::new(vec![
+                Literal::String("carol".to_owned()),
+                Literal::String("Paris".to_owned()),
+            ]),
+        ],
+    );
+    assert_eq!(
+        db.execute("select name, address.city from 'tests/assets/users.json' where address.city = 'Paris'"),
+        Ok(expected_results)
+    );
+}
+
+#[test]
+fn infer_schema_from_sample() {
+    let db = CrackDB::new();
+    let options = JsonOptions {
+        sample_size: Some(1),
+    };
+    let table =
+        JsonTable::with_options("tests/assets/users.ndjson".to_owned(), options).unwrap();
+    assert_eq!(db.register_table("users", Box::new(table)), Ok(()));
+    // fields absent from the sample are not in the schema
+    let columns = db.execute("describe users").unwrap();
+    let names = columns
+        .rows
+        .iter()
+        .map(|row| row.get_field(0).unwrap().to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        vec!["id", "name", "score", "address.city", "address.zip", "tags"]
+    );
+    // values beyond the sample must match the inferred types
+    assert_eq!(
+        db.execute("select id, score from users"),
+        Err(DBError::StorageEngine(
+            "json record 2: field score: json value 4.5 cannot be read as Int64"
+                .to_owned()
+        ))
+    );
+}
+
+#[test]
+fn sample_size_of_sql() {
+    let db = CrackDB::new();
+    // score is inferred as Int64 from the first record only
+    let sample_error = || {
+        Err(DBError::StorageEngine(
+            "json record 2: field score: json value 4.5 cannot be read as Int64"
+                .to_owned(),
+        ))
+    };
+    assert_eq!(
+        db.execute("select id, score from read_json('tests/assets/users.ndjson', sample_size => 1)"),
+        sample_error()
+    );
+    assert_eq!(
+        db.execute("select id, score from read_json('tests/assets/users.ndjson', sample_size => 'all')"),
+        db.execute("select id, score from 'tests/assets/users.ndjson'")
+    );
+
+    assert_eq!(
+        db.execute("create external table users stored as json location 'tests/assets/users.ndjson' options (sample_size 1)"),
+        Ok(ResultSet::empty())
+    );
+    assert_eq!(db.execute("select id, score from users"), sample_error());
+    assert_eq!(
+        db.execute(
+            "select * from read_json('tests/assets/users.ndjson', header => true)"
+        ),
+        Err(DBError::ParserError(
+            "json option header 'true' is not supported".to_owned()
+        ))
+    );
+}
+
+#[test]
+fn query_from_gzip_ndjson() {
+    let db = CrackDB::new();
+    let path = temp_path("users.ndjson.gz");
+    let mut encoder = GzEncoder::new(vec![], flate2::Compression::default());
+    encoder
+        .write_all(&std::fs::read("tests/assets/users.ndjson").unwrap())
+        .unwrap();
+    std::fs::write(&path, encoder.finish().unwrap()).unwrap();
+    let rs = db
+        .execute(&format!("select * from '{}'", path.display()))
+        .unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(
+        rs,
+        db.execute("select * from 'tests/assets/users.ndjson'")
+            .unwrap()
+    );
+}
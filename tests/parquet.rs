@@ -0,0 +1,95 @@
+use crackdb::{data_types::DataType, expressions::Literal, row::Row, CrackDB};
+
+const PRODUCTS: &str = "'tests/assets/products.parquet'";
+
+#[test]
+fn test_select_parquet_file() {
+    let db = CrackDB::new();
+    let rs = db
+        .execute(&format!("select * from {PRODUCTS} where id <= 2"))
+        .unwrap();
+    let fields = rs
+        .schema
+        .get_fields()
+        .iter()
+        .map(|f| (f.name().to_owned(), f.data_type().clone()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        fields,
+        vec![
+            ("id".to_owned(), DataType::Int32),
+            ("name".to_owned(), DataType::String),
+            ("price".to_owned(), DataType::Float64),
+            ("created_at".to_owned(), DataType::DateTime),
+            ("rating".to_owned(), DataType::Float64),
+        ]
+    );
+    assert_eq!(
+        rs.rows,
+        vec![
+            Row::new(vec![
+                Literal::Int32(1),
+                Literal::String("apple".to_owned()),
+                Literal::Float64(1.25),
+                Literal::DateTime("2024-01-01 00:00:00".to_owned()),
+                Literal::Float64(4.5),
+            ]),
+            Row::new(vec![
+                Literal::Int32(2),
+                Literal::String("banana".to_owned()),
+                Literal::Float64(2.5),
+                Literal::DateTime("2024-01-02 00:00:00".to_owned()),
+                Literal::Null,
+            ]),
+        ]
+    );
+}
+
+#[test]
+fn test_parquet_projection_and_row_group_pruning() {
+    let db = CrackDB::new();
+    let rs = db
+        .execute(&format!(
+            "select name from {PRODUCTS} where id > 4 or name = 'cherry' order by name"
+        ))
+        .unwrap();
+    assert_eq!(
+        rs.rows,
+        vec![
+            Row::new(vec![Literal::String("cherry".to_owned())]),
+            Row::new(vec![Literal::String("elderberry".to_owned())]),
+            Row::new(vec![Literal::String("fig".to_owned())]),
+        ]
+    );
+
+    let plan = db
+        .execute(&format!(
+            "explain analyze select name from {PRODUCTS} where id > 4"
+        ))
+        .unwrap();
+    let scan = plan
+        .rows
+        .iter()
+        .filter_map(|row| match row.get_field(1) {
+            Ok(Literal::String(line)) if line.contains("ParquetScan") => Some(line),
+            _ => None,
+        })
+        .next()
+        .unwrap();
+    assert!(
+        scan.contains("columns: [id, name], row groups: 1 of 3"),
+        "{scan}"
+    );
+}
+
+#[test]
+fn test_aggregate_parquet_file() {
+    let db = CrackDB::new();
+    let rs = db
+        .execute(&format!("select count(*), max(price) from {PRODUCTS}"))
+        .unwrap();
+    assert_eq!(
+        rs.rows,
+        vec![Row::new(vec![Literal::UInt64(6), Literal::Float64(19.99)])]
+    );
+}
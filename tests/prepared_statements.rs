@@ -0,0 +1,138 @@
+use crackdb::{
+    data_types::DataType,
+    expressions::Literal,
+    row::Row,
+    tables::{FieldInfo, RelationSchema},
+    CrackDB, DBError, ResultSet,
+};
+
+fn create_orders(db: &CrackDB) {
+    assert_eq!(
+        db.execute("create table orders (id int, amount double, userId String)"),
+        Ok(ResultSet::empty())
+    );
+    assert_eq!(
+        db.execute("insert into orders values (1, 30.0, '101'), (2, 26.0, '101'), (3, 42.0, '102')"),
+        Ok(ResultSet::affected_rows(3))
+    );
+}
+
+fn ids(ids: Vec<i32>) -> ResultSet {
+    let schema =
+        RelationSchema::new(vec![FieldInfo::new("id".to_owned(), DataType::Int32)]);
+    let rows = ids
+        .into_iter()
+        .map(|id| Row::new(vec![Literal::Int32(id)]))
+        .collect();
+    ResultSet::new(schema, rows)
+}
+
+#[test]
+fn prepared_queries() {
+    let db = CrackDB::new();
+    create_orders(&db);
+
+    let mut stmt = db
+        .prepare("select id from orders where userId = $1 order by id")
+        .unwrap();
+    assert_eq!(stmt.num_parameters(), 1);
+    assert!(matches!(stmt.execute(), Err(DBError::ParserError(_))));
+    assert_eq!(stmt.bind(&[Literal::String("101".to_owned())]), Ok(()));
+    assert_eq!(stmt.execute(), Ok(ids(vec![1, 2])));
+    assert_eq!(stmt.bind(&[Literal::String("102".to_owned())]), Ok(()));
+    assert_eq!(stmt.execute(), Ok(ids(vec![3])));
+    assert!(matches!(stmt.bind(&[]), Err(DBError::ParserError(_))));
+
+    // `?` placeholders are numbered in order, and numbers are converted into the data
+    // types of the parameters
+    let mut stmt = db
+        .prepare("select id from orders where amount > ? and id < ? order by id")
+        .unwrap();
+    assert_eq!(stmt.num_parameters(), 2);
+    assert_eq!(stmt.bind(&[Literal::Int64(20), Literal::Int64(3)]), Ok(()));
+    assert_eq!(stmt.execute(), Ok(ids(vec![1, 2])));
+
+    assert!(matches!(
+        stmt.bind(&[Literal::String("abc".to_owned()), Literal::Int64(3)]),
+        Err(DBError::ParserError(_))
+    ));
+    assert!(matches!(
+        stmt.bind(&[Literal::Float64(20.0), Literal::Int64(i64::MAX)]),
+        Err(DBError::ParserError(_))
+    ));
+    assert!(matches!(
+        db.prepare("select id from orders where amount > ? and id < $2"),
+        Err(DBError::ParserError(_))
+    ));
+    assert!(matches!(
+        db.execute("select id from orders where id = $1"),
+        Err(DBError::InterpretingError(_))
+    ));
+
+    // the plan is not reused once the table is altered
+    assert_eq!(
+        db.execute("alter table orders add column coupon String"),
+        Ok(ResultSet::empty())
+    );
+    assert!(matches!(stmt.execute(), Err(DBError::ParserError(_))));
+}
+
+#[test]
+fn prepared_statements() {
+    let db = CrackDB::new();
+    create_orders(&db);
+
+    let mut insert = db
+        .prepare("insert into orders values ($1, $2, $3)")
+        .unwrap();
+    assert_eq!(insert.num_parameters(), 3);
+    for (id, amount) in [(4, 10.5), (5, 12.0)] {
+        let values = [
+            Literal::Int32(id),
+            Literal::Float64(amount),
+            Literal::String("it's 103".to_owned()),
+        ];
+        assert_eq!(insert.bind(&values), Ok(()));
+        assert_eq!(insert.execute(), Ok(ResultSet::affected_rows(1)));
+    }
+
+    // values are checked against the data types of the columns of the parameters
+    assert!(matches!(
+        insert.bind(&[
+            Literal::String("6".to_owned()),
+            Literal::Float64(1.0),
+            Literal::String("104".to_owned()),
+        ]),
+        Err(DBError::ParserError(_))
+    ));
+
+    let mut update = db
+        .prepare("update orders set amount = ? where id = ?")
+        .unwrap();
+    assert_eq!(
+        update.bind(&[Literal::Int64(20), Literal::Int64(3)]),
+        Ok(())
+    );
+    assert_eq!(update.execute(), Ok(ResultSet::affected_rows(1)));
+    assert!(matches!(
+        update.bind(&[Literal::Int64(20), Literal::Int64(i64::MAX)]),
+        Err(DBError::ParserError(_))
+    ));
+
+    let mut delete = db.prepare("delete from orders where id = ?").unwrap();
+    assert!(matches!(
+        delete.bind(&[Literal::String("4".to_owned())]),
+        Err(DBError::ParserError(_))
+    ));
+    assert_eq!(delete.bind(&[Literal::Int32(4)]), Ok(()));
+    assert_eq!(delete.execute(), Ok(ResultSet::affected_rows(1)));
+    assert_eq!(
+        db.execute("select id from orders where amount = 20 order by id"),
+        Ok(ids(vec![3]))
+    );
+
+    assert_eq!(
+        db.execute("select id from orders where userId = 'it''s 103'"),
+        Ok(ids(vec![5]))
+    );
+}
@@ -0,0 +1,234 @@
+use crackdb::{
+    data_types::DataType,
+    expressions::Literal,
+    row::Row,
+    tables::{FieldInfo, RelationSchema},
+    CrackDB, DBError, ResultSet,
+};
+
+fn ids(ids: Vec<i32>) -> ResultSet {
+    let schema =
+        RelationSchema::new(vec![FieldInfo::new("id".to_owned(), DataType::Int32)]);
+    let rows = ids
+        .into_iter()
+        .map(|id| Row::new(vec![Literal::Int32(id)]))
+        .collect();
+    ResultSet::new(schema, rows)
+}
+
+fn names(names: Vec<&str>) -> ResultSet {
+    let schema =
+        RelationSchema::new(vec![FieldInfo::new("name".to_owned(), DataType::String)]);
+    let rows = names
+        .into_iter()
+        .map(|name| Row::new(vec![Literal::String(name.to_owned())]))
+        .collect();
+    ResultSet::new(schema, rows)
+}
+
+#[test]
+fn qualified_names() {
+    let db = CrackDB::new();
+    assert_eq!(db.execute("create schema staging"), Ok(ResultSet::empty()));
+    assert_eq!(
+        db.execute("create schema staging"),
+        Err(DBError::SchemaAlreadyExists("staging".to_owned()))
+    );
+    assert_eq!(
+        db.execute("create schema if not exists staging"),
+        Ok(ResultSet::empty())
+    );
+    assert_eq!(
+        db.execute("create table staging.orders (id int)"),
+        Ok(ResultSet::empty())
+    );
+    assert_eq!(
+        db.execute("create table orders (id int)"),
+        Ok(ResultSet::empty())
+    );
+    assert_eq!(
+        db.execute("insert into staging.orders values (1), (2)"),
+        Ok(ResultSet::affected_rows(2))
+    );
+    assert_eq!(
+        db.execute("insert into main.orders values (3)"),
+        Ok(ResultSet::affected_rows(1))
+    );
+
+    assert_eq!(
+        db.execute("select id from staging.orders"),
+        Ok(ids(vec![1, 2]))
+    );
+    assert_eq!(
+        db.execute("select id from crackdb.staging.orders where id > 1"),
+        Ok(ids(vec![2]))
+    );
+    assert_eq!(db.execute("select id from orders"), Ok(ids(vec![3])));
+    assert_eq!(
+        db.execute("delete from staging.orders where id = 2"),
+        Ok(ResultSet::affected_rows(1))
+    );
+    assert_eq!(
+        db.execute("select id from staging.orders"),
+        Ok(ids(vec![1]))
+    );
+
+    assert_eq!(
+        db.execute("select id from reporting.orders"),
+        Err(DBError::SchemaNotFound("reporting".to_owned()))
+    );
+    assert_eq!(
+        db.execute("select id from other.staging.orders"),
+        Err(DBError::ParserError(
+            "catalog other does not exist".to_owned()
+        ))
+    );
+    assert!(db
+        .execute("create table information_schema.orders (id int)")
+        .is_err());
+}
+
+#[test]
+fn quoted_names() {
+    let db = CrackDB::new();
+    assert_eq!(
+        db.execute("create table \"daily.orders\" (id int)"),
+        Ok(ResultSet::empty())
+    );
+    assert_eq!(
+        db.execute("insert into \"daily.orders\" values (1), (2)"),
+        Ok(ResultSet::affected_rows(2))
+    );
+    assert_eq!(
+        db.execute("update main.\"daily.orders\" set id = 3 where id = 2"),
+        Ok(ResultSet::affected_rows(1))
+    );
+    assert_eq!(
+        db.execute("select id from crackdb.main.\"daily.orders\""),
+        Ok(ids(vec![1, 3]))
+    );
+    assert_eq!(
+        db.execute("select id from daily.orders"),
+        Err(DBError::SchemaNotFound("daily".to_owned()))
+    );
+    assert_eq!(db.execute("show tables"), Ok(names(vec!["daily.orders"])));
+
+    // names of information_schema are case insensitive
+    assert_eq!(
+        db.execute("select table_name from INFORMATION_SCHEMA.TABLES"),
+        Ok(ResultSet::new(
+            RelationSchema::new(vec![FieldInfo::new(
+                "table_name".to_owned(),
+                DataType::String
+            )]),
+            vec![Row::new(vec![Literal::String("daily.orders".to_owned())])]
+        ))
+    );
+
+    assert_eq!(
+        db.execute("alter table \"daily.orders\" rename to \"weekly.orders\""),
+        Ok(ResultSet::empty())
+    );
+    assert_eq!(
+        db.execute("delete from \"weekly.orders\" where id = 1"),
+        Ok(ResultSet::affected_rows(1))
+    );
+    assert_eq!(
+        db.execute("select id from \"weekly.orders\""),
+        Ok(ids(vec![3]))
+    );
+    assert_eq!(
+        db.execute("drop table \"weekly.orders\""),
+        Ok(ResultSet::empty())
+    );
+    assert_eq!(db.execute("show tables"), Ok(names(vec![])));
+}
+
+#[test]
+fn search_path() {
+    let db = CrackDB::new();
+    assert_eq!(db.execute("create schema staging"), Ok(ResultSet::empty()));
+    assert_eq!(
+        db.execute("create table staging.orders (id int)"),
+        Ok(ResultSet::empty())
+    );
+    assert_eq!(
+        db.execute("create table users (id int)"),
+        Ok(ResultSet::empty())
+    );
+    assert_eq!(
+        db.execute("select id from orders"),
+        Err(DBError::TableNotFound("orders".to_owned()))
+    );
+
+    // unqualified names are looked up in the schemas of the search path in order, and
+    // new tables are created in the first schema
+    assert_eq!(
+        db.execute("set search_path = staging, main"),
+        Ok(ResultSet::empty())
+    );
+    assert_eq!(db.execute("select id from orders"), Ok(ids(vec![])));
+    assert_eq!(db.execute("select id from users"), Ok(ids(vec![])));
+    assert_eq!(
+        db.execute("create table events (id int)"),
+        Ok(ResultSet::empty())
+    );
+    assert_eq!(
+        db.execute("show tables"),
+        Ok(names(vec!["events", "orders"]))
+    );
+    assert_eq!(
+        db.execute("show tables from main"),
+        Ok(names(vec!["users"]))
+    );
+    assert_eq!(
+        db.execute("select table_name from information_schema.tables where table_schema = 'staging'"),
+        Ok(ResultSet::new(
+            RelationSchema::new(vec![FieldInfo::new(
+                "table_name".to_owned(),
+                DataType::String
+            )]),
+            vec![
+                Row::new(vec![Literal::String("events".to_owned())]),
+                Row::new(vec![Literal::String("orders".to_owned())]),
+            ]
+        ))
+    );
+
+    // renamed tables stay in their schema
+    assert_eq!(
+        db.execute("alter table orders rename to staged_orders"),
+        Ok(ResultSet::empty())
+    );
+    assert_eq!(
+        db.execute("select id from staging.staged_orders"),
+        Ok(ids(vec![]))
+    );
+
+    assert_eq!(
+        db.execute("set search_path to 'reporting'"),
+        Err(DBError::SchemaNotFound("reporting".to_owned()))
+    );
+}
+
+#[test]
+fn views_of_search_path() {
+    let db = CrackDB::new();
+    db.execute_batch(
+        "create table t (id int); insert into t values (1); \
+         create view v as select id from t; create schema s;",
+    )
+    .unwrap();
+    // names of views are resolved when the views are created, not by the search path
+    db.execute("create view s.t as select id from main.v")
+        .unwrap();
+    db.execute("set search_path = s, main").unwrap();
+    assert_eq!(db.execute("select * from main.v"), Ok(ids(vec![1])));
+    assert_eq!(db.execute("select * from t"), Ok(ids(vec![1])));
+
+    db.execute("drop view s.t").unwrap();
+    db.execute("create table s.t (id int)").unwrap();
+    db.execute("insert into s.t values (2)").unwrap();
+    assert_eq!(db.execute("select * from main.v"), Ok(ids(vec![1])));
+    assert_eq!(db.execute("select * from t"), Ok(ids(vec![2])));
+}
@@ -0,0 +1,161 @@
+use crackdb::{
+    data_types::DataType,
+    expressions::Literal,
+    row::Row,
+    tables::{FieldInfo, RelationSchema},
+    CrackDB, DBError, ResultSet,
+};
+
+fn create_orders(db: &CrackDB) {
+    assert_eq!(
+        db.execute("create table orders (id int, amount double, userId String)"),
+        Ok(ResultSet::empty())
+    );
+    assert_eq!(
+        db.execute("insert into orders values (1, 30.0, '101'), (2, 26.0, '101'), (3, 42.0, '102')"),
+        Ok(ResultSet::affected_rows(3))
+    );
+}
+
+fn user_totals(totals: Vec<(&str, f64)>) -> ResultSet {
+    let schema = RelationSchema::new(vec![
+        FieldInfo::new("userId".to_owned(), DataType::String),
+        FieldInfo::new("total".to_owned(), DataType::Float64),
+    ]);
+    let rows = totals
+        .into_iter()
+        .map(|(user, total)| {
+            Row::new(vec![
+                Literal::String(user.to_owned()),
+                Literal::Float64(total),
+            ])
+        })
+        .collect();
+    ResultSet::new(schema, rows)
+}
+
+#[test]
+fn views() {
+    let db = CrackDB::new();
+    create_orders(&db);
+    assert_eq!(
+        db.execute("create view user_totals as select userId, sum(amount) as total from orders group by userId"),
+        Ok(ResultSet::empty())
+    );
+    assert_eq!(
+        db.execute("select * from user_totals where total > 50.0"),
+        Ok(user_totals(vec![("101", 56.0)]))
+    );
+
+    // views are recomputed on every reference
+    assert_eq!(
+        db.execute("insert into orders values (4, 20.0, '102')"),
+        Ok(ResultSet::affected_rows(1))
+    );
+    assert_eq!(
+        db.execute("select userId, total from user_totals order by userId"),
+        Ok(user_totals(vec![("101", 56.0), ("102", 62.0)]))
+    );
+
+    // views may reference other views
+    assert_eq!(
+        db.execute(
+            "create view big_users as select * from user_totals where total > 60.0"
+        ),
+        Ok(ResultSet::empty())
+    );
+    assert_eq!(
+        db.execute("select * from big_users"),
+        Ok(user_totals(vec![("102", 62.0)]))
+    );
+
+    assert_eq!(
+        db.execute(
+            "create view user_totals as select userId, amount as total from orders"
+        ),
+        Err(DBError::TableAlreadyExists("user_totals".to_owned()))
+    );
+    assert!(matches!(
+        db.execute("create or replace view user_totals as select * from big_users"),
+        Err(DBError::ParserError(_))
+    ));
+    assert!(matches!(
+        db.execute("create view broken as select missing from orders"),
+        Err(DBError::ParserError(_))
+    ));
+    assert_eq!(
+        db.execute("insert into user_totals values ('103', 1.0)"),
+        Err(DBError::TableNotFound("user_totals".to_owned()))
+    );
+
+    assert_eq!(
+        db.execute("drop table big_users"),
+        Err(DBError::TableNotFound("big_users".to_owned()))
+    );
+    assert_eq!(db.execute("drop view big_users"), Ok(ResultSet::empty()));
+    assert_eq!(
+        db.execute("select * from big_users"),
+        Err(DBError::TableNotFound("big_users".to_owned()))
+    );
+}
+
+#[test]
+fn materialized_views() {
+    let db = CrackDB::new();
+    create_orders(&db);
+    assert_eq!(
+        db.execute("create materialized view user_totals as select userId, sum(amount) as total from orders group by userId"),
+        Ok(ResultSet::empty())
+    );
+    assert_eq!(
+        db.execute("insert into orders values (4, 20.0, '102')"),
+        Ok(ResultSet::affected_rows(1))
+    );
+    assert_eq!(
+        db.execute("select * from user_totals order by userId"),
+        Ok(user_totals(vec![("101", 56.0), ("102", 42.0)]))
+    );
+
+    assert_eq!(
+        db.execute("refresh materialized view user_totals"),
+        Ok(ResultSet::empty())
+    );
+    assert_eq!(
+        db.execute("select * from user_totals order by userId"),
+        Ok(user_totals(vec![("101", 56.0), ("102", 62.0)]))
+    );
+
+    assert!(matches!(
+        db.execute("refresh materialized view orders"),
+        Err(DBError::ParserError(_))
+    ));
+    assert_eq!(db.execute("drop view user_totals"), Ok(ResultSet::empty()));
+    assert_eq!(
+        db.execute("select * from user_totals"),
+        Err(DBError::TableNotFound("user_totals".to_owned()))
+    );
+}
+
+#[test]
+fn recursive_views() {
+    let db = CrackDB::new();
+    create_orders(&db);
+    db.execute("create view a as select id from orders")
+        .unwrap();
+    db.execute("create view b as select id from a").unwrap();
+    let err = || DBError::ParserError("view main.a cannot reference itself".to_owned());
+    assert_eq!(
+        db.execute("create or replace view a as select id from b"),
+        Err(err())
+    );
+
+    // the views read by a view are the ones when it is created, whatever the search path
+    db.execute("create schema s").unwrap();
+    db.execute("create view s.c as select id from b").unwrap();
+    db.execute("set search_path = s, main").unwrap();
+    assert_eq!(
+        db.execute("create or replace view main.a as select id from c"),
+        Err(err())
+    );
+    assert_eq!(db.execute("select * from c").unwrap().rows.len(), 3);
+}